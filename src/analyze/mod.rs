@@ -2,12 +2,14 @@ pub mod settings;
 
 use crate::analyze::settings::AnalyzeSettings;
 use crate::app::AppResult;
+use crate::file::metadata::ImageMetadata;
+use crate::image::qoi::{self, QoiDecoder};
 use bytesize::ByteSize;
 use colored::{Color, Colorize};
 use exif::{Exif, Reader as ExifReader};
 use hex::ToHex;
 use image::io::Reader as ImageReader;
-use image::{DynamicImage, ImageFormat};
+use image::DynamicImage;
 use std::fmt::Write;
 use std::fs::{self, File, Metadata};
 use std::io::BufReader;
@@ -21,10 +23,11 @@ pub enum TimeInfo {
 
 /* Analyzer for image files */
 pub struct ImageAnalyzer<'a> {
-	format: Option<ImageFormat>,
+	format: Option<String>,
 	image: DynamicImage,
 	metadata: Metadata,
 	exif: Option<Exif>,
+	image_metadata: Option<ImageMetadata>,
 	settings: &'a AnalyzeSettings,
 }
 
@@ -37,16 +40,23 @@ impl<'a> ImageAnalyzer<'a> {
 	 */
 	pub fn new(settings: &'a AnalyzeSettings) -> AppResult<Self> {
 		debug!("{:?}", settings);
-		let reader = ImageReader::open(&settings.file)?.with_guessed_format()?;
 		Ok(Self {
-			format: reader.format(),
-			image: reader.decode()?,
+			format: if QoiDecoder::is_qoi_file(&settings.file) {
+				Some(String::from("QOI"))
+			} else {
+				ImageReader::open(&settings.file)?
+					.with_guessed_format()?
+					.format()
+					.map(|format| format!("{format:?}").to_uppercase())
+			},
+			image: qoi::read_image(&settings.file)?,
 			metadata: fs::metadata(&settings.file)?,
 			exif: ExifReader::new()
 				.read_from_container(&mut BufReader::new(File::open(
 					&settings.file,
 				)?))
 				.ok(),
+			image_metadata: ImageMetadata::read(&fs::read(&settings.file)?),
 			settings,
 		})
 	}
@@ -100,6 +110,22 @@ impl<'a> ImageAnalyzer<'a> {
 			.collect()
 	}
 
+	/**
+	 * Get the embedded capture information.
+	 *
+	 * @return data
+	 */
+	fn get_capture_data(&self) -> String {
+		let mut data = String::new();
+		if let Some(metadata) = &self.image_metadata {
+			data += "\nCapture Information\n";
+			for (key, value) in metadata.get_fields() {
+				let _ = writeln!(data, "  {key}: {value}");
+			}
+		}
+		data
+	}
+
 	/**
 	 * Get EXIF data from the image.
 	 *
@@ -148,7 +174,7 @@ impl<'a> ImageAnalyzer<'a> {
 			\n  Color Type: {}\
 			\n  Main Colors:\
 			\n   \u{2022} {}\
-			\n{}\n\
+			\n{}{}\n\
 			generated on {}\
 			",
 			env!("CARGO_PKG_NAME"),
@@ -165,13 +191,11 @@ impl<'a> ImageAnalyzer<'a> {
 				.unwrap_or_else(|| String::from("(?)")),
 			self.get_time_info(TimeInfo::Accessed)
 				.unwrap_or_else(|| String::from("(?)")),
-			self.format.map_or_else(
-				|| String::from("(?)"),
-				|f| format!("{f:?}").to_uppercase()
-			),
+			self.format.clone().unwrap_or_else(|| String::from("(?)")),
 			self.get_image_dimensions(),
 			format!("{:?}", self.image.color()).to_uppercase(),
 			self.get_dominant_colors().join("\n   \u{2022} "),
+			self.get_capture_data(),
 			self.get_exif_data(),
 			self.settings.time.now(),
 		)
@@ -267,7 +291,7 @@ mod tests {
 			}
 		}
 		assert_eq!(false, analyzer.metadata.permissions().readonly());
-		assert_eq!(Some(ImageFormat::Png), analyzer.format);
+		assert_eq!(Some(String::from("PNG")), analyzer.format);
 		assert_eq!(ColorType::Rgba8, analyzer.image.color());
 		assert_eq!("1x2", analyzer.get_image_dimensions());
 		assert_eq!(
@@ -275,6 +299,7 @@ mod tests {
 			analyzer.get_dominant_colors().join("-")
 		);
 		assert!(analyzer.exif.is_none());
+		assert!(analyzer.image_metadata.is_none());
 		assert_eq!(17, analyzer.get_colored_report().lines().count());
 		fs::remove_file(file_name).unwrap();
 	}
@@ -2,6 +2,7 @@ use crate::anim::settings::AnimSettings;
 use crate::anim::Frames;
 use crate::app::{AppError, AppResult};
 use crate::edit::ImageOps;
+use crate::image::Image;
 use image::Frame;
 use std::io::{self, Write};
 
@@ -58,17 +59,44 @@ impl<'a> AnimDecoder<'a> {
 	 * @param  frames
 	 * @return Frames (Result)
 	 */
-	pub fn update_frames(mut self, mut frames: Vec<Frame>) -> AppResult<Frames> {
+	pub fn update_frames(self, frames: Vec<Frame>) -> AppResult<Frames> {
+		let mut images = Vec::new();
+		let fps = self.process_frames(frames, |image, _| {
+			images.push(image);
+			Ok(())
+		})?;
+		Ok((images, fps))
+	}
+
+	/**
+	 * Update the frames and pass them to the given function one by one.
+	 *
+	 * @param  frames
+	 * @param  write
+	 * @return u32 (Result)
+	 */
+	pub fn process_frames<F>(
+		mut self,
+		mut frames: Vec<Frame>,
+		mut write: F,
+	) -> AppResult<u32>
+	where
+		F: FnMut(Image, u32) -> AppResult<()>,
+	{
 		let first_frame = frames.first().ok_or_else(|| {
 			AppError::FrameError(String::from("No frames found to process"))
 		})?;
-		self.imageops
-			.init(first_frame.clone().into_buffer().dimensions());
-		let fps = ((1e3 / first_frame.delay().numer_denom_ms().0 as f32)
-			* self.settings.speed) as u32;
+		let fps = match first_frame.delay().numer_denom_ms() {
+			(0, _) => self.settings.fps,
+			(numer, denom) => {
+				((1e3 * denom as f32 / numer as f32) * self.settings.speed) as u32
+			}
+		}
+		.max(1);
 		debug!("FPS: {:?}", fps);
 		let frames = Self::cut_duration(&mut frames, self.settings.cut, fps);
-		let mut images = Vec::new();
+		self.imageops
+			.init_frames(frames.iter().map(|frame| frame.buffer()));
 		for (i, frame) in frames.iter().enumerate() {
 			let percentage = ((i + 1) as f64 / frames.len() as f64) * 100.;
 			info!("Processing the frames... ({:.1}%)\r", percentage);
@@ -79,24 +107,32 @@ impl<'a> AnimDecoder<'a> {
 				frames.len()
 			);
 			io::stdout().flush()?;
-			images.push(
+			let buffer = frame.clone().into_buffer();
+			write(
 				self.imageops
-					.process(frame.clone().into_buffer())
+					.init(buffer.dimensions())
+					.process(buffer)
 					.get_image(),
-			);
+				fps,
+			)?;
 		}
 		info!("\n");
-		Ok((images, fps))
+		Ok(fps)
 	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::args::matches::ArgMatches;
+	use crate::args::Args;
+	use crate::edit::pipeline::{Operation, Pipeline};
 	use crate::edit::settings::EditSettings;
 	use crate::image::geometry::Geometry;
-	use image::{Delay, Frame, RgbaImage};
+	use image::{Delay, ExtendedColorType, Frame, Rgba, RgbaImage};
 	use pretty_assertions::assert_eq;
+	use std::fs;
+	use std::path::PathBuf;
 	#[test]
 	fn test_anim_decoder() {
 		let anim_settings = AnimSettings {
@@ -125,5 +161,60 @@ mod tests {
 		assert_eq!(2, frames.1);
 		assert_eq!(1, frames.0.len());
 		assert_eq!(Geometry::new(0, 0, 2, 2), frames.0[0].geometry);
+		let mut edit_settings = EditSettings::default();
+		edit_settings.color.normalize = true;
+		let get_frame = |values: [u8; 2]| {
+			Frame::new(RgbaImage::from_fn(2, 1, |x, _| {
+				let value = values[x as usize];
+				Rgba([value, value, value, 255])
+			}))
+		};
+		let frames =
+			AnimDecoder::new(edit_settings.get_imageops(), &AnimSettings::default())
+				.update_frames(vec![get_frame([50, 100]), get_frame([100, 150])])
+				.unwrap();
+		assert_eq!(
+			vec![vec![0, 128], vec![128, 255]],
+			frames
+				.0
+				.iter()
+				.map(|image| image.get_data(ExtendedColorType::L8))
+				.collect::<Vec<Vec<u8>>>()
+		);
+		let recipe = PathBuf::from("test_decoder.ops");
+		Pipeline::new(vec![Operation::Grayscale, Operation::Trim(0.)])
+			.save(&recipe)
+			.unwrap();
+		let args = Args::get_app().get_matches_from(vec![
+			"menyoki",
+			"edit",
+			"x",
+			"--recipe",
+			"test_decoder.ops",
+		]);
+		let edit_settings = EditSettings::from_args(&ArgMatches::new(&args));
+		fs::remove_file(recipe).unwrap();
+		let get_frame = |x: u32, y: u32| {
+			let mut image = RgbaImage::new(4, 4);
+			image.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+			Frame::new(image)
+		};
+		let frames =
+			AnimDecoder::new(edit_settings.get_imageops(), &AnimSettings::default())
+				.update_frames(vec![get_frame(1, 1), get_frame(2, 2)])
+				.unwrap();
+		for (image, (x, y)) in frames.0.iter().zip([(0, 0), (1, 1)]) {
+			assert_eq!(Geometry::new(0, 0, 2, 2), image.geometry);
+			let mut content = vec![false; 4];
+			content[y * 2 + x] = true;
+			assert_eq!(
+				content,
+				image
+					.get_data(ExtendedColorType::L8)
+					.iter()
+					.map(|value| *value != 0)
+					.collect::<Vec<bool>>()
+			);
+		}
 	}
 }
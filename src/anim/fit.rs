@@ -0,0 +1,248 @@
+use crate::anim::settings::AnimSettings;
+use crate::anim::Frames;
+use crate::app::{AppError, AppResult};
+use crate::edit::settings::EditSettings;
+use crate::file::format::FileFormat;
+use crate::image::settings::WebPSettings;
+use crate::image::Image;
+use bytesize::ByteSize;
+use image::{ExtendedColorType, RgbaImage};
+
+/* Lowest quality to use while searching */
+const MIN_QUALITY: u8 = 20;
+
+/* Lossy compression level to add on each step (GIF) */
+const LOSSY_STEP: u32 = 40;
+
+/* Highest lossy compression level to use while searching (GIF) */
+const MAX_LOSSY: u32 = 200;
+
+/* Lowest scaling ratio to use while searching */
+const MIN_RATIO: f32 = 0.1;
+
+/* Highest frame decimation step to use while searching */
+const MAX_STEP: usize = 8;
+
+/* Maximum number of encoding attempts */
+const MAX_ATTEMPTS: usize = 20;
+
+/* Encoder wrapper for fitting the output under a size limit */
+#[derive(Debug)]
+pub struct SizeFitter<'a> {
+	max_size: u64,
+	format: &'a FileFormat,
+	settings: &'a AnimSettings,
+	webp: WebPSettings,
+}
+
+impl<'a> SizeFitter<'a> {
+	/**
+	 * Create a new SizeFitter object.
+	 *
+	 * @param  max_size
+	 * @param  format
+	 * @param  settings
+	 * @param  webp
+	 * @return SizeFitter
+	 */
+	pub fn new(
+		max_size: u64,
+		format: &'a FileFormat,
+		settings: &'a AnimSettings,
+		webp: WebPSettings,
+	) -> Self {
+		Self {
+			max_size,
+			format,
+			settings,
+			webp,
+		}
+	}
+
+	/**
+	 * Check if the lossy compression level affects the output size.
+	 *
+	 * @return bool
+	 */
+	fn has_lossy(&self) -> bool {
+		*self.format == FileFormat::Gif && !self.settings.gifski.0
+	}
+
+	/**
+	 * Check if the quality setting affects the output size.
+	 *
+	 * @return bool
+	 */
+	fn has_quality(&self) -> bool {
+		match self.format {
+			FileFormat::Gif => self.settings.gifski.0,
+			FileFormat::AnimWebP => self.webp.quality.is_some(),
+			_ => false,
+		}
+	}
+
+	/**
+	 * Scale and decimate the frames.
+	 *
+	 * FPS is divided by the step and rounded, then the frames
+	 * are picked at that rate to keep the total duration.
+	 *
+	 * @param  images
+	 * @param  fps
+	 * @param  ratio
+	 * @param  step
+	 * @return Frames
+	 */
+	fn get_frames(images: &[Image], fps: u32, ratio: f32, step: usize) -> Frames {
+		let mut edit = EditSettings::default();
+		edit.image.ratio = ratio;
+		let fps = fps.max(1);
+		let step_fps = ((fps as f32 / step as f32).round() as u32).max(1);
+		let interval = fps as f32 / step_fps as f32;
+		let images = (0..)
+			.map(|i| (i as f32 * interval).round() as usize)
+			.map_while(|i| images.get(i))
+			.map(|image| {
+				let (width, height) = (image.geometry.width, image.geometry.height);
+				match RgbaImage::from_raw(
+					width,
+					height,
+					image.get_data(ExtendedColorType::Rgba8),
+				) {
+					Some(buffer) if ratio < 1. => edit
+						.get_imageops()
+						.init((width, height))
+						.process(buffer)
+						.get_image(),
+					_ => image.clone(),
+				}
+			})
+			.collect();
+		(images, step_fps)
+	}
+
+	/**
+	 * Encode the frames repeatedly until the output fits.
+	 *
+	 * @param  frames
+	 * @param  encode
+	 * @return Vector of u8 (Result)
+	 */
+	pub fn fit<Encode>(&self, frames: Frames, encode: Encode) -> AppResult<Vec<u8>>
+	where
+		Encode: Fn(Frames, &AnimSettings, &WebPSettings) -> AppResult<Vec<u8>>,
+	{
+		let (images, fps) = frames;
+		let mut settings = self.settings.clone();
+		let mut webp = self.webp;
+		let (mut ratio, mut step) = (1_f32, 1);
+		for attempt in 1..=MAX_ATTEMPTS {
+			let frames = Self::get_frames(&images, fps, ratio, step);
+			let (geometry, fps) = (
+				frames
+					.0
+					.first()
+					.map(|image| image.geometry)
+					.unwrap_or_default(),
+				frames.1,
+			);
+			let data = encode(frames, &settings, &webp)?;
+			let size = data.len() as u64;
+			debug!(
+				"Attempt {}: {} (quality: {}, lossy: {}, ratio: {:.2}, step: {})",
+				attempt,
+				ByteSize(size),
+				settings.quality,
+				settings.lossy,
+				ratio,
+				step
+			);
+			if size <= self.max_size {
+				info!(
+					"Fitted under {}: {}x{}, {} FPS{}",
+					ByteSize(self.max_size),
+					geometry.width,
+					geometry.height,
+					fps,
+					if self.has_quality() {
+						format!(", quality {}", settings.quality)
+					} else if self.has_lossy() {
+						format!(", lossy {}", settings.lossy)
+					} else {
+						String::new()
+					}
+				);
+				return Ok(data);
+			}
+			let factor = self.max_size as f32 / size as f32;
+			if self.has_lossy() && settings.lossy < MAX_LOSSY {
+				settings.lossy = (settings.lossy + LOSSY_STEP).min(MAX_LOSSY);
+			} else if self.has_quality() && settings.quality > MIN_QUALITY {
+				settings.quality = ((f32::from(settings.quality) * factor) as u8)
+					.min(settings.quality.saturating_sub(15))
+					.max(MIN_QUALITY);
+				webp.quality = webp.quality.map(|_| settings.quality);
+			} else if ratio > MIN_RATIO {
+				ratio = (ratio * factor.sqrt() * 0.95).max(MIN_RATIO);
+			} else if step < MAX_STEP {
+				step += 1;
+			} else {
+				break;
+			}
+		}
+		Err(AppError::FrameError(format!(
+			"Failed to fit the output under {}",
+			ByteSize(self.max_size)
+		)))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::image::geometry::Geometry;
+	use image::Rgba;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_size_fitter() -> AppResult<()> {
+		let geometry = Geometry::new(0, 0, 100, 100);
+		let images =
+			vec![Image::new(vec![Rgba([0; 4]); 100 * 100], false, geometry); 8];
+		let frames = SizeFitter::get_frames(&images, 20, 0.5, 2);
+		assert_eq!((4, 10), (frames.0.len(), frames.1));
+		assert_eq!(
+			(50, 50),
+			(frames.0[0].geometry.width, frames.0[0].geometry.height)
+		);
+		let frames = SizeFitter::get_frames(&vec![images[0].clone(); 30], 10, 1., 3);
+		assert_eq!((9, 3), (frames.0.len(), frames.1));
+		let settings = AnimSettings::default();
+		let webp = WebPSettings::default();
+		let fitter = SizeFitter::new(5000, &FileFormat::Gif, &settings, webp);
+		let data =
+			fitter.fit((images.clone(), 20), |(images, _), settings, _| {
+				let pixels =
+					images.len() * images[0].get_data(ExtendedColorType::L8).len();
+				assert_eq!(75, settings.quality);
+				Ok(vec![0; pixels * (250 - settings.lossy as usize) / 250])
+			})?;
+		assert!(data.len() <= 5000);
+		let settings = AnimSettings {
+			gifski: (true, false),
+			..AnimSettings::default()
+		};
+		let gifski = SizeFitter::new(5000, &FileFormat::Gif, &settings, webp);
+		let data =
+			gifski.fit((images.clone(), 20), |(images, _), settings, _| {
+				let pixels =
+					images.len() * images[0].get_data(ExtendedColorType::L8).len();
+				assert_eq!(0, settings.lossy);
+				Ok(vec![0; pixels * usize::from(settings.quality) / 100])
+			})?;
+		assert!(data.len() <= 5000);
+		assert!(fitter
+			.fit((images, 20), |_, _, _| Ok(vec![0; 5001]))
+			.is_err());
+		Ok(())
+	}
+}
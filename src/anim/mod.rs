@@ -1,8 +1,10 @@
 pub mod decoder;
+pub mod fit;
 pub mod settings;
 
 use crate::image::Image;
 use std::fmt;
+use std::str::FromStr;
 
 /* Images to encode and FPS value */
 pub type Frames = (Vec<Image>, u32);
@@ -12,6 +14,11 @@ pub type Frames = (Vec<Image>, u32);
 pub enum AnimFormat {
 	Gif,
 	Apng,
+	WebP,
+	Tiff,
+	Y4m,
+	Rgba,
+	Pdf,
 }
 
 /* Display implementation for user-facing output */
@@ -21,6 +28,23 @@ impl fmt::Display for AnimFormat {
 	}
 }
 
+/* Implementation for parsing AnimFormat from a string */
+impl FromStr for AnimFormat {
+	type Err = &'static str;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"gif" => Ok(Self::Gif),
+			"apng" => Ok(Self::Apng),
+			"webp" => Ok(Self::WebP),
+			"tiff" => Ok(Self::Tiff),
+			"y4m" => Ok(Self::Y4m),
+			"rgba" => Ok(Self::Rgba),
+			"pdf" => Ok(Self::Pdf),
+			_ => Err("Unrecognized animation format"),
+		}
+	}
+}
+
 /* Animation related subcommands */
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum AnimMode {
@@ -50,6 +74,15 @@ impl AnimMode {
 		}
 	}
 
+	/* Check if the animation mode has one of the given formats.
+	 *
+	 * @param  formats
+	 * @return bool
+	 */
+	pub fn has_any_format(&self, formats: &[AnimFormat]) -> bool {
+		formats.iter().any(|format| self.has_format(*format))
+	}
+
 	/* Get the mode description.
 	 *
 	 * @return str
@@ -59,6 +92,11 @@ impl AnimMode {
 			Self::Record(format) | Self::Edit(format) => match format {
 				AnimFormat::Gif => "Use the GIF encoder",
 				AnimFormat::Apng => "Use the APNG encoder",
+				AnimFormat::WebP => "Use the WebP encoder",
+				AnimFormat::Tiff => "Use the TIFF encoder",
+				AnimFormat::Y4m => "Use the Y4M encoder",
+				AnimFormat::Rgba => "Use the raw RGBA encoder",
+				AnimFormat::Pdf => "Use the PDF encoder",
 			},
 			Self::Make => "Make an animation from frames",
 		}
@@ -88,6 +126,8 @@ mod tests {
 	fn test_anim_mode() {
 		let anim_format = AnimFormat::Apng;
 		assert_eq!("Apng", anim_format.to_string().as_str());
+		assert_eq!(Ok(AnimFormat::WebP), AnimFormat::from_str("webp"));
+		assert!(AnimFormat::from_str("png").is_err());
 		let anim_mode = AnimMode::Record(anim_format);
 		assert!(!anim_mode.is_edit());
 		assert!(anim_mode.has_format(AnimFormat::Apng));
@@ -98,6 +138,12 @@ mod tests {
 		assert!(anim_mode.has_format(AnimFormat::Gif));
 		assert_eq!("Use the GIF encoder", anim_mode.get_description());
 		assert_eq!("gif", anim_mode.to_string().as_str());
+		let anim_mode = AnimMode::Record(AnimFormat::WebP);
+		assert!(anim_mode.has_format(AnimFormat::WebP));
+		assert_eq!("Use the WebP encoder", anim_mode.get_description());
+		assert_eq!("webp", anim_mode.to_string().as_str());
+		assert!(anim_mode.has_any_format(&[AnimFormat::Y4m, AnimFormat::WebP]));
+		assert!(!anim_mode.has_any_format(&[AnimFormat::Gif, AnimFormat::Rgba]));
 		let anim_mode = AnimMode::Make;
 		assert!(!anim_mode.is_edit());
 		assert!(!anim_mode.has_format(AnimFormat::Apng));
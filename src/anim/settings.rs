@@ -6,11 +6,13 @@ use std::fs;
 use std::path::PathBuf;
 
 /* Animation and frame settings */
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct AnimSettings {
 	pub fps: u32,
 	pub repeat: i32,
 	pub quality: u8,
+	pub lossy: u32,
+	pub keyframe: u32,
 	pub speed: f32,
 	pub cut: (f32, f32),
 	pub frames: Vec<PathBuf>,
@@ -24,6 +26,8 @@ impl Default for AnimSettings {
 			fps: 20,
 			repeat: -1,
 			quality: 75,
+			lossy: 0,
+			keyframe: 0,
 			speed: 1.,
 			cut: (0., 0.),
 			frames: Vec::new(),
@@ -39,16 +43,21 @@ impl AnimSettings {
 	 * @param  fps
 	 * @param  repeat
 	 * @param  quality
+	 * @param  lossy
+	 * @param  keyframe
 	 * @param  speed
 	 * @param  cut
 	 * @param  frames
 	 * @param  gifski
 	 * @return AnimSettings
 	 */
+	#[allow(clippy::too_many_arguments)]
 	pub fn new(
 		fps: u32,
 		repeat: i32,
 		quality: u8,
+		lossy: u32,
+		keyframe: u32,
 		speed: f32,
 		cut: (f32, f32),
 		frames: Vec<PathBuf>,
@@ -58,6 +67,8 @@ impl AnimSettings {
 			fps,
 			repeat,
 			quality,
+			lossy,
+			keyframe,
 			speed,
 			cut,
 			frames,
@@ -73,7 +84,7 @@ impl AnimSettings {
 	 * @return AnimSettings
 	 */
 	pub fn from_args(matches: &ArgMatches<'_>, file_format: &FileFormat) -> Self {
-		let format = &file_format.to_string().to_lowercase();
+		let format = &file_format.as_extension();
 		Self::from_parser(ArgParser::from_subcommand(
 			matches,
 			if matches.is_present("make") {
@@ -99,6 +110,8 @@ impl AnimSettings {
 				},
 				parser.parse("repeat", Self::default().repeat) - 1,
 				parser.parse("quality", Self::default().quality),
+				parser.parse("lossy", Self::default().lossy),
+				parser.parse("keyframe", Self::default().keyframe),
 				parser.parse("speed", Self::default().speed),
 				(
 					parser.parse("cut-beginning", Self::default().cut.0) * 1000.,
@@ -254,6 +267,12 @@ mod tests {
 			.arg(Arg::with_name("fps").long("fps").takes_value(true))
 			.arg(Arg::with_name("repeat").long("repeat").takes_value(true))
 			.arg(Arg::with_name("quality").long("quality").takes_value(true))
+			.arg(Arg::with_name("lossy").long("lossy").takes_value(true))
+			.arg(
+				Arg::with_name("keyframe")
+					.long("keyframe")
+					.takes_value(true),
+			)
 			.arg(Arg::with_name("gifski").long("gifski"))
 			.arg(Arg::with_name("fast").long("fast"))
 			.arg(Arg::with_name("speed").long("speed").takes_value(true))
@@ -271,6 +290,10 @@ mod tests {
 				"5",
 				"--quality",
 				"10",
+				"--lossy",
+				"40",
+				"--keyframe",
+				"9",
 				"--gifski",
 				"--fast",
 				"--speed",
@@ -284,6 +307,8 @@ mod tests {
 		assert_eq!(15, anim_settings.fps);
 		assert_eq!(4, anim_settings.repeat);
 		assert_eq!(10, anim_settings.quality);
+		assert_eq!(40, anim_settings.lossy);
+		assert_eq!(9, anim_settings.keyframe);
 		assert_eq!(true, anim_settings.gifski.0);
 		assert_eq!(true, anim_settings.gifski.1);
 		assert_eq!(1.1, anim_settings.speed);
@@ -291,6 +316,8 @@ mod tests {
 		let anim_settings = AnimSettings::from_parser(ArgParser::new(None));
 		assert_eq!(-1, anim_settings.repeat);
 		assert_eq!(75, anim_settings.quality);
+		assert_eq!(0, anim_settings.lossy);
+		assert_eq!(0, anim_settings.keyframe);
 		assert_eq!(false, anim_settings.gifski.0);
 		assert_eq!(false, anim_settings.gifski.1);
 		assert_eq!(1.0, anim_settings.speed);
@@ -1,43 +1,247 @@
 use crate::anim::settings::AnimSettings;
 use crate::app::AppResult;
 use crate::image::geometry::Geometry;
+use crate::image::settings::PngSettings;
 use crate::image::Image;
 use crate::util::state::InputState;
 use image::ExtendedColorType;
-use png::{BitDepth, ColorType, Encoder, FilterType};
+use png::{BitDepth, BlendOp, ColorType, DisposeOp, Encoder};
+use std::collections::HashMap;
 use std::io::{self, Write};
 
+/* Palette, transparency table and color indices */
+type Palette = (Vec<u8>, Vec<u8>, HashMap<[u8; 4], u8>);
+
+/* Region of the animation that is updated by a frame */
+#[derive(Clone, Debug)]
+struct SubFrame {
+	geometry: Geometry,
+	data: Vec<u8>,
+	delay: u16,
+	blend: BlendOp,
+	dispose: DisposeOp,
+}
+
 /* APNG encoder and settings */
 pub struct ApngEncoder<'a, Output: Write> {
 	encoder: Encoder<'a, Output>,
+	geometry: Geometry,
 	settings: &'a AnimSettings,
+	png: &'a PngSettings,
 }
 
 impl<'a, Output: Write> ApngEncoder<'a, Output> {
 	/**
 	 * Create a new ApngEncoder object.
 	 *
-	 * @param  frame_count
 	 * @param  geometry
 	 * @param  output
 	 * @param  settings
-	 * @return ApngEncoder (Result)
+	 * @param  png
+	 * @return ApngEncoder
 	 */
 	pub fn new(
-		frame_count: u32,
 		geometry: Geometry,
 		output: Output,
 		settings: &'a AnimSettings,
-	) -> AppResult<Self> {
+		png: &'a PngSettings,
+	) -> Self {
 		let mut encoder = Encoder::new(output, geometry.width, geometry.height);
-		encoder.set_animated(
-			frame_count,
-			settings.repeat.try_into().unwrap_or_default(),
-		)?;
-		encoder.set_color(ColorType::Rgba);
 		encoder.set_depth(BitDepth::Eight);
-		encoder.set_filter(FilterType::NoFilter);
-		Ok(Self { encoder, settings })
+		encoder.set_compression(png.get_compression());
+		let (filter, adaptive_filter) = png.get_filter();
+		encoder.set_filter(filter);
+		encoder.set_adaptive_filter(adaptive_filter);
+		Self {
+			encoder,
+			geometry,
+			settings,
+			png,
+		}
+	}
+
+	/**
+	 * Get the bounding box of the pixels that differ between two frames.
+	 *
+	 * @param  previous
+	 * @param  current
+	 * @return Geometry (Option)
+	 */
+	fn get_diff_area(&self, previous: &[u8], current: &[u8]) -> Option<Geometry> {
+		let width = self.geometry.width as usize;
+		let (mut x_min, mut y_min, mut x_max, mut y_max) =
+			(usize::MAX, usize::MAX, 0, 0);
+		for (i, (a, b)) in previous.chunks(4).zip(current.chunks(4)).enumerate() {
+			if a != b {
+				let (x, y) = (i % width, i / width);
+				x_min = x_min.min(x);
+				y_min = y_min.min(y);
+				x_max = x_max.max(x);
+				y_max = y_max.max(y);
+			}
+		}
+		if x_min == usize::MAX {
+			None
+		} else {
+			Some(Geometry::new(
+				x_min as i32,
+				y_min as i32,
+				(x_max - x_min + 1) as u32,
+				(y_max - y_min + 1) as u32,
+			))
+		}
+	}
+
+	/**
+	 * Crop the given area of the frame while choosing the blend operation.
+	 *
+	 * Unchanged pixels become transparent if the frame can be blended over
+	 * the previous one, which results in a better compression.
+	 *
+	 * @param  previous
+	 * @param  current
+	 * @param  area
+	 * @return Tuple of Vector of u8 and BlendOp
+	 */
+	fn crop_frame(
+		&self,
+		previous: &[u8],
+		current: &[u8],
+		area: Geometry,
+	) -> (Vec<u8>, BlendOp) {
+		let width = self.geometry.width as usize;
+		let pixels = (area.y as usize..area.y as usize + area.height as usize)
+			.flat_map(|y| {
+				(area.x as usize..area.x as usize + area.width as usize)
+					.map(move |x| (y * width + x) * 4)
+			})
+			.collect::<Vec<usize>>();
+		let blend = if pixels.iter().all(|i| {
+			previous[*i..*i + 4] == current[*i..*i + 4] || current[*i + 3] == 0xFF
+		}) {
+			BlendOp::Over
+		} else {
+			BlendOp::Source
+		};
+		let data = pixels
+			.iter()
+			.flat_map(|i| {
+				if blend == BlendOp::Over
+					&& previous[*i..*i + 4] == current[*i..*i + 4]
+				{
+					[0, 0, 0, 0]
+				} else {
+					[
+						current[*i],
+						current[*i + 1],
+						current[*i + 2],
+						current[*i + 3],
+					]
+				}
+			})
+			.collect();
+		(data, blend)
+	}
+
+	/**
+	 * Optimize the frames by only keeping the changed regions.
+	 *
+	 * @param  images
+	 * @return Vector of SubFrame
+	 */
+	fn get_subframes(&self, images: &[Image]) -> Vec<SubFrame> {
+		let mut subframes = Vec::<SubFrame>::new();
+		let mut canvas = Vec::new();
+		for image in images {
+			let data = image.get_data(ExtendedColorType::Rgba8);
+			let previous = match subframes.last_mut() {
+				Some(previous) => previous,
+				None => {
+					subframes.push(SubFrame {
+						geometry: self.geometry,
+						data: data.clone(),
+						delay: 1,
+						blend: BlendOp::Source,
+						dispose: DisposeOp::None,
+					});
+					canvas = data;
+					continue;
+				}
+			};
+			let mut cleared = canvas.clone();
+			let width = self.geometry.width as usize;
+			for y in previous.geometry.y as usize
+				..(previous.geometry.y as u32 + previous.geometry.height) as usize
+			{
+				let start = (y * width + previous.geometry.x as usize) * 4;
+				cleared[start..start + previous.geometry.width as usize * 4].fill(0);
+			}
+			let (base, area) = match (
+				self.get_diff_area(&canvas, &data),
+				self.get_diff_area(&cleared, &data),
+			) {
+				(None, _) => {
+					previous.delay = previous.delay.saturating_add(1);
+					continue;
+				}
+				(Some(area), Some(cleared_area))
+					if cleared_area.width * cleared_area.height
+						< area.width * area.height =>
+				{
+					previous.dispose = DisposeOp::Background;
+					(cleared, cleared_area)
+				}
+				(Some(area), _) => (canvas, area),
+			};
+			let (frame_data, blend) = self.crop_frame(&base, &data, area);
+			subframes.push(SubFrame {
+				geometry: area,
+				data: frame_data,
+				delay: 1,
+				blend,
+				dispose: DisposeOp::None,
+			});
+			canvas = data;
+		}
+		subframes
+	}
+
+	/**
+	 * Get the palette and transparency table if the colors fit.
+	 *
+	 * @param  subframes
+	 * @return Palette (Option)
+	 */
+	fn get_palette(&self, subframes: &[SubFrame]) -> Option<Palette> {
+		let mut colors = HashMap::<[u8; 4], u8>::new();
+		for subframe in subframes {
+			for rgba in subframe.data.chunks(4) {
+				let color = [rgba[0], rgba[1], rgba[2], rgba[3]];
+				if !colors.contains_key(&color) {
+					if colors.len() == 256 {
+						return None;
+					}
+					colors.insert(color, 0);
+				}
+			}
+		}
+		let mut palette = colors.keys().copied().collect::<Vec<[u8; 4]>>();
+		palette.sort_by_key(|rgba| (rgba[3], rgba[0], rgba[1], rgba[2]));
+		for (i, color) in palette.iter().enumerate() {
+			colors.insert(*color, i as u8);
+		}
+		let mut trns = palette.iter().map(|rgba| rgba[3]).collect::<Vec<u8>>();
+		while trns.last() == Some(&0xFF) {
+			trns.pop();
+		}
+		Some((
+			palette
+				.iter()
+				.flat_map(|rgba| [rgba[0], rgba[1], rgba[2]])
+				.collect(),
+			trns,
+			colors,
+		))
 	}
 
 	/**
@@ -48,20 +252,45 @@ impl<'a, Output: Write> ApngEncoder<'a, Output> {
 	 * @return Result
 	 */
 	pub fn save(
-		self,
+		mut self,
 		images: Vec<Image>,
 		input_state: Option<&'static InputState>,
 	) -> AppResult<()> {
+		let subframes = self.get_subframes(&images);
+		debug!("Optimized {} frames to {}", images.len(), subframes.len());
+		self.encoder.set_animated(
+			subframes.len().try_into().unwrap_or_default(),
+			self.settings.repeat.try_into().unwrap_or_default(),
+		)?;
+		let palette = if self.png.palette {
+			let palette = self.get_palette(&subframes);
+			if palette.is_none() {
+				warn!("Too many colors for palette, using RGBA instead.");
+			}
+			palette
+		} else {
+			None
+		};
+		let indices = if let Some((colors, trns, indices)) = palette {
+			self.encoder.set_color(ColorType::Indexed);
+			self.encoder.set_palette(colors);
+			if !trns.is_empty() {
+				self.encoder.set_trns(trns);
+			}
+			Some(indices)
+		} else {
+			self.encoder.set_color(ColorType::Rgba);
+			None
+		};
 		let mut writer = self.encoder.write_header()?;
-		writer.set_frame_delay(1, self.settings.fps.try_into().unwrap_or(1))?;
-		for (i, image) in images.iter().enumerate() {
-			let percentage = ((i + 1) as f64 / images.len() as f64) * 100.;
+		for (i, subframe) in subframes.iter().enumerate() {
+			let percentage = ((i + 1) as f64 / subframes.len() as f64) * 100.;
 			info!("Saving... ({:.1}%)\r", percentage);
 			debug!(
 				"Encoding... ({:.1}%) [{}/{}]\r",
 				percentage,
 				i + 1,
-				images.len()
+				subframes.len()
 			);
 			io::stdout().flush()?;
 			if let Some(state) = input_state {
@@ -71,7 +300,31 @@ impl<'a, Output: Write> ApngEncoder<'a, Output> {
 					panic!("Failed to write the frames")
 				}
 			}
-			writer.write_image_data(&image.get_data(ExtendedColorType::Rgba8))?;
+			writer.set_frame_delay(
+				subframe.delay,
+				self.settings.fps.try_into().unwrap_or(1),
+			)?;
+			writer.reset_frame_position()?;
+			writer.set_frame_dimension(
+				subframe.geometry.width,
+				subframe.geometry.height,
+			)?;
+			writer.set_frame_position(
+				subframe.geometry.x.try_into().unwrap_or_default(),
+				subframe.geometry.y.try_into().unwrap_or_default(),
+			)?;
+			writer.set_blend_op(subframe.blend)?;
+			writer.set_dispose_op(subframe.dispose)?;
+			match &indices {
+				Some(indices) => writer.write_image_data(
+					&subframe
+						.data
+						.chunks(4)
+						.map(|rgba| indices[&[rgba[0], rgba[1], rgba[2], rgba[3]]])
+						.collect::<Vec<u8>>(),
+				)?,
+				None => writer.write_image_data(&subframe.data)?,
+			}
 		}
 		info!("\n");
 		Ok(())
@@ -81,7 +334,20 @@ impl<'a, Output: Write> ApngEncoder<'a, Output> {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use image::Rgba;
+	use image::codecs::png::PngDecoder;
+	use image::{AnimationDecoder, Rgba};
+	use pretty_assertions::assert_eq;
+	fn encode(images: Vec<Image>, png: &PngSettings) -> AppResult<Vec<u8>> {
+		let mut output = Vec::new();
+		ApngEncoder::new(
+			images[0].geometry,
+			&mut output,
+			&AnimSettings::default(),
+			png,
+		)
+		.save(images, None)?;
+		Ok(output)
+	}
 	#[test]
 	fn test_apng_encoder() -> AppResult<()> {
 		let geometry = Geometry::new(0, 0, 1, 2);
@@ -90,16 +356,48 @@ mod tests {
 			Image::new(data.clone(), false, geometry),
 			Image::new(data.into_iter().rev().collect(), false, geometry),
 		];
-		let mut output = Vec::new();
-		ApngEncoder::new(
-			images.len().try_into().unwrap(),
-			geometry,
-			&mut output,
-			&AnimSettings::default(),
-		)?
-		.save(images, None)?;
+		let mut output = encode(images, &PngSettings::default())?;
 		output.truncate(6);
 		assert_eq!(vec![0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a], output);
 		Ok(())
 	}
+	#[test]
+	fn test_apng_subframes() -> AppResult<()> {
+		let geometry = Geometry::new(0, 0, 4, 3);
+		let mut images = Vec::new();
+		for i in 0..4 {
+			let mut data = vec![Rgba::from([0, 0, 0, 255]); 12];
+			data[i] = Rgba::from([255, 0, 0, 255]);
+			data[11] = Rgba::from([0, 0, 255, if i == 3 { 0 } else { 255 }]);
+			images.push(Image::new(data, true, geometry));
+		}
+		images.insert(1, images[0].clone());
+		for palette in [false, true] {
+			let png = PngSettings {
+				palette,
+				..PngSettings::default()
+			};
+			let output = encode(images.clone(), &png)?;
+			let frames = PngDecoder::new(output.as_slice())?
+				.apng()
+				.into_frames()
+				.collect_frames()?;
+			assert_eq!(4, frames.len());
+			assert_eq!((100, 1), frames[0].delay().numer_denom_ms());
+			assert_eq!((50, 1), frames[1].delay().numer_denom_ms());
+			for (frame, image) in frames.iter().zip(
+				images
+					.iter()
+					.enumerate()
+					.filter(|(i, _)| *i != 1)
+					.map(|v| v.1),
+			) {
+				assert_eq!(
+					image.get_data(ExtendedColorType::Rgba8),
+					frame.buffer().to_vec()
+				);
+			}
+		}
+		Ok(())
+	}
 }
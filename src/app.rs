@@ -1,23 +1,39 @@
 use crate::anim::decoder::AnimDecoder;
+use crate::anim::fit::SizeFitter;
+use crate::anim::settings::AnimSettings;
 use crate::anim::Frames;
 use crate::apng::ApngEncoder;
 use crate::args::Args;
+use crate::edit::orientation::Orientation;
 use crate::file::format::FileFormat;
+use crate::file::metadata::ImageMetadata;
 use crate::file::File as FileUtil;
 use crate::gif::encoder::{Encoder, EncoderConfig};
 #[cfg(feature = "ski")]
 use crate::gif::ski::GifskiEncoder;
 use crate::gif::GifEncoder;
+use crate::image::avif::AvifEncoder;
+use crate::image::icon::IconEncoder;
+use crate::image::png::PngWriter;
+use crate::image::qoi::{self, QoiEncoder};
+use crate::image::settings::WebPSettings;
 use crate::image::Image;
+use crate::pdf::PdfEncoder;
 use crate::record::Recorder;
 use crate::settings::AppSettings;
+use crate::tiff::decoder::AnimTiffDecoder;
+use crate::tiff::AnimTiffEncoder;
+use crate::video::pipe::FramePipe;
+use crate::video::raw::RawEncoder;
+use crate::video::y4m::Y4mEncoder;
 use crate::view::ImageViewer;
+use crate::webp::decoder::AnimWebPDecoder;
+use crate::webp::AnimWebPEncoder;
 use crate::window::Capture;
 use bytesize::ByteSize;
 use image::codecs::bmp::BmpEncoder;
 use image::codecs::farbfeld::FarbfeldEncoder;
 use image::codecs::gif::GifDecoder;
-use image::codecs::ico::IcoEncoder;
 use image::codecs::jpeg::JpegEncoder;
 use image::codecs::openexr::OpenExrEncoder;
 use image::codecs::png::PngDecoder;
@@ -31,12 +47,14 @@ use image::error::{
 };
 use image::io::Reader;
 use image::{
-	AnimationDecoder, ColorType, ExtendedColorType, ImageEncoder, ImageFormat,
+	AnimationDecoder, ColorType, DynamicImage, ExtendedColorType, Frame,
+	ImageEncoder, ImageFormat,
 };
 use std::fmt::Debug;
 use std::fs::{self, File};
 use std::io::{self, Cursor, Read, Seek, Write};
 use std::path::Path;
+use std::sync::mpsc;
 use std::thread;
 use thiserror::Error as ThisError;
 
@@ -53,6 +71,10 @@ pub enum AppError {
 	GifEncoding(#[from] gif::EncodingError),
 	#[error("PNG encoding error: `{0}`")]
 	PngEncoding(#[from] png::EncodingError),
+	#[error("WebP encoding error: `{0}`")]
+	WebPEncoding(String),
+	#[error("TIFF error: `{0}`")]
+	Tiff(#[from] tiff::TiffError),
 	#[cfg(feature = "ski")]
 	#[error("gifski error: `{0}`")]
 	Gifski(#[from] gifski::Error),
@@ -119,15 +141,14 @@ where
 		} else if self.settings.args.is_present("view") {
 			debug!("Viewing the image... ({:?})", self.settings.view.file);
 			self.view_image()?;
+		} else if let Some((command, format)) = &self.settings.save.pipe {
+			self.pipe_output(command, format)?;
+			info!("Frames piped to: {:?}", command);
 		} else if self.settings.save.file.path.to_str() == Some("-") {
-			let mut buffer = Cursor::new(Vec::new());
-			self.save_output(self.get_app_output()?, &mut buffer)?;
-			io::stdout().write_all(&buffer.into_inner())?;
+			io::stdout().write_all(&self.get_output_data()?)?;
 		} else {
-			self.save_output(
-				self.get_app_output()?,
-				File::create(&self.settings.save.file.path)?,
-			)?;
+			let data = self.get_output_data()?;
+			File::create(&self.settings.save.file.path)?.write_all(&data)?;
 			info!(
 				"{} saved to: {:?} ({})",
 				self.settings.save.file.format.as_extension().to_uppercase(),
@@ -144,7 +165,10 @@ where
 	 * @return AppOutput (Result)
 	 */
 	fn get_app_output(self) -> AppResult<AppOutput> {
-		let output = if self.settings.save.file.format.is_animation() {
+		self.save_recipe()?;
+		let output = if self.settings.save.file.format.is_animation()
+			|| self.settings.args.is_present("make")
+		{
 			(None, Some(self.get_frames()?))
 		} else {
 			(Some(self.get_image()?), None)
@@ -155,6 +179,85 @@ where
 		Ok(output)
 	}
 
+	/**
+	 * Save the operations of the pipeline to the recipe file.
+	 *
+	 * @return Result
+	 */
+	fn save_recipe(&self) -> AppResult<()> {
+		if let (Some(pipeline), Some(path)) = (
+			&self.settings.edit.pipeline,
+			&self.settings.edit.save_recipe,
+		) {
+			pipeline.save(path)?;
+			info!("Recipe saved to: {:?}", path);
+		}
+		Ok(())
+	}
+
+	/**
+	 * Get the encoded application output along with the metadata.
+	 *
+	 * @return Vector of u8 (Result)
+	 */
+	fn get_output_data(&self) -> AppResult<Vec<u8>> {
+		let title = self.window.and_then(|window| window.get_name());
+		let app_output = self.get_app_output()?;
+		let metadata = self.get_metadata(title, &app_output);
+		let mut buffer = Cursor::new(Vec::new());
+		self.save_output(app_output, &mut buffer)?;
+		let data = buffer.into_inner();
+		Ok(match metadata {
+			Some(metadata) => metadata.embed(data, &self.settings.save.file.format),
+			None => data,
+		})
+	}
+
+	/**
+	 * Get the metadata to embed into the output file.
+	 *
+	 * Metadata (including the camera EXIF fields) of the edited file
+	 * is kept unless it is stripped.
+	 *
+	 * @param  title (Option)
+	 * @param  app_output
+	 * @return ImageMetadata (Option)
+	 */
+	fn get_metadata(
+		&self,
+		title: Option<String>,
+		app_output: &AppOutput,
+	) -> Option<ImageMetadata> {
+		let mut metadata = if !self.settings.save.metadata {
+			ImageMetadata::default()
+		} else if self.window.is_some() {
+			ImageMetadata::new(
+				title,
+				match app_output {
+					(Some(image), _) => Some(image.geometry),
+					(None, Some((images, _))) => {
+						images.first().map(|image| image.geometry)
+					}
+					_ => None,
+				},
+				self.settings.record.flag.monitor,
+				self.settings.record.command,
+			)
+		} else if self.settings.args.is_present("edit")
+			&& !self.settings.edit.metadata.strip
+		{
+			fs::read(&self.settings.edit.path)
+				.ok()
+				.and_then(|data| ImageMetadata::read(&data))
+				.unwrap_or_else(|| ImageMetadata::new(None, None, None, None))
+		} else {
+			ImageMetadata::new(None, None, None, None)
+		};
+		self.settings.edit.metadata.apply(&mut metadata);
+		debug!("{:?}", metadata);
+		(!metadata.is_empty()).then_some(metadata)
+	}
+
 	/**
 	 * Get the image to save.
 	 *
@@ -166,7 +269,13 @@ where
 			info!("Opening {:?}...", self.settings.edit.path);
 			self.edit_image(&self.settings.edit.path)
 		} else {
-			self.capture()
+			let edit = &self.settings.edit;
+			let image = self.capture()?;
+			Ok(if edit.is_capture_edited() {
+				edit.get_imageops().process_image(image)
+			} else {
+				image
+			})
 		}
 	}
 
@@ -183,21 +292,54 @@ where
 				&self.settings.edit.path,
 			)
 		} else if self.settings.args.is_present("make") {
-			info!(
-				"Making an animation from {} frames...",
-				self.settings.anim.frames.len()
-			);
-			let mut images = Vec::new();
-			for path in &self.settings.anim.frames {
-				debug!("Reading a frame from {:?}   \r", path);
-				io::stdout().flush()?;
-				images.push(self.edit_image(path)?);
-			}
-			debug!("\n");
-			Ok((images, self.settings.anim.fps))
+			AnimDecoder::new(self.settings.edit.get_imageops(), &self.settings.anim)
+				.update_frames(self.read_frames()?)
 		} else {
-			Ok((self.record()?, self.settings.anim.fps))
+			let (edit, fps) = (&self.settings.edit, self.settings.anim.fps);
+			let (sender, receiver) = mpsc::channel();
+			let images = if self.record(sender)? {
+				receiver.into_iter().collect()
+			} else {
+				Vec::new()
+			};
+			Ok((
+				if edit.is_capture_edited() {
+					let mut imageops = edit.get_imageops();
+					images
+						.into_iter()
+						.map(|image| imageops.process_image(image))
+						.collect()
+				} else {
+					images
+				},
+				fps,
+			))
+		}
+	}
+
+	/**
+	 * Read the frames to make an animation from.
+	 *
+	 * @return Vector of Frame (Result)
+	 */
+	fn read_frames(&self) -> AppResult<Vec<Frame>> {
+		info!(
+			"Making an animation from {} frames...",
+			self.settings.anim.frames.len()
+		);
+		let mut frames = Vec::new();
+		for path in &self.settings.anim.frames {
+			debug!("Reading a frame from {:?}   \r", path);
+			io::stdout().flush()?;
+			let image = qoi::read_image(path)?.to_rgba8();
+			frames.push(Frame::new(if self.settings.edit.image.auto_orient {
+				Orientation::read(path).apply(image)
+			} else {
+				image
+			}));
 		}
+		debug!("\n");
+		Ok(frames)
 	}
 
 	/**
@@ -238,9 +380,13 @@ where
 	/**
 	 * Start recording the frames.
 	 *
-	 * @return Vector of Image (Result)
+	 * Frames are sent through the channel as they are captured.
+	 * False is returned if the recording is cancelled.
+	 *
+	 * @param  sender
+	 * @return bool (Result)
 	 */
-	fn record(self) -> AppResult<Vec<Image>> {
+	fn record(self, sender: mpsc::Sender<Image>) -> AppResult<bool> {
 		let mut recorder = Recorder::new(
 			self.window.ok_or_else(|| {
 				AppError::WsError(String::from("Failed to get the window"))
@@ -250,7 +396,7 @@ where
 			self.settings.record,
 		);
 		if self.settings.record.command.is_some() {
-			let record = recorder.record_async();
+			let record = recorder.record_async(sender);
 			self.settings
 				.record
 				.get_command()
@@ -260,21 +406,55 @@ where
 					))
 				})?
 				.execute()?;
-			Ok(match record.get() {
-				Some(frames) => frames.expect("Failed to retrieve the frames."),
-				None => Vec::new(),
-			})
+			if let Some(result) = record.get() {
+				result.expect("Failed to retrieve the frames.");
+			}
+			Ok(true)
 		} else {
-			Ok(recorder.record_sync(
+			recorder.record_sync(
 				if self.settings.record.flag.action_keys.is_some() {
 					self.settings.input_state
 				} else {
 					None
 				},
-			)?)
+				sender,
+			)
 		}
 	}
 
+	/**
+	 * Record the frames and pass them to the given function.
+	 *
+	 * Frames are edited and passed on a separate thread
+	 * as they are captured to keep the recording FPS.
+	 *
+	 * @param  write
+	 * @return Result
+	 */
+	fn stream_record<F>(self, mut write: F) -> AppResult<()>
+	where
+		F: FnMut(Image) -> AppResult<()> + Send,
+	{
+		let edit = &self.settings.edit;
+		let (sender, receiver) = mpsc::channel();
+		thread::scope(|scope| {
+			let writer = scope.spawn(move || -> AppResult<()> {
+				let mut imageops = edit.get_imageops();
+				for image in receiver {
+					write(if edit.is_capture_edited() {
+						imageops.process_image(image)
+					} else {
+						image
+					})?;
+				}
+				Ok(())
+			});
+			let recorded = self.record(sender);
+			writer.join().expect("Failed to join the writer thread.")?;
+			recorded.map(|_| ())
+		})
+	}
+
 	/**
 	 * Edit and return the image.
 	 *
@@ -282,14 +462,12 @@ where
 	 * @return Image (Result)
 	 */
 	fn edit_image(self, path: &Path) -> AppResult<Image> {
-		let image = Reader::open(path)?
-			.with_guessed_format()?
-			.decode()?
-			.to_rgba8();
+		let image = qoi::read_image(path)?.to_rgba8();
 		Ok(self
 			.settings
 			.edit
 			.get_imageops()
+			.orient(Orientation::read(path))
 			.init(image.dimensions())
 			.process(image)
 			.get_image())
@@ -321,9 +499,11 @@ where
 	 * @return Result
 	 */
 	fn view_image(self) -> AppResult<()> {
-		let image = Reader::open(&self.settings.view.file)?
-			.with_guessed_format()?
-			.decode()?;
+		let mut image = qoi::read_image(&self.settings.view.file)?;
+		let orientation = Orientation::read(&self.settings.view.file);
+		if self.settings.view.auto_orient && orientation != Orientation::Normal {
+			image = DynamicImage::ImageRgba8(orientation.apply(image.to_rgba8()));
+		}
 		let viewer = ImageViewer::new(image, &self.settings.view);
 		viewer
 			.view()
@@ -338,25 +518,44 @@ where
 	 * @return Frames (Result)
 	 */
 	fn edit_anim<Input: Read>(self, input: Input, path: &Path) -> AppResult<Frames> {
+		AnimDecoder::new(self.settings.edit.get_imageops(), &self.settings.anim)
+			.update_frames(self.decode_anim(input, path)?)
+	}
+
+	/**
+	 * Decode the frames of the animation.
+	 *
+	 * @param  input
+	 * @param  path
+	 * @return Vector of Frame (Result)
+	 */
+	fn decode_anim<Input: Read>(
+		&self,
+		input: Input,
+		path: &Path,
+	) -> AppResult<Vec<Frame>> {
 		let format = Reader::open(path)?.with_guessed_format()?.format();
-		let frames =
-			AnimDecoder::new(self.settings.edit.get_imageops(), &self.settings.anim)
-				.update_frames(match format {
-					Some(ImageFormat::Gif) => {
-						GifDecoder::new(input)?.into_frames().collect_frames()
-					}
-					Some(ImageFormat::Png) => PngDecoder::new(input)?
-						.apng()
-						.into_frames()
-						.collect_frames(),
-					_ => Err(ImageError::Unsupported(
-						UnsupportedError::from_format_and_kind(
-							ImageFormatHint::Unknown,
-							UnsupportedErrorKind::Format(ImageFormatHint::Unknown),
-						),
-					)),
-				}?)?;
-		Ok(frames)
+		Ok(match format {
+			Some(ImageFormat::Gif) => {
+				GifDecoder::new(input)?.into_frames().collect_frames()
+			}
+			Some(ImageFormat::Png) => PngDecoder::new(input)?
+				.apng()
+				.into_frames()
+				.collect_frames(),
+			Some(ImageFormat::WebP) => {
+				AnimWebPDecoder::new(input)?.into_frames().collect_frames()
+			}
+			Some(ImageFormat::Tiff) => {
+				AnimTiffDecoder::new(input)?.into_frames().collect_frames()
+			}
+			_ => Err(ImageError::Unsupported(
+				UnsupportedError::from_format_and_kind(
+					ImageFormatHint::Unknown,
+					UnsupportedErrorKind::Format(ImageFormatHint::Unknown),
+				),
+			)),
+		}?)
 	}
 
 	/**
@@ -396,13 +595,35 @@ where
 	) -> AppResult<()> {
 		let (image, frames) = app_output;
 		match self.settings.save.file.format {
-			FileFormat::Gif => {
+			FileFormat::Gif | FileFormat::Apng | FileFormat::AnimWebP => {
 				debug!("{:?}", self.settings.anim);
-				self.save_gif(frames, output)
+				self.save_anim(frames, output)
 			}
-			FileFormat::Apng => {
+			FileFormat::Y4m => {
 				debug!("{:?}", self.settings.anim);
-				self.save_apng(frames, output)
+				self.save_y4m(frames, output)
+			}
+			FileFormat::Rgba => {
+				debug!("{:?}", self.settings.anim);
+				self.save_rgba(frames, output)
+			}
+			FileFormat::AnimTiff => {
+				debug!("{:?}", self.settings.anim);
+				self.save_tiff(frames, output)
+			}
+			FileFormat::Pdf => {
+				debug!("{:?}", self.settings.pdf);
+				self.save_pdf(image.map(|image| (vec![image], 0)).or(frames), output)
+			}
+			FileFormat::Png
+				if self.settings.png.colors.is_some()
+					|| self.settings.png.optimize =>
+			{
+				self.save_image(
+					image,
+					PngWriter::new(output, self.settings.png),
+					ExtendedColorType::Rgba8,
+				)
 			}
 			FileFormat::Png => self.save_image(
 				image,
@@ -429,19 +650,29 @@ where
 				),
 				ExtendedColorType::Rgb8,
 			),
+			FileFormat::Avif => self.save_image(
+				image,
+				AvifEncoder::new(output, self.settings.avif),
+				ExtendedColorType::Rgba8,
+			),
 			FileFormat::Bmp => self.save_image(
 				image,
 				BmpEncoder::new(&mut output),
 				ExtendedColorType::Rgba8,
 			),
-			FileFormat::Ico => self.save_image(
+			FileFormat::Ico | FileFormat::Icns => self.save_image(
 				image,
-				IcoEncoder::new(output),
+				IconEncoder::new(
+					output,
+					&self.settings.save.file.format,
+					&self.settings.icon,
+					self.settings.edit.image.filter,
+				),
 				ExtendedColorType::Rgba8,
 			),
 			FileFormat::Tiff => self.save_image(
 				image,
-				TiffEncoder::new(File::create(&self.settings.save.file.path)?),
+				TiffEncoder::new(output),
 				ExtendedColorType::Rgba8,
 			),
 			FileFormat::Tga => self.save_image(
@@ -469,10 +700,57 @@ where
 				OpenExrEncoder::new(output),
 				ExtendedColorType::Rgba32F,
 			),
+			FileFormat::Qoi => self.save_image(
+				image,
+				QoiEncoder::new(output),
+				ExtendedColorType::Rgba8,
+			),
 			_ => Ok(()),
 		}
 	}
 
+	/**
+	 * Pipe the application output to a command.
+	 *
+	 * Frames are written to the command as they are produced.
+	 *
+	 * @param   command
+	 * @param   format
+	 * @return  Result
+	 */
+	fn pipe_output(self, command: &str, format: &FileFormat) -> AppResult<()> {
+		self.save_recipe()?;
+		let mut pipe = FramePipe::new(command, format, &self.settings.png);
+		let fps = self.settings.anim.fps;
+		if !self.settings.save.file.format.is_animation()
+			&& !self.settings.args.is_present("make")
+		{
+			pipe.write(&self.get_image()?, fps)?;
+		} else if self.settings.args.is_present("edit") {
+			info!("Reading frames from {:?}...", self.settings.edit.path);
+			AnimDecoder::new(self.settings.edit.get_imageops(), &self.settings.anim)
+				.process_frames(
+					self.decode_anim(
+						File::open(&self.settings.edit.path)?,
+						&self.settings.edit.path,
+					)?,
+					|image, fps| pipe.write(&image, fps),
+				)?;
+		} else if self.settings.args.is_present("make") {
+			AnimDecoder::new(self.settings.edit.get_imageops(), &self.settings.anim)
+				.process_frames(self.read_frames()?, |image, fps| {
+					pipe.write(&image, fps)
+				})?;
+		} else {
+			self.stream_record(|image| pipe.write(&image, fps))?;
+		}
+		if let Some(window) = self.window {
+			window.release();
+		}
+		pipe.finish()?;
+		Ok(())
+	}
+
 	/**
 	 * Save the image to a file.
 	 *
@@ -516,11 +794,81 @@ where
 		Ok(())
 	}
 
+	/**
+	 * Save frames to an animation file, fitting it under the size limit if set.
+	 *
+	 * @param   frames (Option)
+	 * @param   output
+	 * @return  Result
+	 */
+	fn save_anim<Output: Write>(
+		self,
+		frames: Option<Frames>,
+		mut output: Output,
+	) -> AppResult<()> {
+		match self.settings.save.max_size {
+			Some(max_size) => {
+				let frames = frames.ok_or_else(|| {
+					AppError::FrameError(String::from("Failed to get the frames"))
+				})?;
+				let data = SizeFitter::new(
+					max_size,
+					&self.settings.save.file.format,
+					&self.settings.anim,
+					self.settings.webp,
+				)
+				.fit(frames, |frames, anim, webp| {
+					let mut data = Vec::new();
+					self.encode_anim(Some(frames), &mut data, anim, webp)?;
+					Ok(data)
+				})?;
+				output.write_all(&data)?;
+				Ok(())
+			}
+			None => self.encode_anim(
+				frames,
+				output,
+				&self.settings.anim,
+				&self.settings.webp,
+			),
+		}
+	}
+
+	/**
+	 * Encode frames with the animation encoder of the output format.
+	 *
+	 * @param   frames (Option)
+	 * @param   output
+	 * @param   anim
+	 * @param   webp
+	 * @return  Result
+	 */
+	fn encode_anim<Output: Write>(
+		self,
+		frames: Option<Frames>,
+		output: Output,
+		anim: &AnimSettings,
+		webp: &WebPSettings,
+	) -> AppResult<()> {
+		match self.settings.save.file.format {
+			FileFormat::Apng => {
+				debug!("{:?}", self.settings.png);
+				self.save_apng(frames, output, anim)
+			}
+			FileFormat::AnimWebP => {
+				debug!("{:?}", webp);
+				self.save_webp(frames, output, anim, webp)
+			}
+			_ => self.save_gif(frames, output, anim),
+		}
+	}
+
 	/**
 	 * Save frames to a GIF file.
 	 *
 	 * @param   frames (Option)
 	 * @param   output
+	 * @param   anim
 	 * @return  Result
 	 */
 	#[cfg(feature = "ski")]
@@ -528,6 +876,7 @@ where
 		self,
 		frames: Option<Frames>,
 		output: Output,
+		anim: &AnimSettings,
 	) -> AppResult<()> {
 		let (images, fps) = frames.ok_or_else(|| {
 			AppError::FrameError(String::from("Failed to get the frames"))
@@ -538,8 +887,8 @@ where
 				AppError::FrameError(String::from("No frames found to save"))
 			})?
 			.geometry;
-		let config = EncoderConfig::new(fps, geometry, output, &self.settings.anim);
-		if self.settings.anim.gifski.0 {
+		let config = EncoderConfig::new(fps, geometry, output, anim);
+		if anim.gifski.0 {
 			GifskiEncoder::new(config)?.save(images, self.settings.input_state)?;
 		} else {
 			GifEncoder::new(config)?.save(images, self.settings.input_state)?;
@@ -552,6 +901,7 @@ where
 	 *
 	 * @param   frames (Option)
 	 * @param   output
+	 * @param   anim
 	 * @return  Result
 	 */
 	#[cfg(not(feature = "ski"))]
@@ -559,6 +909,7 @@ where
 		self,
 		frames: Option<Frames>,
 		output: Output,
+		anim: &AnimSettings,
 	) -> AppResult<()> {
 		let (images, fps) = frames.ok_or_else(|| {
 			AppError::FrameError(String::from("Failed to get the frames"))
@@ -569,13 +920,8 @@ where
 				AppError::FrameError(String::from("No frames found to save"))
 			})?
 			.geometry;
-		GifEncoder::new(EncoderConfig::new(
-			fps,
-			geometry,
-			output,
-			&self.settings.anim,
-		))?
-		.save(images, self.settings.input_state)?;
+		GifEncoder::new(EncoderConfig::new(fps, geometry, output, anim))?
+			.save(images, self.settings.input_state)?;
 		Ok(())
 	}
 
@@ -584,12 +930,14 @@ where
 	 *
 	 * @param   frames (Option)
 	 * @param   output
+	 * @param   anim
 	 * @return  Result
 	 */
 	fn save_apng<Output: Write>(
 		self,
 		frames: Option<Frames>,
 		output: Output,
+		anim: &AnimSettings,
 	) -> AppResult<()> {
 		let images = frames
 			.ok_or_else(|| {
@@ -602,13 +950,145 @@ where
 				AppError::FrameError(String::from("No frames found to save"))
 			})?
 			.geometry;
-		ApngEncoder::new(
-			images.len().try_into().unwrap_or_default(),
-			geometry,
-			output,
-			&self.settings.anim,
-		)?
-		.save(images, self.settings.input_state)?;
+		ApngEncoder::new(geometry, output, anim, &self.settings.png)
+			.save(images, self.settings.input_state)?;
+		Ok(())
+	}
+
+	/**
+	 * Save frames to an animated WebP file.
+	 *
+	 * @param   frames (Option)
+	 * @param   output
+	 * @param   anim
+	 * @param   webp
+	 * @return  Result
+	 */
+	fn save_webp<Output: Write>(
+		self,
+		frames: Option<Frames>,
+		output: Output,
+		anim: &AnimSettings,
+		webp: &WebPSettings,
+	) -> AppResult<()> {
+		let (images, fps) = frames.ok_or_else(|| {
+			AppError::FrameError(String::from("Failed to get the frames"))
+		})?;
+		let geometry = images
+			.first()
+			.ok_or_else(|| {
+				AppError::FrameError(String::from("No frames found to save"))
+			})?
+			.geometry;
+		AnimWebPEncoder::new(fps, geometry, output, anim, webp)
+			.save(images, self.settings.input_state)?;
+		Ok(())
+	}
+
+	/**
+	 * Save frames to a Y4M stream.
+	 *
+	 * @param   frames (Option)
+	 * @param   output
+	 * @return  Result
+	 */
+	fn save_y4m<Output: Write>(
+		self,
+		frames: Option<Frames>,
+		output: Output,
+	) -> AppResult<()> {
+		let (images, fps) = frames.ok_or_else(|| {
+			AppError::FrameError(String::from("Failed to get the frames"))
+		})?;
+		let geometry = images
+			.first()
+			.ok_or_else(|| {
+				AppError::FrameError(String::from("No frames found to save"))
+			})?
+			.geometry;
+		Y4mEncoder::new(fps, geometry, output)
+			.save(images, self.settings.input_state)?;
+		Ok(())
+	}
+
+	/**
+	 * Save frames as raw RGBA data along with a header file.
+	 *
+	 * @param   frames (Option)
+	 * @param   output
+	 * @return  Result
+	 */
+	fn save_rgba<Output: Write>(
+		self,
+		frames: Option<Frames>,
+		output: Output,
+	) -> AppResult<()> {
+		let (images, fps) = frames.ok_or_else(|| {
+			AppError::FrameError(String::from("Failed to get the frames"))
+		})?;
+		let geometry = images
+			.first()
+			.ok_or_else(|| {
+				AppError::FrameError(String::from("No frames found to save"))
+			})?
+			.geometry;
+		let encoder = RawEncoder::new(fps, geometry, output);
+		let header = encoder.get_header(images.len());
+		encoder.save(images, self.settings.input_state)?;
+		if self.settings.save.file.path.to_str() != Some("-") {
+			let path = self.settings.save.file.path.with_extension("ini");
+			fs::write(&path, header)?;
+			info!("Header saved to: {:?}", path);
+		}
+		Ok(())
+	}
+
+	/**
+	 * Save frames as pages of a TIFF file.
+	 *
+	 * @param   frames (Option)
+	 * @param   output
+	 * @return  Result
+	 */
+	fn save_tiff<Output: Write + Seek>(
+		self,
+		frames: Option<Frames>,
+		output: Output,
+	) -> AppResult<()> {
+		let (images, _) = frames.ok_or_else(|| {
+			AppError::FrameError(String::from("Failed to get the frames"))
+		})?;
+		if images.is_empty() {
+			return Err(AppError::FrameError(String::from(
+				"No frames found to save",
+			)));
+		}
+		AnimTiffEncoder::new(output).save(images, self.settings.input_state)?;
+		Ok(())
+	}
+
+	/**
+	 * Save images as pages of a PDF document.
+	 *
+	 * @param   frames (Option)
+	 * @param   output
+	 * @return  Result
+	 */
+	fn save_pdf<Output: Write>(
+		self,
+		frames: Option<Frames>,
+		output: Output,
+	) -> AppResult<()> {
+		let (images, _) = frames.ok_or_else(|| {
+			AppError::FrameError(String::from("Failed to get the frames"))
+		})?;
+		if images.is_empty() {
+			return Err(AppError::FrameError(String::from(
+				"No frames found to save",
+			)));
+		}
+		PdfEncoder::new(output, &self.settings.pdf, &self.settings.jpg)
+			.save(images, self.settings.input_state)?;
 		Ok(())
 	}
 }
@@ -616,9 +1096,14 @@ where
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::analyze::settings::{AnalyzeSettings, TimeZone};
+	use crate::analyze::ImageAnalyzer;
 	use crate::args::matches::ArgMatches;
 	use crate::window::test::TestWindow;
 	use clap::ArgMatches as Args;
+	use colored::Color;
+	use exif::experimental::Writer as ExifWriter;
+	use exif::{Field, In, Tag, Value};
 	use std::env;
 	use std::path::PathBuf;
 	#[test]
@@ -637,6 +1122,7 @@ mod tests {
 			FileFormat::Pnm(String::from("ppm")),
 			FileFormat::Ff,
 			FileFormat::Exr,
+			FileFormat::Qoi,
 		] {
 			let path =
 				FileUtil::get_path_with_extension(PathBuf::from("test.*"), &format);
@@ -649,9 +1135,140 @@ mod tests {
 			app.analyze_image()?;
 			fs::remove_file(path)?;
 		}
+		for (format, magic) in [
+			(FileFormat::Avif, &b"ftypavif"[..]),
+			(FileFormat::Icns, b"icns"),
+			(FileFormat::Pdf, b"%PDF-1.4"),
+		] {
+			settings.save.file.format = format;
+			let app = App::new(Some(window), &settings);
+			let mut output = Cursor::new(Vec::new());
+			app.save_output((app.get_image().ok(), None), &mut output)?;
+			assert!(output.get_ref().windows(magic.len()).any(|v| v == magic));
+		}
 		settings.save.file.path = PathBuf::from("test");
 		App::new(Some(window), &settings).start()?;
-		fs::remove_file(settings.save.file.path)?;
+		fs::remove_file(&settings.save.file.path)?;
+		settings.save.file.format = FileFormat::Png;
+		App::new(Some(window), &settings).start()?;
+		let metadata = ImageMetadata::read(&fs::read(&settings.save.file.path)?);
+		assert_eq!(
+			Some(String::from("Test Window")),
+			metadata.and_then(|metadata| metadata.title)
+		);
+		fs::remove_file(&settings.save.file.path)?;
+		Ok(())
+	}
+	#[test]
+	fn test_app_metadata() -> AppResult<()> {
+		let args = Args::default();
+		let matches = ArgMatches::new(&args);
+		let mut settings = AppSettings::new(&matches);
+		settings.save.file.format = FileFormat::Png;
+		settings.save.file.path = PathBuf::from("test_metadata.png");
+		App::new(Some(TestWindow::default()), &settings).start()?;
+		for (edit_args, path) in [
+			(
+				vec!["--set-tag", "Artist=menyoki", "--remove-tag", "title"],
+				"test_metadata_edit.png",
+			),
+			(vec!["--strip-metadata"], "test_metadata_edit.jpg"),
+		] {
+			let args = crate::args::Args::get_app().get_matches_from(
+				[
+					vec!["menyoki", "edit", "test_metadata.png", "--convert"],
+					edit_args,
+				]
+				.concat()
+				.into_iter()
+				.chain(if path.ends_with("jpg") {
+					vec!["jpg", "save", path]
+				} else {
+					vec!["png", "save", path]
+				}),
+			);
+			let matches = ArgMatches::new(&args);
+			let settings = AppSettings::new(&matches);
+			App::<TestWindow>::new(None, &settings).start()?;
+			let analyze_settings = AnalyzeSettings::new(
+				PathBuf::from(path),
+				Color::White,
+				TimeZone::Utc(false),
+			);
+			let report = ImageAnalyzer::new(&analyze_settings)?.get_report();
+			if settings.edit.metadata.strip {
+				assert!(report.contains("  Software: menyoki"));
+				assert!(!report.contains("Geometry"));
+			} else {
+				assert!(report.contains("  Artist: menyoki"));
+				assert!(report.contains("  Geometry: "));
+				assert!(!report.contains("Test Window"));
+			}
+			fs::remove_file(path)?;
+		}
+		fs::remove_file(&settings.save.file.path)?;
+		let fields = [
+			(Tag::Make, Value::Ascii(vec![b"Camera".to_vec()])),
+			(Tag::GPSLatitudeRef, Value::Ascii(vec![b"N".to_vec()])),
+			(
+				Tag::GPSLatitude,
+				Value::Rational(vec![(52, 1).into(), (30, 1).into(), (0, 1).into()]),
+			),
+		]
+		.map(|(tag, value)| Field {
+			tag,
+			ifd_num: In::PRIMARY,
+			value,
+		});
+		let mut writer = ExifWriter::new();
+		fields.iter().for_each(|field| writer.push_field(field));
+		let mut exif = Cursor::new(Vec::new());
+		writer
+			.write(&mut exif, true)
+			.map_err(|e| AppError::Io(io::Error::other(e)))?;
+		let mut segment = vec![0xFF, 0xE1];
+		segment.extend((8 + exif.get_ref().len() as u16).to_be_bytes());
+		segment.extend(b"Exif\0\0");
+		segment.extend(exif.into_inner());
+		let mut data = Vec::new();
+		JpegEncoder::new(&mut data).write_image(
+			&[0; 4 * 3 * 3],
+			4,
+			3,
+			ColorType::Rgb8,
+		)?;
+		data.splice(2..2, segment);
+		fs::write("test_camera.jpg", data)?;
+		for edit_args in [
+			vec![],
+			vec!["--remove-tag", "GPSLatitude"],
+			vec!["--strip-metadata"],
+		] {
+			let args = crate::args::Args::get_app().get_matches_from(
+				[
+					vec!["menyoki", "edit", "test_camera.jpg"],
+					edit_args.clone(),
+					vec!["--convert", "jpg", "save", "test_camera_edit.jpg"],
+				]
+				.concat(),
+			);
+			let matches = ArgMatches::new(&args);
+			let settings = AppSettings::new(&matches);
+			App::<TestWindow>::new(None, &settings).start()?;
+			let analyze_settings = AnalyzeSettings::new(
+				PathBuf::from("test_camera_edit.jpg"),
+				Color::White,
+				TimeZone::Utc(false),
+			);
+			let report = ImageAnalyzer::new(&analyze_settings)?.get_report();
+			assert_eq!(!settings.edit.metadata.strip, report.contains("Camera"));
+			assert_eq!(
+				edit_args.is_empty(),
+				report.contains("  GPSLatitude: 52 deg 30 min 0 sec")
+			);
+			fs::remove_file("test_camera_edit.jpg")?;
+		}
+		fs::remove_file("test_camera.jpg")?;
 		Ok(())
 	}
 	#[test]
@@ -665,8 +1282,20 @@ mod tests {
 		let window = TestWindow::default();
 		let app = App::new(Some(window), &settings);
 		let images = app.get_frames()?.0;
-		app.save_gif(Some((images.clone(), 10)), File::create("test.gif")?)?;
+		app.save_gif(
+			Some((images.clone(), 10)),
+			File::create("test.gif")?,
+			&settings.anim,
+		)?;
 		app.edit_anim(File::open("test.gif")?, Path::new("test.gif"))?;
+		assert!(FileFormat::is_animated_file(Path::new("test.gif")));
+		app.save_gif(
+			Some((images[..1].to_vec(), 10)),
+			File::create("still.gif")?,
+			&settings.anim,
+		)?;
+		assert!(!FileFormat::is_animated_file(Path::new("still.gif")));
+		fs::remove_file("still.gif")?;
 		let dir = env::current_dir()?;
 		settings.split.dir = PathBuf::from(dir.to_str().unwrap_or_default());
 		settings.split.file = PathBuf::from("test.gif");
@@ -674,14 +1303,49 @@ mod tests {
 		let app = App::new(Some(window), &settings);
 		app.split_anim(File::open("test.gif")?)?;
 		fs::remove_file("test.gif")?;
-		app.save_apng(Some((images.clone(), 20)), File::create("test.apng")?)?;
+		app.save_apng(
+			Some((images.clone(), 20)),
+			File::create("test.apng")?,
+			&settings.anim,
+		)?;
+		assert!(FileFormat::is_animated_file(Path::new("test.apng")));
 		fs::remove_file("test.apng")?;
+		app.save_y4m(Some((images.clone(), 20)), io::sink())?;
+		app.save_webp(
+			Some((images.clone(), 20)),
+			File::create("test.webp")?,
+			&settings.anim,
+			&settings.webp,
+		)?;
+		app.edit_anim(File::open("test.webp")?, Path::new("test.webp"))?;
+		fs::remove_file("test.webp")?;
+		app.save_tiff(Some((images.clone(), 20)), File::create("test.tiff")?)?;
+		assert!(FileFormat::is_animated_file(Path::new("test.tiff")));
+		assert_eq!(
+			images.len(),
+			app.edit_anim(File::open("test.tiff")?, Path::new("test.tiff"))?
+				.0
+				.len()
+		);
+		fs::remove_file("test.tiff")?;
 		for i in 0..images.len() {
 			let path = PathBuf::from(format!("frame_{i}.png"));
 			if path.exists() {
 				fs::remove_file(path)?;
 			}
 		}
+		settings.save.file.format = FileFormat::Gif;
+		for format in [
+			FileFormat::Rgba,
+			FileFormat::Png,
+			FileFormat::Qoi,
+			FileFormat::Y4m,
+		] {
+			App::new(Some(window), &settings).pipe_output(
+				"test \"$MENYOKI_WIDTH\" -gt 0 && cat > /dev/null",
+				&format,
+			)?;
+		}
 		Ok(())
 	}
 }
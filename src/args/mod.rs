@@ -1,9 +1,20 @@
 pub mod matches;
 pub mod parser;
 use crate::anim::{AnimFormat, AnimMode};
+use crate::edit::adjust::{Curve, Levels};
+use crate::edit::annotate::parse_color;
+use crate::edit::border::Extend;
+use crate::edit::canvas::{Background, Shadow};
+use crate::edit::convolve::{Kernel, Unsharpen};
+use crate::edit::overlay::Overlay;
+use crate::edit::pipeline::{Operation, Pipeline};
+use crate::edit::redact::Redaction;
 use crate::file::format::FileFormat;
+use crate::file::metadata::ImageMetadata;
+use bytesize::ByteSize;
 use clap::{App, AppSettings, Arg, ArgMatches, Shell, SubCommand};
 use std::io::Write;
+use std::path::Path;
 use std::str::FromStr;
 
 /* Help template for the main arguments */
@@ -90,7 +101,7 @@ where
 	 *
 	 * @return App
 	 */
-	fn get_app() -> App<'a, 'b> {
+	pub(crate) fn get_app() -> App<'a, 'b> {
 		let args = Self::init();
 		App::new(env!("CARGO_PKG_NAME"))
 			.version(env!("CARGO_PKG_VERSION"))
@@ -152,6 +163,22 @@ where
 						Self::get_anim_args(AnimMode::Record(AnimFormat::Apng))
 							.subcommand(Self::get_save_args(FileFormat::Apng)),
 					)
+					.subcommand(
+						Self::get_anim_args(AnimMode::Record(AnimFormat::WebP))
+							.subcommand(Self::get_save_args(FileFormat::AnimWebP)),
+					)
+					.subcommand(
+						Self::get_anim_args(AnimMode::Record(AnimFormat::Tiff))
+							.subcommand(Self::get_save_args(FileFormat::AnimTiff)),
+					)
+					.subcommand(
+						Self::get_anim_args(AnimMode::Record(AnimFormat::Y4m))
+							.subcommand(Self::get_save_args(FileFormat::Y4m)),
+					)
+					.subcommand(
+						Self::get_anim_args(AnimMode::Record(AnimFormat::Rgba))
+							.subcommand(Self::get_save_args(FileFormat::Rgba)),
+					)
 					.subcommand(Self::get_save_args(FileFormat::Gif)),
 			)
 			.subcommand(Self::get_image_args(args.split, false))
@@ -166,6 +193,14 @@ where
 					.subcommand(
 						Self::get_anim_args(AnimMode::Edit(AnimFormat::Apng))
 							.subcommand(Self::get_save_args(FileFormat::Apng)),
+					)
+					.subcommand(
+						Self::get_anim_args(AnimMode::Edit(AnimFormat::Y4m))
+							.subcommand(Self::get_save_args(FileFormat::Y4m)),
+					)
+					.subcommand(
+						Self::get_anim_args(AnimMode::Edit(AnimFormat::Rgba))
+							.subcommand(Self::get_save_args(FileFormat::Rgba)),
 					),
 				true,
 			))
@@ -361,6 +396,8 @@ where
 					})
 					.takes_value(true),
 			)
+			.arg(Self::get_redact_arg())
+			.args(&Self::get_overlay_args())
 	}
 
 	/**
@@ -393,10 +430,110 @@ where
 					.short("q")
 					.long("quality")
 					.value_name("QUALITY")
-					.default_value("75")
+					.default_value(if mode.has_format(AnimFormat::WebP) {
+						"80"
+					} else {
+						"75"
+					})
 					.help("Set the frame quality (1-100)")
 					.takes_value(true)
-					.hidden(mode.has_format(AnimFormat::Apng)),
+					.hidden(mode.has_any_format(&[
+						AnimFormat::Apng,
+						AnimFormat::Tiff,
+						AnimFormat::Y4m,
+						AnimFormat::Rgba,
+					])),
+			)
+			.arg(
+				Arg::with_name("lossy")
+					.long("lossy")
+					.value_name("LEVEL")
+					.default_value("0")
+					.help("Set the lossy LZW compression level (0-200)")
+					.validator(|lossy| match lossy.parse::<u32>() {
+						Ok(lossy) if lossy <= 200 => Ok(()),
+						_ => Err(String::from("Expected a value between 0 and 200")),
+					})
+					.takes_value(true)
+					.hidden(mode.has_any_format(&[
+						AnimFormat::Apng,
+						AnimFormat::WebP,
+						AnimFormat::Tiff,
+						AnimFormat::Y4m,
+						AnimFormat::Rgba,
+					])),
+			)
+			.arg(
+				Arg::with_name("lossless")
+					.short("l")
+					.long("lossless")
+					.help("Use lossless encoding (WebP)")
+					.hidden(mode.has_any_format(&[
+						AnimFormat::Gif,
+						AnimFormat::Apng,
+						AnimFormat::Tiff,
+						AnimFormat::Y4m,
+						AnimFormat::Rgba,
+					])),
+			)
+			.arg(
+				Arg::with_name("keyframe")
+					.long("keyframe")
+					.value_name("FRAMES")
+					.default_value("0")
+					.help("Set the maximum distance between key frames (WebP)")
+					.takes_value(true)
+					.hidden(mode.has_any_format(&[
+						AnimFormat::Gif,
+						AnimFormat::Apng,
+						AnimFormat::Tiff,
+						AnimFormat::Y4m,
+						AnimFormat::Rgba,
+					])),
+			)
+			.arg(
+				Arg::with_name("compression")
+					.long("compression")
+					.value_name("COMPRESSION")
+					.possible_values(&["default", "fast", "best"])
+					.default_value("default")
+					.help("Set the compression level (APNG)")
+					.takes_value(true)
+					.hidden(mode.has_any_format(&[
+						AnimFormat::Gif,
+						AnimFormat::WebP,
+						AnimFormat::Tiff,
+						AnimFormat::Y4m,
+						AnimFormat::Rgba,
+					])),
+			)
+			.arg(
+				Arg::with_name("filter")
+					.long("filter")
+					.value_name("FILTER")
+					.possible_values(&["none", "sub", "up", "avg", "paeth"])
+					.default_value("sub")
+					.help("Set the filter algorithm (APNG)")
+					.takes_value(true)
+					.hidden(mode.has_any_format(&[
+						AnimFormat::Gif,
+						AnimFormat::WebP,
+						AnimFormat::Tiff,
+						AnimFormat::Y4m,
+						AnimFormat::Rgba,
+					])),
+			)
+			.arg(
+				Arg::with_name("palette")
+					.long("palette")
+					.help("Use indexed colors if possible (APNG)")
+					.hidden(mode.has_any_format(&[
+						AnimFormat::Gif,
+						AnimFormat::WebP,
+						AnimFormat::Tiff,
+						AnimFormat::Y4m,
+						AnimFormat::Rgba,
+					])),
 			)
 			.arg(
 				Arg::with_name("repeat")
@@ -405,15 +542,25 @@ where
 					.value_name("REPEAT")
 					.default_value("\u{221E}")
 					.help("Set the number of repetitions")
-					.takes_value(true),
+					.takes_value(true)
+					.hidden(mode.has_any_format(&[
+						AnimFormat::Tiff,
+						AnimFormat::Y4m,
+						AnimFormat::Rgba,
+					])),
 			)
 			.arg(
 				Arg::with_name("gifski")
 					.long("gifski")
 					.help("Use the gifski encoder")
 					.hidden(
-						mode.has_format(AnimFormat::Apng)
-							|| cfg!(not(feature = "ski")),
+						mode.has_any_format(&[
+							AnimFormat::Apng,
+							AnimFormat::WebP,
+							AnimFormat::Tiff,
+							AnimFormat::Y4m,
+							AnimFormat::Rgba,
+						]) || cfg!(not(feature = "ski")),
 					),
 			)
 			.arg(
@@ -421,8 +568,13 @@ where
 					.long("fast")
 					.help("Encode 3 times faster (gifski)")
 					.hidden(
-						mode.has_format(AnimFormat::Apng)
-							|| cfg!(not(feature = "ski")),
+						mode.has_any_format(&[
+							AnimFormat::Apng,
+							AnimFormat::WebP,
+							AnimFormat::Tiff,
+							AnimFormat::Y4m,
+							AnimFormat::Rgba,
+						]) || cfg!(not(feature = "ski")),
 					),
 			)
 			.arg(
@@ -486,11 +638,119 @@ where
 					.long("format")
 					.value_name("FORMAT")
 					.help("Set the animation format")
-					.possible_values(&["gif", "apng"])
+					.possible_values(&[
+						"gif", "apng", "webp", "tiff", "y4m", "rgba", "pdf",
+					])
 					.default_value("gif")
 					.hidden(mode != AnimMode::Make)
 					.takes_value(true),
 			)
+			.arg(
+				Arg::with_name("jpg")
+					.long("jpg")
+					.help("Embed the frames as JPEG (PDF)")
+					.hidden(mode != AnimMode::Make),
+			)
+			.arg(Self::get_recipe_arg().hidden(mode != AnimMode::Make))
+	}
+
+	/**
+	 * Get the argument for loading the operations from a recipe file.
+	 *
+	 * @return Arg
+	 */
+	fn get_recipe_arg() -> Arg<'a, 'b> {
+		Arg::with_name("recipe")
+			.long("recipe")
+			.value_name("FILE")
+			.help("Apply the operations from a recipe file")
+			.validator(|path| Pipeline::load(Path::new(&path)).map(|_| ()))
+			.takes_value(true)
+	}
+
+	/**
+	 * Get the argument for redacting the areas of the image.
+	 *
+	 * @return Arg
+	 */
+	fn get_redact_arg() -> Arg<'a, 'b> {
+		Arg::with_name("redact")
+			.long("redact")
+			.value_name("WxH+X+Y[:MODE]")
+			.help("Hide an area of the image [pixelate, blur, fill]")
+			.multiple(true)
+			.number_of_values(1)
+			.validator(|value| Redaction::parse(&value).map(|_| ()))
+			.takes_value(true)
+	}
+
+	/**
+	 * Get the arguments for blending an overlay image.
+	 *
+	 * @return Vector of Arg
+	 */
+	fn get_overlay_args() -> Vec<Arg<'a, 'b>> {
+		vec![
+			Arg::with_name("overlay")
+				.long("overlay")
+				.value_name("FILE")
+				.help("Blend an image (e.g. logo) onto the image")
+				.validator(|path| {
+					if Path::new(&path).is_file() {
+						Ok(())
+					} else {
+						Err(format!("File not found: {path:?}"))
+					}
+				})
+				.takes_value(true),
+			Arg::with_name("gravity")
+				.long("gravity")
+				.value_name("GRAVITY")
+				.possible_values(&[
+					"northwest",
+					"north",
+					"northeast",
+					"west",
+					"center",
+					"east",
+					"southwest",
+					"south",
+					"southeast",
+				])
+				.default_value("southeast")
+				.help("Set the position of the overlay")
+				.takes_value(true),
+			Arg::with_name("offset")
+				.long("offset")
+				.value_name("X,Y")
+				.default_value("0,0")
+				.help("Set the offset of the overlay from the edges")
+				.allow_hyphen_values(true)
+				.validator(|offset| match Overlay::parse_offset(&offset) {
+					Some(_) => Ok(()),
+					None => Err(String::from("Expected X,Y")),
+				})
+				.takes_value(true),
+			Arg::with_name("opacity")
+				.long("opacity")
+				.value_name("OPACITY")
+				.default_value("1.0")
+				.help("Set the opacity of the overlay")
+				.validator(|opacity| match opacity.parse::<f32>() {
+					Ok(opacity) if (0. ..=1.).contains(&opacity) => Ok(()),
+					_ => Err(String::from("Expected a value between 0 and 1")),
+				})
+				.takes_value(true),
+			Arg::with_name("scale")
+				.long("scale")
+				.value_name("SCALE")
+				.help("Scale the overlay relative to the image width")
+				.validator(|scale| match scale.parse::<f32>() {
+					Ok(scale) if scale > 0. => Ok(()),
+					_ => Err(String::from("Expected a positive value")),
+				})
+				.takes_value(true),
+		]
 	}
 
 	/**
@@ -516,46 +776,132 @@ where
 			.arg(
 				Arg::with_name("grayscale")
 					.long("grayscale")
+					.multiple(true)
 					.help("Convert image to grayscale"),
 			)
 			.arg(
 				Arg::with_name("invert")
 					.long("invert")
+					.multiple(true)
 					.help("Invert the colors of the image"),
 			)
+			.arg(
+				Arg::with_name("trim")
+					.long("trim")
+					.multiple(true)
+					.min_values(0)
+					.require_equals(true)
+					.value_name("FUZZ")
+					.help("Trim the uniform or transparent borders")
+					.validator(|fuzz| match fuzz.parse::<f32>() {
+						Ok(fuzz) if (0. ..=100.).contains(&fuzz) => Ok(()),
+						_ => Err(String::from("Expected a value between 0 and 100")),
+					})
+					.takes_value(true),
+			)
 			.arg(
 				Arg::with_name("crop")
 					.long("crop")
+					.multiple(true)
+					.number_of_values(1)
 					.value_name("T:R:B:L")
 					.help("Apply padding to crop the image")
 					.takes_value(true),
 			)
+			.arg(
+				Arg::with_name("extend")
+					.long("extend")
+					.multiple(true)
+					.number_of_values(1)
+					.value_name("T:R:B:L[:HEX]")
+					.help("Add margins around the image")
+					.validator(|value| Extend::parse(&value).map(|_| ()))
+					.takes_value(true),
+			)
 			.arg(
 				Arg::with_name("resize")
 					.long("resize")
+					.multiple(true)
+					.number_of_values(1)
 					.value_name("WxH")
-					.help("Resize the image without keeping the aspect ratio")
+					.help("Resize the image (W or H can be omitted)")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("resize-mode")
+					.long("resize-mode")
+					.value_name("MODE")
+					.possible_values(&["exact", "fit", "fill", "cover"])
+					.default_value("exact")
+					.help("Set the aspect ratio mode for resizing")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("letterbox")
+					.long("letterbox")
+					.value_name("HEX")
+					.help("Fill the resized area with a color (fit mode)")
+					.validator(|color| match parse_color(&color) {
+						Some(_) => Ok(()),
+						None => Err(String::from("Expected a color in hex")),
+					})
 					.takes_value(true),
 			)
 			.arg(
 				Arg::with_name("ratio")
 					.long("ratio")
+					.multiple(true)
+					.number_of_values(1)
 					.value_name("RATIO")
 					.default_value("1.0")
 					.help("Resize the image proportionally by aspect ratio")
 					.takes_value(true),
 			)
+			.arg(
+				Arg::with_name("max-dimensions")
+					.long("max-dimensions")
+					.multiple(true)
+					.number_of_values(1)
+					.value_name("WxH")
+					.help("Shrink the image to fit in the maximum size")
+					.takes_value(true),
+			)
 			.arg(
 				Arg::with_name("rotate")
 					.long("rotate")
-					.value_name("ROTATE")
+					.multiple(true)
+					.number_of_values(1)
+					.value_name("DEGREES")
 					.help("Rotate the image (clockwise)")
-					.possible_values(&["90", "180", "270"])
+					.validator(|angle| match angle.parse::<f32>() {
+						Ok(angle) if angle.is_finite() => Ok(()),
+						_ => Err(String::from("Expected an angle in degrees")),
+					})
+					.allow_hyphen_values(true)
 					.takes_value(true),
 			)
+			.arg(
+				Arg::with_name("rotate-fill")
+					.long("rotate-fill")
+					.value_name("HEX")
+					.default_value("00000000")
+					.help("Set the background color for rotating")
+					.validator(|color| match parse_color(&color) {
+						Some(_) => Ok(()),
+						None => Err(String::from("Expected a color in hex")),
+					})
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("rotate-crop")
+					.long("rotate-crop")
+					.help("Crop the rotated image to remove the background"),
+			)
 			.arg(
 				Arg::with_name("flip")
 					.long("flip")
+					.multiple(true)
+					.number_of_values(1)
 					.value_name("FLIP")
 					.help("Flip the image")
 					.possible_values(&["horizontal", "vertical"])
@@ -564,14 +910,57 @@ where
 			.arg(
 				Arg::with_name("blur")
 					.long("blur")
+					.multiple(true)
+					.number_of_values(1)
 					.value_name("SIGMA")
 					.default_value("0.0")
 					.help("Blur the image")
 					.takes_value(true),
 			)
+			.arg(
+				Arg::with_name("sharpen")
+					.long("sharpen")
+					.multiple(true)
+					.help("Sharpen the image"),
+			)
+			.arg(
+				Arg::with_name("unsharpen")
+					.long("unsharpen")
+					.multiple(true)
+					.number_of_values(1)
+					.value_name("SIGMA:THRESHOLD")
+					.help("Sharpen the image with an unsharp mask")
+					.validator(|value| Unsharpen::parse(&value).map(|_| ()))
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("kernel")
+					.long("kernel")
+					.multiple(true)
+					.number_of_values(1)
+					.value_name("WxH:VALUES")
+					.help("Apply a custom convolution matrix to the image")
+					.validator(|value| Kernel::parse(&value).map(|_| ()))
+					.allow_hyphen_values(true)
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("edges")
+					.long("edges")
+					.multiple(true)
+					.help("Detect the edges of the image"),
+			)
+			.arg(
+				Arg::with_name("emboss")
+					.long("emboss")
+					.multiple(true)
+					.help("Emboss the image"),
+			)
 			.arg(
 				Arg::with_name("hue")
 					.long("hue")
+					.multiple(true)
+					.number_of_values(1)
 					.value_name("HUE")
 					.default_value("\u{00B1}0")
 					.help("Adjust the hue of the image")
@@ -581,6 +970,8 @@ where
 			.arg(
 				Arg::with_name("contrast")
 					.long("contrast")
+					.multiple(true)
+					.number_of_values(1)
 					.value_name("CONTRAST")
 					.default_value("\u{00B1}0.0")
 					.help("Adjust the contrast of the image")
@@ -590,12 +981,124 @@ where
 			.arg(
 				Arg::with_name("brightness")
 					.long("brightness")
+					.multiple(true)
+					.number_of_values(1)
 					.value_name("BRIGHTNESS")
 					.default_value("\u{00B1}0")
 					.help("Adjust the brightness of the image")
 					.allow_hyphen_values(true)
 					.takes_value(true),
 			)
+			.arg(
+				Arg::with_name("normalize")
+					.long("normalize")
+					.multiple(true)
+					.help("Stretch the colors of the image to the full range"),
+			)
+			.arg(
+				Arg::with_name("levels")
+					.long("levels")
+					.multiple(true)
+					.number_of_values(1)
+					.value_name("BLACK:WHITE")
+					.help("Set the black and white points of the image")
+					.validator(|value| Levels::parse(&value).map(|_| ()))
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("gamma")
+					.long("gamma")
+					.multiple(true)
+					.number_of_values(1)
+					.value_name("GAMMA")
+					.default_value("1.0")
+					.help("Adjust the gamma of the image")
+					.validator(|gamma| match gamma.parse::<f32>() {
+						Ok(gamma) if gamma > 0. => Ok(()),
+						_ => Err(String::from("Expected a positive value")),
+					})
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("curve")
+					.long("curve")
+					.multiple(true)
+					.number_of_values(1)
+					.value_name("IN:OUT,...")
+					.help("Map the colors of the image with a tone curve")
+					.validator(|value| Curve::parse(&value).map(|_| ()))
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("temperature")
+					.long("temperature")
+					.multiple(true)
+					.number_of_values(1)
+					.value_name("TEMPERATURE")
+					.default_value("\u{00B1}0")
+					.help("Adjust the color temperature of the image")
+					.validator(|value| match value.parse::<f32>() {
+						Ok(_) => Ok(()),
+						Err(_) if value == "\u{00B1}0" => Ok(()),
+						Err(_) => Err(String::from("Expected a number")),
+					})
+					.allow_hyphen_values(true)
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("tint")
+					.long("tint")
+					.multiple(true)
+					.number_of_values(1)
+					.value_name("TINT")
+					.default_value("\u{00B1}0")
+					.help("Adjust the tint of the image")
+					.validator(|value| match value.parse::<f32>() {
+						Ok(_) => Ok(()),
+						Err(_) if value == "\u{00B1}0" => Ok(()),
+						Err(_) => Err(String::from("Expected a number")),
+					})
+					.allow_hyphen_values(true)
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("saturation")
+					.long("saturation")
+					.multiple(true)
+					.number_of_values(1)
+					.value_name("SATURATION")
+					.default_value("\u{00B1}0")
+					.help("Adjust the saturation of the image")
+					.validator(|value| match value.parse::<f32>() {
+						Ok(_) => Ok(()),
+						Err(_) if value == "\u{00B1}0" => Ok(()),
+						Err(_) => Err(String::from("Expected a number")),
+					})
+					.allow_hyphen_values(true)
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("vibrance")
+					.long("vibrance")
+					.multiple(true)
+					.number_of_values(1)
+					.value_name("VIBRANCE")
+					.default_value("\u{00B1}0")
+					.help("Adjust the saturation of the less saturated colors")
+					.validator(|value| match value.parse::<f32>() {
+						Ok(_) => Ok(()),
+						Err(_) if value == "\u{00B1}0" => Ok(()),
+						Err(_) => Err(String::from("Expected a number")),
+					})
+					.allow_hyphen_values(true)
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("sepia")
+					.long("sepia")
+					.multiple(true)
+					.help("Apply the sepia tone to the image"),
+			)
 			.arg(
 				Arg::with_name("filter")
 					.long("filter")
@@ -608,7 +1111,111 @@ where
 						"lanczos3",
 					])
 					.default_value("lanczos3")
-					.help("Set the sampling filter for scaling")
+					.help("Set the sampling filter for scaling and rotating")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("round")
+					.long("round")
+					.multiple(true)
+					.number_of_values(1)
+					.value_name("RADIUS")
+					.help("Round the corners of the image")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("shadow")
+					.long("shadow")
+					.multiple(true)
+					.number_of_values(1)
+					.value_name("SIGMA[:X:Y[:HEX]]")
+					.help("Add a drop shadow to the image")
+					.validator(|value| Shadow::parse(&value).map(|_| ()))
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("canvas")
+					.long("canvas")
+					.multiple(true)
+					.number_of_values(1)
+					.value_name("PADDING[,HEX[,HEX]]")
+					.help("Place the image on a solid or gradient background")
+					.validator(|value| Background::parse(&value).map(|_| ()))
+					.takes_value(true),
+			)
+			.arg(Self::get_redact_arg())
+			.args(&Self::get_overlay_args())
+			.args(
+				&[
+					("rect", "WxH+X+Y [K=V]..", "Draw a rectangle"),
+					("ellipse", "WxH+X+Y [K=V]..", "Draw an ellipse"),
+					("line", "WxH+X+Y [K=V]..", "Draw a line to X+W,Y+H"),
+					("arrow", "WxH+X+Y [K=V]..", "Draw an arrow to X+W,Y+H"),
+					("step", "+X+Y [K=V]..", "Draw a numbered step marker"),
+					("text", "+X+Y [K=V].. TEXT", "Draw a text label"),
+				]
+				.iter()
+				.map(|(name, value_name, help)| {
+					Arg::with_name(name)
+						.long(name)
+						.multiple(true)
+						.number_of_values(1)
+						.value_name(value_name)
+						.help(help)
+						.allow_hyphen_values(true)
+						.validator(move |value| {
+							Operation::parse(name, Some(&value)).map(|_| ())
+						})
+						.takes_value(true)
+				})
+				.collect::<Vec<Arg<'a, 'b>>>(),
+			)
+			.arg(
+				Arg::with_name("pipeline")
+					.long("pipeline")
+					.help("Apply the operations in the order given"),
+			)
+			.arg(Self::get_recipe_arg())
+			.arg(
+				Arg::with_name("save-recipe")
+					.long("save-recipe")
+					.value_name("FILE")
+					.help("Save the operations to a recipe file")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("no-auto-orient")
+					.long("no-auto-orient")
+					.help("Do not rotate the image by its EXIF orientation"),
+			)
+			.arg(
+				Arg::with_name("strip-metadata")
+					.long("strip-metadata")
+					.help("Remove the metadata (EXIF, XMP, ICC, text) of the image"),
+			)
+			.arg(
+				Arg::with_name("set-tag")
+					.long("set-tag")
+					.value_name("KEY=VALUE")
+					.help("Set a metadata tag")
+					.multiple(true)
+					.number_of_values(1)
+					.validator(|value| match value.split_once('=') {
+						Some((key, _)) if ImageMetadata::is_valid_key(key) => Ok(()),
+						Some(_) => Err(String::from(
+							"Expected a key of 1-79 printable Latin-1 characters",
+						)),
+						_ => Err(String::from("Expected a KEY=VALUE pair")),
+					})
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("remove-tag")
+					.long("remove-tag")
+					.value_name("KEY")
+					.help("Remove a metadata tag")
+					.multiple(true)
+					.number_of_values(1)
 					.takes_value(true),
 			)
 	}
@@ -693,16 +1300,24 @@ where
 					.long("transparent")
 					.help("Display transparent image with transparent background"),
 			)
+			.arg(
+				Arg::with_name("no-auto-orient")
+					.long("no-auto-orient")
+					.help("Do not rotate the image by its EXIF orientation"),
+			)
 	}
 
 	/**
 	 * Add image related subcommands to the given arguments.
 	 *
+	 * The WebP encoder of the edit subcommand also accepts animations.
+	 *
 	 * @param  app
 	 * @param  save
 	 * @return App
 	 */
 	fn get_image_args(app: App<'a, 'b>, save: bool) -> App<'a, 'b> {
+		let edit = app.get_name() == "edit";
 		let mut save_settings = Vec::new();
 		if !save {
 			save_settings.push(AppSettings::Hidden);
@@ -731,6 +1346,20 @@ where
 						.help("Set the filter algorithm")
 						.takes_value(true),
 				)
+				.arg(
+					Arg::with_name("colors")
+						.short("n")
+						.long("colors")
+						.value_name("COLORS")
+						.help("Use indexed colors with a palette size (2-256)")
+						.takes_value(true),
+				)
+				.arg(
+					Arg::with_name("optimize")
+						.short("o")
+						.long("optimize")
+						.help("Try all filters and compression levels"),
+				)
 				.subcommand(
 					Self::get_save_args(FileFormat::Png).settings(&save_settings),
 				),
@@ -752,7 +1381,11 @@ where
 					Self::get_save_args(FileFormat::Jpg).settings(&save_settings),
 				),
 		)
-		.subcommand(
+		.subcommand(if edit {
+			Self::get_anim_args(AnimMode::Edit(AnimFormat::WebP)).subcommand(
+				Self::get_save_args(FileFormat::WebP).settings(&save_settings),
+			)
+		} else {
 			SubCommand::with_name("webp")
 				.about("Use the WebP encoder")
 				.help_message("Print help information")
@@ -766,13 +1399,39 @@ where
 						.takes_value(true),
 				)
 				.arg(
-					Arg::with_name("losless")
+					Arg::with_name("lossless")
 						.short("l")
 						.long("lossless")
 						.help("Use lossless encoding"),
 				)
 				.subcommand(
 					Self::get_save_args(FileFormat::WebP).settings(&save_settings),
+				)
+		})
+		.subcommand(
+			SubCommand::with_name("avif")
+				.about("Use the AVIF encoder")
+				.help_message("Print help information")
+				.arg(
+					Arg::with_name("quality")
+						.short("q")
+						.long("quality")
+						.value_name("QUALITY")
+						.default_value("80")
+						.help("Set the image quality (1-100)")
+						.takes_value(true),
+				)
+				.arg(
+					Arg::with_name("speed")
+						.short("s")
+						.long("speed")
+						.value_name("SPEED")
+						.default_value("6")
+						.help("Set the encoding speed (1-10)")
+						.takes_value(true),
+				)
+				.subcommand(
+					Self::get_save_args(FileFormat::Avif).settings(&save_settings),
 				),
 		)
 		.subcommand(
@@ -787,18 +1446,48 @@ where
 			SubCommand::with_name("ico")
 				.about("Use the ICO encoder")
 				.help_message("Print help information")
+				.arg(
+					Arg::with_name("sizes")
+						.short("s")
+						.long("sizes")
+						.value_name("SIZES")
+						.default_value("16,32,48,256")
+						.help("Set the icon sizes (1-256)")
+						.takes_value(true),
+				)
 				.subcommand(
 					Self::get_save_args(FileFormat::Ico).settings(&save_settings),
 				),
 		)
 		.subcommand(
+			SubCommand::with_name("icns")
+				.about("Use the ICNS encoder")
+				.help_message("Print help information")
+				.arg(
+					Arg::with_name("sizes")
+						.short("s")
+						.long("sizes")
+						.value_name("SIZES")
+						.default_value("16,32,64,128,256,512")
+						.help("Set the icon sizes (16-1024, powers of 2)")
+						.takes_value(true),
+				)
+				.subcommand(
+					Self::get_save_args(FileFormat::Icns).settings(&save_settings),
+				),
+		)
+		.subcommand(if edit {
+			Self::get_anim_args(AnimMode::Edit(AnimFormat::Tiff)).subcommand(
+				Self::get_save_args(FileFormat::Tiff).settings(&save_settings),
+			)
+		} else {
 			SubCommand::with_name("tiff")
 				.about("Use the TIFF encoder")
 				.help_message("Print help information")
 				.subcommand(
 					Self::get_save_args(FileFormat::Tiff).settings(&save_settings),
-				),
-		)
+				)
+		})
 		.subcommand(
 			SubCommand::with_name("tga")
 				.about("Use the TGA encoder")
@@ -857,6 +1546,37 @@ where
 					Self::get_save_args(FileFormat::Exr).settings(&save_settings),
 				),
 		)
+		.subcommand(
+			SubCommand::with_name("qoi")
+				.about("Use the QOI encoder")
+				.help_message("Print help information")
+				.subcommand(
+					Self::get_save_args(FileFormat::Qoi).settings(&save_settings),
+				),
+		)
+		.subcommand(
+			SubCommand::with_name("pdf")
+				.about("Use the PDF encoder")
+				.help_message("Print help information")
+				.arg(
+					Arg::with_name("jpg")
+						.short("j")
+						.long("jpg")
+						.help("Embed the image as JPEG"),
+				)
+				.arg(
+					Arg::with_name("quality")
+						.short("q")
+						.long("quality")
+						.value_name("QUALITY")
+						.default_value("90")
+						.help("Set the JPEG quality (1-100)")
+						.takes_value(true),
+				)
+				.subcommand(
+					Self::get_save_args(FileFormat::Pdf).settings(&save_settings),
+				),
+		)
 		.subcommand(Self::get_save_args(FileFormat::Any).settings(&save_settings))
 	}
 
@@ -867,6 +1587,9 @@ where
 	 * @return App
 	 */
 	fn get_save_args(file_format: FileFormat) -> App<'a, 'b> {
+		let is_animation = file_format.is_animation()
+			|| file_format == FileFormat::WebP
+			|| file_format == FileFormat::Tiff;
 		SubCommand::with_name("save")
 			.about("Save the output file(s)")
 			.help_message("Print help information")
@@ -902,6 +1625,36 @@ where
 					.help("Add formatted date/time to the file name")
 					.takes_value(true),
 			)
+			.arg(
+				Arg::with_name("pipe")
+					.long("pipe")
+					.value_name("COMMAND")
+					.help("Pipe the frames to a command as they are produced")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("pipe-format")
+					.long("pipe-format")
+					.value_name("FORMAT")
+					.possible_values(&["rgba", "png", "qoi", "y4m"])
+					.default_value("rgba")
+					.help("Set the format of the piped frames")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("max-size")
+					.long("max-size")
+					.value_name("SIZE")
+					.help("Fit the animation under the given file size")
+					.validator(|size| ByteSize::from_str(&size).map(|_| ()))
+					.takes_value(true)
+					.hidden(!is_animation),
+			)
+			.arg(
+				Arg::with_name("no-metadata")
+					.long("no-metadata")
+					.help("Do not embed metadata into the file"),
+			)
 	}
 
 	/**
@@ -0,0 +1,387 @@
+use image::{Rgba, RgbaImage};
+use std::fmt;
+
+/**
+ * Get the luma value of the pixel (Rec. 709).
+ *
+ * @param  pixel
+ * @return f32
+ */
+fn get_luma(pixel: &Rgba<u8>) -> f32 {
+	0.2126 * f32::from(pixel[0])
+		+ 0.7152 * f32::from(pixel[1])
+		+ 0.0722 * f32::from(pixel[2])
+}
+
+/* Lookup table for the color channels */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lut {
+	tables: [[u8; 256]; 3],
+}
+
+impl Lut {
+	/**
+	 * Create a new Lut from the given function of channel and value.
+	 *
+	 * @param  f
+	 * @return Lut
+	 */
+	pub fn new<F: Fn(usize, f32) -> f32>(f: F) -> Self {
+		let mut tables = [[0; 256]; 3];
+		for (channel, table) in tables.iter_mut().enumerate() {
+			for (value, entry) in table.iter_mut().enumerate() {
+				*entry = f(channel, value as f32).round().clamp(0., 255.) as u8;
+			}
+		}
+		Self { tables }
+	}
+
+	/**
+	 * Create a Lut for gamma correction.
+	 *
+	 * @param  gamma
+	 * @return Lut
+	 */
+	pub fn gamma(gamma: f32) -> Self {
+		Self::new(|_, value| 255. * (value / 255.).powf(1. / gamma))
+	}
+
+	/**
+	 * Create a Lut for adjusting the color temperature.
+	 *
+	 * Positive values make the image warmer.
+	 *
+	 * @param  temperature (-100 to 100)
+	 * @return Lut
+	 */
+	pub fn temperature(temperature: f32) -> Self {
+		let temperature = temperature.clamp(-100., 100.);
+		Self::new(|channel, value| match channel {
+			0 => value * (1. + temperature / 200.),
+			2 => value * (1. - temperature / 200.),
+			_ => value,
+		})
+	}
+
+	/**
+	 * Create a Lut for adjusting the tint.
+	 *
+	 * Positive values shift the colors to magenta and
+	 * negative values shift them to green.
+	 *
+	 * @param  tint (-100 to 100)
+	 * @return Lut
+	 */
+	pub fn tint(tint: f32) -> Self {
+		let tint = tint.clamp(-100., 100.);
+		Self::new(|channel, value| match channel {
+			1 => value * (1. - tint / 200.),
+			_ => value,
+		})
+	}
+
+	/**
+	 * Create a Lut that stretches the given range of colors to the full range.
+	 *
+	 * The same range is used for all channels to keep the hue.
+	 *
+	 * @param  range
+	 * @return Lut
+	 */
+	pub fn normalize(range: (u8, u8)) -> Self {
+		let (black, white) = range;
+		if black >= white {
+			Self::new(|_, value| value)
+		} else {
+			Levels::new(black, white).get_lut()
+		}
+	}
+
+	/**
+	 * Combine the Lut with another one that is applied after it.
+	 *
+	 * @param  lut
+	 * @return Lut
+	 */
+	pub fn then(&self, lut: &Self) -> Self {
+		let mut tables = self.tables;
+		for (table, next) in tables.iter_mut().zip(lut.tables.iter()) {
+			for entry in table.iter_mut() {
+				*entry = next[usize::from(*entry)];
+			}
+		}
+		Self { tables }
+	}
+
+	/**
+	 * Map the colors of the image.
+	 *
+	 * @param image
+	 */
+	pub fn apply(&self, image: &mut RgbaImage) {
+		for pixel in image.pixels_mut() {
+			for (value, table) in pixel.0.iter_mut().zip(self.tables.iter()) {
+				*value = table[usize::from(*value)];
+			}
+		}
+	}
+}
+
+/* Black and white points of the image */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Levels {
+	pub black: u8,
+	pub white: u8,
+}
+
+/* Display implementation for user-facing output */
+impl fmt::Display for Levels {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}:{}", self.black, self.white)
+	}
+}
+
+impl Levels {
+	/**
+	 * Create a new Levels object.
+	 *
+	 * @param  black
+	 * @param  white
+	 * @return Levels
+	 */
+	pub fn new(black: u8, white: u8) -> Self {
+		Self { black, white }
+	}
+
+	/**
+	 * Parse Levels from `BLACK:WHITE`.
+	 *
+	 * @param  levels
+	 * @return Levels (Result)
+	 */
+	pub fn parse(levels: &str) -> Result<Self, String> {
+		let invalid = || format!("Invalid levels: {levels:?}");
+		let (black, white) = levels.split_once(':').ok_or_else(invalid)?;
+		match (black.trim().parse(), white.trim().parse()) {
+			(Ok(black), Ok(white)) if black < white => Ok(Self::new(black, white)),
+			_ => Err(invalid()),
+		}
+	}
+
+	/**
+	 * Get the Lut that maps the black and white points to 0 and 255.
+	 *
+	 * @return Lut
+	 */
+	pub fn get_lut(&self) -> Lut {
+		let (black, white) = (f32::from(self.black), f32::from(self.white));
+		Lut::new(|_, value| (value - black) * 255. / (white - black))
+	}
+}
+
+/* Tone curve that is interpolated between its points */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Curve {
+	pub points: Vec<(u8, u8)>,
+}
+
+/* Display implementation for user-facing output */
+impl fmt::Display for Curve {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{}",
+			self.points
+				.iter()
+				.map(|(x, y)| format!("{x}:{y}"))
+				.collect::<Vec<String>>()
+				.join(",")
+		)
+	}
+}
+
+impl Curve {
+	/**
+	 * Parse a Curve from `IN:OUT,IN:OUT,...`.
+	 *
+	 * @param  curve
+	 * @return Curve (Result)
+	 */
+	pub fn parse(curve: &str) -> Result<Self, String> {
+		let invalid = || format!("Invalid curve: {curve:?}");
+		let mut points = curve
+			.split(',')
+			.map(|point| {
+				let (x, y) = point.split_once(':')?;
+				Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+			})
+			.collect::<Option<Vec<(u8, u8)>>>()
+			.ok_or_else(invalid)?;
+		points.sort_by_key(|(x, _)| *x);
+		if points.windows(2).any(|points| points[0].0 == points[1].0) {
+			return Err(invalid());
+		}
+		Ok(Self { points })
+	}
+
+	/**
+	 * Get the Lut for the curve.
+	 *
+	 * Curve is extended to the black and white points
+	 * if they are not given.
+	 *
+	 * @return Lut
+	 */
+	pub fn get_lut(&self) -> Lut {
+		let mut points = self
+			.points
+			.iter()
+			.map(|(x, y)| (f32::from(*x), f32::from(*y)))
+			.collect::<Vec<(f32, f32)>>();
+		if points.first().map(|(x, _)| *x) != Some(0.) {
+			points.insert(0, (0., 0.));
+		}
+		if points.last().map(|(x, _)| *x) != Some(255.) {
+			points.push((255., 255.));
+		}
+		Lut::new(|_, value| {
+			match points.windows(2).find(|points| value <= points[1].0) {
+				Some(points) => {
+					let ((x0, y0), (x1, y1)) = (points[0], points[1]);
+					y0 + (value - x0) * (y1 - y0) / (x1 - x0)
+				}
+				None => value,
+			}
+		})
+	}
+}
+
+/**
+ * Get the range of the colors in the image.
+ *
+ * Transparent pixels are not included.
+ *
+ * @param  image
+ * @return black and white points
+ */
+pub fn get_range(image: &RgbaImage) -> (u8, u8) {
+	let (mut black, mut white) = (255, 0);
+	for pixel in image.pixels().filter(|pixel| pixel[3] != 0) {
+		for value in &pixel.0[..3] {
+			black = black.min(*value);
+			white = white.max(*value);
+		}
+	}
+	(black, white)
+}
+
+/**
+ * Adjust the saturation of the image.
+ *
+ * Less saturated colors are adjusted more if `vibrance` is set.
+ *
+ * @param image
+ * @param amount (percentage)
+ * @param vibrance
+ */
+pub fn saturate(image: &mut RgbaImage, amount: f32, vibrance: bool) {
+	let amount = amount.max(-100.);
+	for pixel in image.pixels_mut() {
+		let luma = get_luma(pixel);
+		let factor = if vibrance {
+			let max = pixel.0[..3].iter().max().copied().unwrap_or_default();
+			let min = pixel.0[..3].iter().min().copied().unwrap_or_default();
+			let saturation = f32::from(max - min) / 255.;
+			1. + amount / 100. * (1. - saturation)
+		} else {
+			1. + amount / 100.
+		};
+		for value in pixel.0.iter_mut().take(3) {
+			*value = (luma + (f32::from(*value) - luma) * factor)
+				.round()
+				.clamp(0., 255.) as u8;
+		}
+	}
+}
+
+/**
+ * Apply the sepia tone to the image.
+ *
+ * @param image
+ */
+pub fn sepia(image: &mut RgbaImage) {
+	let lut = Lut::new(|channel, value| value * [1.351, 1.203, 0.937][channel]);
+	for pixel in image.pixels_mut() {
+		let luma = get_luma(pixel).round() as usize;
+		for (channel, table) in lut.tables.iter().enumerate() {
+			pixel[channel] = table[luma.min(255)];
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_adjust() {
+		let mut image = RgbaImage::from_fn(4, 1, |x, _| {
+			let value = (x * 60 + 40) as u8;
+			Rgba([value, value, value, 255 - x as u8])
+		});
+		let original = image.clone();
+		Lut::gamma(2.).apply(&mut image);
+		assert_eq!(Rgba([101, 101, 101, 255]), *image.get_pixel(0, 0));
+		assert_eq!(252, image.get_pixel(3, 0)[3]);
+		let mut image = original.clone();
+		assert_eq!((40, 220), get_range(&image));
+		Lut::normalize(get_range(&image)).apply(&mut image);
+		assert_eq!(0, image.get_pixel(0, 0)[0]);
+		assert_eq!(85, image.get_pixel(1, 0)[0]);
+		assert_eq!(255, image.get_pixel(3, 0)[0]);
+		let levels = Levels::parse("20:200").unwrap();
+		assert_eq!("20:200", levels.to_string());
+		for invalid in ["", "20", "200:20", "0:256", "a:b"] {
+			assert!(Levels::parse(invalid).is_err(), "{invalid}");
+		}
+		let mut image = original.clone();
+		levels.get_lut().apply(&mut image);
+		assert_eq!(28, image.get_pixel(0, 0)[0]);
+		assert_eq!(255, image.get_pixel(3, 0)[0]);
+		let curve = Curve::parse("128:64, 64:32").unwrap();
+		assert_eq!("64:32,128:64", curve.to_string());
+		assert!(Curve::parse("1:1,1:2").is_err());
+		assert!(Curve::parse("1").is_err());
+		let mut image = original.clone();
+		curve.get_lut().apply(&mut image);
+		assert_eq!(20, image.get_pixel(0, 0)[0]);
+		assert_eq!(50, image.get_pixel(1, 0)[0]);
+		assert_eq!(202, image.get_pixel(3, 0)[0]);
+		let mut image = original.clone();
+		Curve::parse("0:255,255:0")
+			.unwrap()
+			.get_lut()
+			.apply(&mut image);
+		assert_eq!(215, image.get_pixel(0, 0)[0]);
+		let mut image = RgbaImage::from_pixel(1, 1, Rgba([100, 100, 100, 255]));
+		let mut combined = image.clone();
+		Lut::temperature(50.).apply(&mut image);
+		Lut::tint(-100.).apply(&mut image);
+		assert_eq!(Rgba([125, 150, 75, 255]), *image.get_pixel(0, 0));
+		Lut::temperature(50.)
+			.then(&Lut::tint(-100.))
+			.apply(&mut combined);
+		assert_eq!(image, combined);
+		saturate(&mut image, -100., false);
+		assert_eq!(Rgba([139, 139, 139, 255]), *image.get_pixel(0, 0));
+		let mut image = RgbaImage::from_pixel(1, 1, Rgba([200, 100, 100, 255]));
+		let mut vibrant = image.clone();
+		saturate(&mut image, 50., false);
+		saturate(&mut vibrant, 50., true);
+		assert_eq!(Rgba([239, 89, 89, 255]), *image.get_pixel(0, 0));
+		assert!(vibrant.get_pixel(0, 0)[0] < image.get_pixel(0, 0)[0]);
+		sepia(&mut vibrant);
+		let pixel = vibrant.get_pixel(0, 0);
+		assert!(pixel[0] > pixel[1] && pixel[1] > pixel[2]);
+	}
+}
@@ -0,0 +1,580 @@
+use embedded_graphics::mono_font::ascii::{
+	FONT_10X20, FONT_6X10, FONT_7X13_BOLD, FONT_9X15_BOLD,
+};
+use embedded_graphics::mono_font::{MonoFont, MonoTextStyle};
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{
+	Circle, Ellipse, Line, PrimitiveStyle, PrimitiveStyleBuilder, Rectangle,
+	StrokeAlignment, StyledDrawable,
+};
+use embedded_graphics::text::{Baseline, Text};
+use image::{Rgba, RgbaImage};
+use std::convert::Infallible;
+use std::fmt;
+
+/* Bundled bitmap fonts (in ascending order of height) */
+const FONTS: &[MonoFont<'_>] =
+	&[FONT_6X10, FONT_7X13_BOLD, FONT_9X15_BOLD, FONT_10X20];
+
+/* Default diameter of the step markers */
+const STEP_DIAMETER: u32 = 28;
+
+/**
+ * Parse a color from `RRGGBB[AA]`.
+ *
+ * @param  color
+ * @return Rgba (Option)
+ */
+pub fn parse_color(color: &str) -> Option<Rgba<u8>> {
+	match hex::decode(color.trim_start_matches('#')).ok()?.as_slice() {
+		[r, g, b] => Some(Rgba([*r, *g, *b, 255])),
+		[r, g, b, a] => Some(Rgba([*r, *g, *b, *a])),
+		_ => None,
+	}
+}
+
+/**
+ * Format the color as `RRGGBB[AA]`.
+ *
+ * @param  color
+ * @return String
+ */
+pub fn format_color(color: Rgba<u8>) -> String {
+	if color[3] == 255 {
+		hex::encode(&color.0[..3])
+	} else {
+		hex::encode(color.0)
+	}
+}
+
+/* Shape of the annotation */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shape {
+	Rectangle,
+	Ellipse,
+	Line,
+	Arrow,
+	Step,
+	Text,
+}
+
+impl Shape {
+	/**
+	 * Create a Shape from the name of the operation.
+	 *
+	 * @param  name
+	 * @return Shape (Option)
+	 */
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"rect" => Some(Self::Rectangle),
+			"ellipse" => Some(Self::Ellipse),
+			"line" => Some(Self::Line),
+			"arrow" => Some(Self::Arrow),
+			"step" => Some(Self::Step),
+			"text" => Some(Self::Text),
+			_ => None,
+		}
+	}
+
+	/**
+	 * Get the name of the operation.
+	 *
+	 * @return str
+	 */
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::Rectangle => "rect",
+			Self::Ellipse => "ellipse",
+			Self::Line => "line",
+			Self::Arrow => "arrow",
+			Self::Step => "step",
+			Self::Text => "text",
+		}
+	}
+}
+
+/* Shape or text to draw on the image */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Annotation {
+	pub shape: Shape,
+	pub position: (i32, i32),
+	pub size: (i32, i32),
+	pub color: Rgba<u8>,
+	pub fill: Option<Rgba<u8>>,
+	pub width: u32,
+	pub font_size: u32,
+	pub frames: Option<(usize, usize)>,
+	pub text: String,
+}
+
+/* Display implementation for user-facing output */
+impl fmt::Display for Annotation {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let default = Self::new(self.shape);
+		if self.size != (0, 0) {
+			write!(f, "{}x{}", self.size.0, self.size.1)?;
+		}
+		write!(f, "{:+}{:+}", self.position.0, self.position.1)?;
+		if self.color != default.color {
+			write!(f, " color={}", format_color(self.color))?;
+		}
+		if let Some(fill) = self.fill {
+			write!(f, " fill={}", format_color(fill))?;
+		}
+		if self.width != default.width {
+			write!(f, " width={}", self.width)?;
+		}
+		if self.font_size != default.font_size {
+			write!(f, " size={}", self.font_size)?;
+		}
+		match self.frames {
+			Some((first, usize::MAX)) => write!(f, " frames={first}-")?,
+			Some((first, last)) => write!(f, " frames={first}-{last}")?,
+			None => {}
+		}
+		if !self.text.is_empty() {
+			write!(f, " {}", self.text)?;
+		}
+		Ok(())
+	}
+}
+
+impl Annotation {
+	/**
+	 * Create a new Annotation object with the default style.
+	 *
+	 * @param  shape
+	 * @return Annotation
+	 */
+	pub fn new(shape: Shape) -> Self {
+		Self {
+			shape,
+			position: (0, 0),
+			size: (0, 0),
+			color: Rgba([255, 0, 0, 255]),
+			fill: None,
+			width: 3,
+			font_size: 20,
+			frames: None,
+			text: String::new(),
+		}
+	}
+
+	/**
+	 * Parse an Annotation from its specification.
+	 *
+	 * Format: `[WxH]+X+Y [key=value]... [text]`
+	 *
+	 * @param  shape
+	 * @param  spec
+	 * @return Annotation (Result)
+	 */
+	pub fn parse(shape: Shape, spec: &str) -> Result<Self, String> {
+		let mut annotation = Self::new(shape);
+		let spec = spec.trim_start();
+		let (geometry, mut rest) =
+			spec.split_once(char::is_whitespace).unwrap_or((spec, ""));
+		annotation.parse_geometry(geometry)?;
+		loop {
+			rest = rest.trim_start();
+			let (token, remaining) =
+				rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+			let (key, value) = match token.split_once('=') {
+				Some(option) => option,
+				None => break,
+			};
+			let invalid = || format!("Invalid value for {key:?}: {value:?}");
+			match key {
+				"color" => {
+					annotation.color = parse_color(value).ok_or_else(invalid)?
+				}
+				"fill" => {
+					annotation.fill = Some(parse_color(value).ok_or_else(invalid)?)
+				}
+				"width" => {
+					annotation.width = value.parse().map_err(|_| invalid())?
+				}
+				"size" => {
+					annotation.font_size = value
+						.parse()
+						.ok()
+						.filter(|size| *size > 0)
+						.ok_or_else(invalid)?
+				}
+				"frames" => {
+					annotation.frames =
+						Some(Self::parse_frames(value).ok_or_else(invalid)?)
+				}
+				_ => break,
+			}
+			rest = remaining;
+		}
+		annotation.text = rest.trim().to_string();
+		if shape == Shape::Text && annotation.text.is_empty() {
+			return Err(String::from("Missing text for the annotation"));
+		}
+		Ok(annotation)
+	}
+
+	/* Parse the size and position from `[WxH]+X+Y` */
+	fn parse_geometry(&mut self, geometry: &str) -> Result<(), String> {
+		let invalid = || format!("Invalid geometry: {geometry:?}");
+		let (size, position) = match geometry.split_once('x') {
+			Some((width, rest)) => {
+				let index = rest
+					.char_indices()
+					.skip(1)
+					.find(|(_, c)| *c == '+' || *c == '-')
+					.map(|(i, _)| i)
+					.unwrap_or(rest.len());
+				let height = &rest[..index];
+				(
+					(
+						width.parse().map_err(|_| invalid())?,
+						height.parse().map_err(|_| invalid())?,
+					),
+					&rest[index..],
+				)
+			}
+			None => ((0, 0), geometry),
+		};
+		let values = position
+			.match_indices(['+', '-'])
+			.map(|(i, _)| i)
+			.chain([position.len()])
+			.collect::<Vec<usize>>();
+		if values.len() != 3 || values[0] != 0 {
+			return Err(invalid());
+		}
+		self.size = size;
+		self.position = (
+			position[values[0]..values[1]]
+				.parse()
+				.map_err(|_| invalid())?,
+			position[values[1]..values[2]]
+				.parse()
+				.map_err(|_| invalid())?,
+		);
+		Ok(())
+	}
+
+	/* Parse the frame range from `FIRST[-[LAST]]` */
+	fn parse_frames(frames: &str) -> Option<(usize, usize)> {
+		let (first, last) = match frames.split_once('-') {
+			Some((first, "")) => (first.parse().ok()?, usize::MAX),
+			Some((first, last)) => (first.parse().ok()?, last.parse().ok()?),
+			None => (frames.parse().ok()?, frames.parse().ok()?),
+		};
+		(first > 0 && first <= last).then_some((first, last))
+	}
+
+	/**
+	 * Check if the annotation is drawn on the given frame.
+	 *
+	 * @param  frame (starting from 1)
+	 * @return bool
+	 */
+	pub fn is_visible(&self, frame: usize) -> bool {
+		self.frames
+			.map_or(true, |(first, last)| (first..=last).contains(&frame))
+	}
+
+	/**
+	 * Draw the annotation on the image.
+	 *
+	 * @param image
+	 * @param step (number of the step marker)
+	 */
+	pub fn draw(&self, image: &mut RgbaImage, step: usize) {
+		let (x, y) = self.position;
+		let (width, height) = self.size;
+		let top_left = Point::new(x.min(x + width), y.min(y + height));
+		let size = Size::new(width.unsigned_abs(), height.unsigned_abs());
+		let end = Point::new(x + width, y + height);
+		let mut canvas = Canvas::new(image, self.color);
+		match self.shape {
+			Shape::Rectangle => {
+				self.draw_shape(&mut canvas, &Rectangle::new(top_left, size))
+			}
+			Shape::Ellipse => {
+				self.draw_shape(&mut canvas, &Ellipse::new(top_left, size))
+			}
+			Shape::Line => {
+				canvas.draw(&Line::new(Point::new(x, y), end), self.get_stroke())
+			}
+			Shape::Arrow => {
+				canvas.draw(&Line::new(Point::new(x, y), end), self.get_stroke());
+				let angle = f64::from(height).atan2(f64::from(width));
+				let length = f64::from(12.max(self.width * 4));
+				for side in [-1., 1.] {
+					let angle =
+						angle + std::f64::consts::PI + side * 30_f64.to_radians();
+					let head = Point::new(
+						end.x + (length * angle.cos()).round() as i32,
+						end.y + (length * angle.sin()).round() as i32,
+					);
+					canvas.draw(&Line::new(end, head), self.get_stroke());
+				}
+			}
+			Shape::Step => {
+				let diameter = match width.unsigned_abs() {
+					0 => STEP_DIAMETER,
+					diameter => diameter,
+				};
+				let circle = Circle::with_center(Point::new(x, y), diameter);
+				canvas.color = self.fill.unwrap_or(self.color);
+				canvas.draw(&circle, PrimitiveStyle::with_fill(BinaryColor::On));
+				if self.fill.is_some() && self.width != 0 {
+					canvas.color = self.color;
+					canvas.draw(&circle, self.get_stroke());
+				}
+				let text = if self.text.is_empty() {
+					step.to_string()
+				} else {
+					self.text.clone()
+				};
+				canvas.color = Rgba([255, 255, 255, 255]);
+				canvas.draw_text(&text, Point::new(x, y), diameter * 3 / 5, true);
+			}
+			Shape::Text => {
+				if let Some(fill) = self.fill {
+					let size = canvas.get_text_size(&self.text, self.font_size);
+					canvas.color = fill;
+					canvas.draw(
+						&Rectangle::new(
+							Point::new(x - 4, y - 4),
+							size + Size::new(8, 8),
+						),
+						PrimitiveStyle::with_fill(BinaryColor::On),
+					);
+					canvas.color = self.color;
+				}
+				canvas.draw_text(
+					&self.text,
+					Point::new(x, y),
+					self.font_size,
+					false,
+				);
+			}
+		}
+	}
+
+	/* Draw the filled and outlined shape */
+	fn draw_shape<S>(&self, canvas: &mut Canvas<'_>, shape: &S)
+	where
+		S: StyledDrawable<PrimitiveStyle<BinaryColor>, Color = BinaryColor>,
+	{
+		if let Some(fill) = self.fill {
+			canvas.color = fill;
+			canvas.draw(shape, PrimitiveStyle::with_fill(BinaryColor::On));
+			canvas.color = self.color;
+		}
+		if self.width != 0 {
+			canvas.draw(
+				shape,
+				PrimitiveStyleBuilder::from(&self.get_stroke())
+					.stroke_alignment(StrokeAlignment::Inside)
+					.build(),
+			);
+		}
+	}
+
+	/* Get the style for drawing lines */
+	fn get_stroke(&self) -> PrimitiveStyle<BinaryColor> {
+		PrimitiveStyle::with_stroke(BinaryColor::On, self.width)
+	}
+}
+
+/* Draw target that blends the pixels into the image */
+struct Canvas<'a> {
+	image: &'a mut RgbaImage,
+	color: Rgba<u8>,
+	origin: Point,
+	scale: u32,
+}
+
+impl<'a> Canvas<'a> {
+	/**
+	 * Create a new Canvas object.
+	 *
+	 * @param  image
+	 * @param  color
+	 * @return Canvas
+	 */
+	fn new(image: &'a mut RgbaImage, color: Rgba<u8>) -> Self {
+		Self {
+			image,
+			color,
+			origin: Point::zero(),
+			scale: 1,
+		}
+	}
+
+	/* Draw the shape with the given style */
+	fn draw<S>(&mut self, shape: &S, style: PrimitiveStyle<BinaryColor>)
+	where
+		S: StyledDrawable<PrimitiveStyle<BinaryColor>, Color = BinaryColor>,
+	{
+		let _ = shape.draw_styled(&style, self);
+	}
+
+	/* Get the font and scale for the given text size */
+	fn get_font(size: u32) -> (&'static MonoFont<'static>, u32) {
+		let largest = &FONTS[FONTS.len() - 1];
+		if size >= largest.character_size.height * 2 {
+			(largest, size / largest.character_size.height)
+		} else {
+			(
+				FONTS
+					.iter()
+					.rev()
+					.find(|font| font.character_size.height <= size)
+					.unwrap_or(&FONTS[0]),
+				1,
+			)
+		}
+	}
+
+	/* Get the size of the text when drawn */
+	fn get_text_size(&self, text: &str, size: u32) -> Size {
+		let (font, scale) = Self::get_font(size);
+		Text::with_baseline(
+			text,
+			Point::zero(),
+			MonoTextStyle::new(font, BinaryColor::On),
+			Baseline::Top,
+		)
+		.bounding_box()
+		.size * scale
+	}
+
+	/* Draw the text at the position (or centered on it) */
+	fn draw_text(&mut self, text: &str, position: Point, size: u32, center: bool) {
+		let (font, scale) = Self::get_font(size);
+		let text = Text::with_baseline(
+			text,
+			Point::zero(),
+			MonoTextStyle::new(font, BinaryColor::On),
+			Baseline::Top,
+		);
+		self.origin = if center {
+			position - (text.bounding_box().size * scale / 2)
+		} else {
+			position
+		};
+		self.scale = scale;
+		let _ = text.draw(self);
+		self.origin = Point::zero();
+		self.scale = 1;
+	}
+
+	/* Blend the color into the pixel */
+	fn blend(&mut self, x: i32, y: i32) {
+		let pixel = match (u32::try_from(x), u32::try_from(y)) {
+			(Ok(x), Ok(y)) if x < self.image.width() && y < self.image.height() => {
+				self.image.get_pixel_mut(x, y)
+			}
+			_ => return,
+		};
+		let alpha = f32::from(self.color[3]) / 255.;
+		for i in 0..3 {
+			pixel[i] = (f32::from(self.color[i]) * alpha
+				+ f32::from(pixel[i]) * (1. - alpha))
+				.round() as u8;
+		}
+		pixel[3] = (f32::from(self.color[3]) + f32::from(pixel[3]) * (1. - alpha))
+			.round() as u8;
+	}
+}
+
+/* Dimensions implementation for the draw target */
+impl OriginDimensions for Canvas<'_> {
+	fn size(&self) -> Size {
+		Size::new(self.image.width(), self.image.height())
+	}
+}
+
+/* DrawTarget implementation for drawing the primitives */
+impl DrawTarget for Canvas<'_> {
+	type Color = BinaryColor;
+	type Error = Infallible;
+	fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+	where
+		I: IntoIterator<Item = Pixel<Self::Color>>,
+	{
+		let scale = self.scale as i32;
+		for Pixel(point, color) in pixels {
+			if color.is_off() {
+				continue;
+			}
+			for dx in 0..scale {
+				for dy in 0..scale {
+					self.blend(
+						self.origin.x + point.x * scale + dx,
+						self.origin.y + point.y * scale + dy,
+					);
+				}
+			}
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_annotate() {
+		let annotation = Annotation::parse(
+			Shape::Arrow,
+			"-20x10+30-5 color=00ff0080 width=2 frames=2-",
+		)
+		.unwrap();
+		assert_eq!((-20, 10), annotation.size);
+		assert_eq!((30, -5), annotation.position);
+		assert_eq!(Rgba([0, 255, 0, 128]), annotation.color);
+		assert_eq!(Some((2, usize::MAX)), annotation.frames);
+		assert_eq!(
+			"-20x10+30-5 color=00ff0080 width=2 frames=2-",
+			annotation.to_string()
+		);
+		assert!(!annotation.is_visible(1));
+		assert!(annotation.is_visible(5));
+		let annotation =
+			Annotation::parse(Shape::Text, "+2+3 size=40 fill=000000 Hello  world")
+				.unwrap();
+		assert_eq!("Hello  world", annotation.text);
+		assert_eq!(Some(Rgba([0, 0, 0, 255])), annotation.fill);
+		assert_eq!(Ok(annotation.clone()), {
+			Annotation::parse(Shape::Text, &annotation.to_string())
+		});
+		assert!(Annotation::parse(Shape::Text, "+0+0").is_err());
+		assert!(Annotation::parse(Shape::Rectangle, "10x10").is_err());
+		assert!(Annotation::parse(Shape::Rectangle, "10x10+0+0 width=x").is_err());
+		assert!(Annotation::parse(Shape::Line, "+1+2 frames=3-1").is_err());
+		let mut image = RgbaImage::from_pixel(20, 20, Rgba([0, 0, 0, 255]));
+		Annotation::parse(Shape::Rectangle, "10x10+5+5 width=1 fill=0000ff")
+			.unwrap()
+			.draw(&mut image, 1);
+		assert_eq!(Rgba([255, 0, 0, 255]), *image.get_pixel(5, 5));
+		assert_eq!(Rgba([255, 0, 0, 255]), *image.get_pixel(14, 14));
+		assert_eq!(Rgba([0, 0, 255, 255]), *image.get_pixel(10, 10));
+		assert_eq!(Rgba([0, 0, 0, 255]), *image.get_pixel(15, 15));
+		Annotation::parse(Shape::Line, "-30x0+10+2 width=1 color=ffffff80")
+			.unwrap()
+			.draw(&mut image, 1);
+		assert_eq!(Rgba([128, 128, 128, 255]), *image.get_pixel(0, 2));
+		let mut image = RgbaImage::new(40, 40);
+		Annotation::parse(Shape::Step, "+20+20")
+			.unwrap()
+			.draw(&mut image, 7);
+		assert_eq!(Rgba([255, 0, 0, 255]), *image.get_pixel(20, 9));
+		assert!(image
+			.pixels()
+			.any(|pixel| pixel == &Rgba([255, 255, 255, 255])));
+		assert_eq!(Rgba([0, 0, 0, 0]), *image.get_pixel(0, 0));
+	}
+}
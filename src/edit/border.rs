@@ -0,0 +1,191 @@
+use crate::edit::annotate::{format_color, parse_color};
+use crate::edit::canvas::Background;
+use crate::image::padding::Padding;
+use image::{Rgba, RgbaImage};
+use std::fmt;
+
+/**
+ * Get the padding of the uniform borders of the image.
+ *
+ * Color of the top-left pixel is used as the border color and
+ * pixels that differ by at most `fuzz` percent are also counted.
+ * Transparent pixels are matched regardless of their color.
+ * Nothing is returned if the whole image is uniform.
+ *
+ * @param  image
+ * @param  fuzz
+ * @return Padding (Option)
+ */
+pub fn get_trim(image: &RgbaImage, fuzz: f32) -> Option<Padding> {
+	let (width, height) = image.dimensions();
+	if width == 0 || height == 0 {
+		return None;
+	}
+	let border = *image.get_pixel(0, 0);
+	let tolerance = (fuzz.clamp(0., 100.) / 100. * 255.).round() as i16;
+	let is_border = |pixel: &Rgba<u8>| {
+		if border[3] == 0 {
+			pixel[3] == 0
+		} else {
+			pixel
+				.0
+				.iter()
+				.zip(border.0.iter())
+				.all(|(a, b)| (i16::from(*a) - i16::from(*b)).abs() <= tolerance)
+		}
+	};
+	let (mut min_x, mut min_y, mut max_x, mut max_y) = (width, height, 0, 0);
+	for (x, y, pixel) in image.enumerate_pixels() {
+		if !is_border(pixel) {
+			min_x = min_x.min(x);
+			min_y = min_y.min(y);
+			max_x = max_x.max(x);
+			max_y = max_y.max(y);
+		}
+	}
+	if min_x > max_x {
+		None
+	} else {
+		Some(Padding::new(
+			min_y,
+			width - max_x - 1,
+			height - max_y - 1,
+			min_x,
+		))
+	}
+}
+
+/**
+ * Get the padding that keeps the content of all the images.
+ *
+ * Images without a padding (uniform) are skipped.
+ *
+ * @param  paddings
+ * @return Padding (Option)
+ */
+pub fn get_union(
+	paddings: impl Iterator<Item = Option<Padding>>,
+) -> Option<Padding> {
+	paddings.flatten().reduce(|a, b| {
+		Padding::new(
+			a.top.min(b.top),
+			a.right.min(b.right),
+			a.bottom.min(b.bottom),
+			a.left.min(b.left),
+		)
+	})
+}
+
+/* Margins to add around the image */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Extend {
+	pub padding: Padding,
+	pub color: Rgba<u8>,
+}
+
+/* Display implementation for user-facing output */
+impl fmt::Display for Extend {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}:{}", self.padding, format_color(self.color))
+	}
+}
+
+impl Extend {
+	/**
+	 * Parse an Extend from `T:R:B:L[:COLOR]`.
+	 *
+	 * Margins are transparent if the color is not given.
+	 *
+	 * @param  extend
+	 * @return Extend (Result)
+	 */
+	pub fn parse(extend: &str) -> Result<Self, String> {
+		let invalid = || format!("Invalid margins: {extend:?}");
+		let values = extend.split(':').collect::<Vec<&str>>();
+		if !(4..=5).contains(&values.len())
+			|| !values[..4].iter().all(|value| value.parse::<u32>().is_ok())
+		{
+			return Err(invalid());
+		}
+		Ok(Self {
+			padding: Padding::parse(&values[..4].join(":")),
+			color: match values.get(4) {
+				Some(color) => parse_color(color).ok_or_else(invalid)?,
+				None => Rgba([0, 0, 0, 0]),
+			},
+		})
+	}
+
+	/**
+	 * Get the size of the image with the margins.
+	 *
+	 * @param  size
+	 * @return size
+	 */
+	pub fn get_size(&self, size: (u32, u32)) -> (u32, u32) {
+		(
+			size.0 + self.padding.left + self.padding.right,
+			size.1 + self.padding.top + self.padding.bottom,
+		)
+	}
+
+	/**
+	 * Add the margins around the image.
+	 *
+	 * @param  image
+	 * @return RgbaImage
+	 */
+	pub fn apply(&self, image: &RgbaImage) -> RgbaImage {
+		Background {
+			padding: self.padding,
+			color: self.color,
+			gradient: None,
+		}
+		.apply(image)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_border() {
+		let mut image = RgbaImage::from_pixel(6, 5, Rgba([250, 250, 250, 255]));
+		image.put_pixel(2, 1, Rgba([0, 0, 0, 255]));
+		image.put_pixel(3, 2, Rgba([255, 255, 255, 255]));
+		assert_eq!(Some(Padding::new(1, 2, 2, 2)), get_trim(&image, 0.));
+		assert_eq!(Some(Padding::new(1, 3, 3, 2)), get_trim(&image, 5.));
+		assert_eq!(None, get_trim(&image, 100.));
+		let mut image = RgbaImage::new(4, 4);
+		image.put_pixel(3, 3, Rgba([0, 0, 0, 1]));
+		image.put_pixel(1, 2, Rgba([255, 0, 0, 0]));
+		assert_eq!(Some(Padding::new(3, 0, 0, 3)), get_trim(&image, 0.));
+		assert_eq!(
+			Some(Padding::new(1, 0, 2, 1)),
+			get_union(
+				[
+					Some(Padding::new(1, 2, 3, 4)),
+					None,
+					Some(Padding::new(5, 0, 2, 1)),
+				]
+				.into_iter()
+			)
+		);
+		assert_eq!(None, get_union([None].into_iter()));
+		let extend = Extend::parse("1:2:0:0:ff0000").unwrap();
+		assert_eq!("1:2:0:0:ff0000", extend.to_string());
+		assert_eq!(
+			Ok(Rgba([0, 0, 0, 0])),
+			Extend::parse("1:1:1:1").map(|v| v.color)
+		);
+		for invalid in ["1", "1:1:1", "1:1:1:x", "1:1:1:1:x", "1:1:1:1:000:0"] {
+			assert!(Extend::parse(invalid).is_err(), "{invalid}");
+		}
+		let extended =
+			extend.apply(&RgbaImage::from_pixel(2, 2, Rgba([0, 0, 255, 255])));
+		assert_eq!((4, 3), extended.dimensions());
+		assert_eq!(Rgba([255, 0, 0, 255]), *extended.get_pixel(3, 0));
+		assert_eq!(Rgba([0, 0, 255, 255]), *extended.get_pixel(0, 1));
+	}
+}
@@ -0,0 +1,307 @@
+use crate::edit::annotate::{format_color, parse_color};
+use crate::image::padding::Padding;
+use image::imageops;
+use image::{Rgba, RgbaImage};
+use std::fmt;
+
+/**
+ * Round the corners of the image with anti-aliased edges.
+ *
+ * @param image
+ * @param radius
+ */
+pub fn round_corners(image: &mut RgbaImage, radius: u32) {
+	let (width, height) = image.dimensions();
+	let radius = radius.min(width / 2).min(height / 2) as f32;
+	let (width, height) = (width as f32, height as f32);
+	let get_center = |value: f32, size: f32| {
+		if value < radius {
+			Some(radius)
+		} else if value > size - radius {
+			Some(size - radius)
+		} else {
+			None
+		}
+	};
+	for (x, y, pixel) in image.enumerate_pixels_mut() {
+		let (x, y) = (x as f32 + 0.5, y as f32 + 0.5);
+		if let (Some(cx), Some(cy)) = (get_center(x, width), get_center(y, height)) {
+			let distance = ((x - cx).powi(2) + (y - cy).powi(2)).sqrt();
+			let coverage = (radius - distance + 0.5).clamp(0., 1.);
+			pixel[3] = (f32::from(pixel[3]) * coverage).round() as u8;
+		}
+	}
+}
+
+/* Drop shadow properties */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Shadow {
+	pub sigma: f32,
+	pub offset: (i32, i32),
+	pub color: Rgba<u8>,
+}
+
+/* Default initialization values for Shadow */
+impl Default for Shadow {
+	fn default() -> Self {
+		Self {
+			sigma: 10.,
+			offset: (0, 10),
+			color: Rgba([0, 0, 0, 128]),
+		}
+	}
+}
+
+/* Display implementation for user-facing output */
+impl fmt::Display for Shadow {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{}:{}:{}:{}",
+			self.sigma,
+			self.offset.0,
+			self.offset.1,
+			format_color(self.color)
+		)
+	}
+}
+
+impl Shadow {
+	/**
+	 * Parse a Shadow from `SIGMA[:X:Y[:COLOR]]`.
+	 *
+	 * @param  shadow
+	 * @return Shadow (Result)
+	 */
+	pub fn parse(shadow: &str) -> Result<Self, String> {
+		let invalid = || format!("Invalid shadow: {shadow:?}");
+		let values = shadow.split(':').collect::<Vec<&str>>();
+		let mut shadow = Self {
+			sigma: values[0]
+				.parse()
+				.ok()
+				.filter(|sigma| *sigma >= 0.)
+				.ok_or_else(invalid)?,
+			..Self::default()
+		};
+		match values[1..] {
+			[] => {}
+			[x, y] | [x, y, _] => {
+				shadow.offset = (
+					x.parse().map_err(|_| invalid())?,
+					y.parse().map_err(|_| invalid())?,
+				)
+			}
+			_ => return Err(invalid()),
+		}
+		if let Some(color) = values.get(3) {
+			shadow.color = parse_color(color).ok_or_else(invalid)?;
+		}
+		Ok(shadow)
+	}
+
+	/**
+	 * Get the margin that is added around the image for the shadow.
+	 *
+	 * @return u32
+	 */
+	fn get_margin(&self) -> u32 {
+		(self.sigma * 3.).ceil() as u32
+			+ self
+				.offset
+				.0
+				.unsigned_abs()
+				.max(self.offset.1.unsigned_abs())
+	}
+
+	/**
+	 * Get the size of the image with the shadow.
+	 *
+	 * @param  size
+	 * @return size
+	 */
+	pub fn get_size(&self, size: (u32, u32)) -> (u32, u32) {
+		let margin = self.get_margin();
+		(size.0 + margin * 2, size.1 + margin * 2)
+	}
+
+	/**
+	 * Draw the shadow behind the image.
+	 *
+	 * @param  image
+	 * @return RgbaImage
+	 */
+	pub fn apply(&self, image: &RgbaImage) -> RgbaImage {
+		let (width, height) = image.dimensions();
+		let margin = self.get_margin();
+		let color = self.color;
+		let mut shadow = RgbaImage::from_pixel(
+			width + margin * 2,
+			height + margin * 2,
+			Rgba([color[0], color[1], color[2], 0]),
+		);
+		for (x, y, pixel) in image.enumerate_pixels() {
+			shadow.put_pixel(
+				(x + margin).saturating_add_signed(self.offset.0),
+				(y + margin).saturating_add_signed(self.offset.1),
+				Rgba([
+					color[0],
+					color[1],
+					color[2],
+					(u32::from(pixel[3]) * u32::from(color[3]) / 255) as u8,
+				]),
+			);
+		}
+		if self.sigma > 0. {
+			shadow = imageops::blur(&shadow, self.sigma);
+		}
+		imageops::overlay(&mut shadow, image, i64::from(margin), i64::from(margin));
+		shadow
+	}
+}
+
+/* Padded background of the image */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Background {
+	pub padding: Padding,
+	pub color: Rgba<u8>,
+	pub gradient: Option<Rgba<u8>>,
+}
+
+/* Display implementation for user-facing output */
+impl fmt::Display for Background {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{},{}", self.padding, format_color(self.color))?;
+		if let Some(gradient) = self.gradient {
+			write!(f, ",{}", format_color(gradient))?;
+		}
+		Ok(())
+	}
+}
+
+impl Background {
+	/**
+	 * Parse a Background from `PADDING[,COLOR[,COLOR]]`.
+	 *
+	 * A single padding value is applied to all sides.
+	 *
+	 * @param  background
+	 * @return Background (Result)
+	 */
+	pub fn parse(background: &str) -> Result<Self, String> {
+		let invalid = || format!("Invalid canvas: {background:?}");
+		let values = background.split(',').collect::<Vec<&str>>();
+		if values.len() > 3
+			|| !values[0]
+				.split(':')
+				.all(|value| value.parse::<u32>().is_ok())
+		{
+			return Err(invalid());
+		}
+		let padding = match values[0].parse() {
+			Ok(value) => Padding::new(value, value, value, value),
+			Err(_) => Padding::parse(values[0]),
+		};
+		Ok(Self {
+			padding,
+			color: match values.get(1) {
+				Some(color) => parse_color(color).ok_or_else(invalid)?,
+				None => Rgba([255, 255, 255, 255]),
+			},
+			gradient: match values.get(2) {
+				Some(color) => Some(parse_color(color).ok_or_else(invalid)?),
+				None => None,
+			},
+		})
+	}
+
+	/**
+	 * Get the size of the image on the background.
+	 *
+	 * @param  size
+	 * @return size
+	 */
+	pub fn get_size(&self, size: (u32, u32)) -> (u32, u32) {
+		(
+			size.0 + self.padding.left + self.padding.right,
+			size.1 + self.padding.top + self.padding.bottom,
+		)
+	}
+
+	/**
+	 * Place the image on the background.
+	 *
+	 * @param  image
+	 * @return RgbaImage
+	 */
+	pub fn apply(&self, image: &RgbaImage) -> RgbaImage {
+		let (width, height) = self.get_size(image.dimensions());
+		let mut background =
+			RgbaImage::from_fn(width, height, |x, y| match self.gradient {
+				Some(gradient) => {
+					let ratio = (x as f32 / (width.max(2) - 1) as f32
+						+ y as f32 / (height.max(2) - 1) as f32)
+						/ 2.;
+					Rgba([0, 1, 2, 3].map(|i| {
+						(f32::from(self.color[i]) * (1. - ratio)
+							+ f32::from(gradient[i]) * ratio)
+							.round() as u8
+					}))
+				}
+				None => self.color,
+			});
+		imageops::overlay(
+			&mut background,
+			image,
+			i64::from(self.padding.left),
+			i64::from(self.padding.top),
+		);
+		background
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_canvas() {
+		let mut image = RgbaImage::from_pixel(20, 10, Rgba([255, 0, 0, 255]));
+		round_corners(&mut image, 4);
+		assert_eq!(0, image.get_pixel(0, 0)[3]);
+		assert_eq!(0, image.get_pixel(19, 9)[3]);
+		assert_eq!(255, image.get_pixel(10, 0)[3]);
+		assert_eq!(255, image.get_pixel(0, 5)[3]);
+		assert!((1..255).contains(&image.get_pixel(1, 1)[3]));
+		let shadow = Shadow::parse("2:-1:3:ff000080").unwrap();
+		assert_eq!((-1, 3), shadow.offset);
+		assert_eq!("2:-1:3:ff000080", shadow.to_string());
+		assert_eq!(Ok(Shadow::default()), Shadow::parse("10"));
+		assert!(Shadow::parse("2:1").is_err());
+		assert!(Shadow::parse("x").is_err());
+		let image = RgbaImage::from_pixel(4, 4, Rgba([0, 0, 255, 255]));
+		let shadowed = shadow.apply(&image);
+		assert_eq!((4 + 9 * 2, 4 + 9 * 2), shadowed.dimensions());
+		assert_eq!(Rgba([0, 0, 255, 255]), *shadowed.get_pixel(9, 9));
+		assert_eq!(0, shadowed.get_pixel(0, 0)[3]);
+		assert!(shadowed.get_pixel(10, 15)[3] > 0);
+		let background = Background::parse("2,000000,ffffff").unwrap();
+		assert_eq!(Padding::new(2, 2, 2, 2), background.padding);
+		assert_eq!("2:2:2:2,000000,ffffff", background.to_string());
+		assert_eq!(Ok(background), Background::parse(&background.to_string()));
+		assert!(Background::parse("2,x").is_err());
+		assert!(Background::parse("a").is_err());
+		let canvas = background.apply(&image);
+		assert_eq!((8, 8), canvas.dimensions());
+		assert_eq!(Rgba([0, 0, 0, 255]), *canvas.get_pixel(0, 0));
+		assert_eq!(Rgba([255, 255, 255, 255]), *canvas.get_pixel(7, 7));
+		assert_eq!(Rgba([0, 0, 255, 255]), *canvas.get_pixel(2, 2));
+		assert_eq!(
+			Rgba([255, 255, 255, 255]),
+			*Background::parse("0:1")
+				.unwrap()
+				.apply(&image)
+				.get_pixel(4, 0)
+		);
+	}
+}
@@ -0,0 +1,299 @@
+use image::imageops;
+use image::{Rgba, RgbaImage};
+use std::fmt;
+
+/* Convolution matrix */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Kernel {
+	pub width: u32,
+	pub height: u32,
+	pub values: Vec<f32>,
+}
+
+/* Display implementation for user-facing output */
+impl fmt::Display for Kernel {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{}x{}:{}",
+			self.width,
+			self.height,
+			self.values
+				.iter()
+				.map(|value| value.to_string())
+				.collect::<Vec<String>>()
+				.join(",")
+		)
+	}
+}
+
+impl Kernel {
+	/**
+	 * Create a new Kernel object.
+	 *
+	 * @param  width
+	 * @param  height
+	 * @param  values
+	 * @return Kernel
+	 */
+	pub fn new(width: u32, height: u32, values: Vec<f32>) -> Self {
+		Self {
+			width,
+			height,
+			values,
+		}
+	}
+
+	/**
+	 * Create a Kernel for sharpening.
+	 *
+	 * @return Kernel
+	 */
+	pub fn sharpen() -> Self {
+		Self::new(3, 3, vec![0., -1., 0., -1., 5., -1., 0., -1., 0.])
+	}
+
+	/**
+	 * Create a Kernel for detecting edges.
+	 *
+	 * @return Kernel
+	 */
+	pub fn edges() -> Self {
+		Self::new(3, 3, vec![-1., -1., -1., -1., 8., -1., -1., -1., -1.])
+	}
+
+	/**
+	 * Create a Kernel for embossing.
+	 *
+	 * @return Kernel
+	 */
+	pub fn emboss() -> Self {
+		Self::new(3, 3, vec![-2., -1., 0., -1., 1., 1., 0., 1., 2.])
+	}
+
+	/**
+	 * Parse a Kernel from `WxH:VALUE,VALUE,...`.
+	 *
+	 * @param  kernel
+	 * @return Kernel (Result)
+	 */
+	pub fn parse(kernel: &str) -> Result<Self, String> {
+		let invalid = || format!("Invalid kernel: {kernel:?}");
+		let (size, values) = kernel.split_once(':').ok_or_else(invalid)?;
+		let (width, height) = size.split_once('x').ok_or_else(invalid)?;
+		let (width, height): (u32, u32) = (
+			width.trim().parse().map_err(|_| invalid())?,
+			height.trim().parse().map_err(|_| invalid())?,
+		);
+		let values = values
+			.split(',')
+			.map(|value| value.trim().parse::<f32>())
+			.collect::<Result<Vec<f32>, _>>()
+			.map_err(|_| invalid())?;
+		if width % 2 == 0
+			|| height % 2 == 0
+			|| width.checked_mul(height) != u32::try_from(values.len()).ok()
+			|| values.iter().any(|value| !value.is_finite())
+		{
+			return Err(invalid());
+		}
+		Ok(Self::new(width, height, values))
+	}
+
+	/**
+	 * Convolve the image with the kernel.
+	 *
+	 * Values are divided by their sum unless it is zero.
+	 * Pixels outside of the image are taken from the nearest edge,
+	 * colors are weighted by their alpha values so that transparent
+	 * pixels do not bleed into the result and the alpha is preserved.
+	 * Weights of kernels with negative values are not allowed to fall
+	 * below the alpha of the pixel to avoid saturating the colors
+	 * at semi-transparent edges.
+	 *
+	 * @param  image
+	 * @return RgbaImage
+	 */
+	pub fn apply(&self, image: &RgbaImage) -> RgbaImage {
+		let sum = self.values.iter().sum::<f32>();
+		let is_mixed = self.values.iter().any(|value| *value < 0.);
+		let (width, height) = image.dimensions();
+		let (max_x, max_y) = (i64::from(width) - 1, i64::from(height) - 1);
+		let (center_x, center_y) =
+			(i64::from(self.width / 2), i64::from(self.height / 2));
+		RgbaImage::from_fn(width, height, |x, y| {
+			let alpha = image.get_pixel(x, y)[3];
+			if alpha == 0 {
+				return Rgba([0, 0, 0, 0]);
+			}
+			let mut color = [0_f32; 3];
+			let mut weights = 0.;
+			for (i, value) in self.values.iter().enumerate() {
+				let kx = (i as u32 % self.width) as i64;
+				let ky = (i as u32 / self.width) as i64;
+				let pixel = image.get_pixel(
+					(i64::from(x) + kx - center_x).clamp(0, max_x) as u32,
+					(i64::from(y) + ky - center_y).clamp(0, max_y) as u32,
+				);
+				let weight = value * f32::from(pixel[3]) / 255.;
+				for (c, channel) in color.iter_mut().enumerate() {
+					*channel += f32::from(pixel[c]) * weight;
+				}
+				weights += weight;
+			}
+			let min_divisor =
+				f32::from(alpha) / 255. * if sum == 0. { 1. } else { sum };
+			let divisor = if sum != 0.
+				&& weights * sum > 0.
+				&& (!is_mixed || weights.abs() > min_divisor.abs())
+			{
+				weights
+			} else {
+				min_divisor
+			};
+			Rgba([
+				(color[0] / divisor).round().clamp(0., 255.) as u8,
+				(color[1] / divisor).round().clamp(0., 255.) as u8,
+				(color[2] / divisor).round().clamp(0., 255.) as u8,
+				alpha,
+			])
+		})
+	}
+}
+
+/* Unsharp mask properties */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Unsharpen {
+	pub sigma: f32,
+	pub threshold: i32,
+}
+
+/* Display implementation for user-facing output */
+impl fmt::Display for Unsharpen {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}:{}", self.sigma, self.threshold)
+	}
+}
+
+impl Unsharpen {
+	/**
+	 * Parse an Unsharpen from `SIGMA[:THRESHOLD]`.
+	 *
+	 * @param  unsharpen
+	 * @return Unsharpen (Result)
+	 */
+	pub fn parse(unsharpen: &str) -> Result<Self, String> {
+		let invalid = || format!("Invalid unsharp mask: {unsharpen:?}");
+		let (sigma, threshold) = match unsharpen.split_once(':') {
+			Some((sigma, threshold)) => {
+				(sigma, threshold.parse().map_err(|_| invalid())?)
+			}
+			None => (unsharpen, 0),
+		};
+		match sigma.parse::<f32>() {
+			Ok(sigma) if sigma > 0. && threshold >= 0 => {
+				Ok(Self { sigma, threshold })
+			}
+			_ => Err(invalid()),
+		}
+	}
+
+	/**
+	 * Sharpen the image with an unsharp mask.
+	 *
+	 * The difference from the blurred image is added to the channels
+	 * where it exceeds the threshold. Blurring is done with
+	 * premultiplied colors and the alpha channel is preserved.
+	 *
+	 * @param  image
+	 * @return RgbaImage
+	 */
+	pub fn apply(&self, image: &RgbaImage) -> RgbaImage {
+		let mut premultiplied = image.clone();
+		premultiplied.pixels_mut().for_each(|pixel| {
+			for c in 0..3 {
+				pixel[c] = (u32::from(pixel[c]) * u32::from(pixel[3]) / 255) as u8;
+			}
+		});
+		let blurred = imageops::blur(&premultiplied, self.sigma);
+		let mut sharpened = image.clone();
+		for (pixel, blurred) in sharpened.pixels_mut().zip(blurred.pixels()) {
+			if pixel[3] == 0 || blurred[3] == 0 {
+				continue;
+			}
+			for c in 0..3 {
+				let value = i32::from(pixel[c]);
+				let diff = value
+					- (u32::from(blurred[c]) * 255 / u32::from(blurred[3])) as i32;
+				if diff.abs() > self.threshold {
+					pixel[c] = (value + diff).clamp(0, 255) as u8;
+				}
+			}
+		}
+		sharpened
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_convolve() {
+		let kernel = Kernel::parse("3x1:1, 2,1").unwrap();
+		assert_eq!(Kernel::new(3, 1, vec![1., 2., 1.]), kernel);
+		assert_eq!("3x1:1,2,1", kernel.to_string());
+		assert_eq!(
+			Ok(Kernel::sharpen()),
+			Kernel::parse(&Kernel::sharpen().to_string())
+		);
+		for invalid in [
+			"3x3:1,2",
+			"2x1:1,1",
+			"3x1",
+			"3:1,1,1",
+			"1x1:x",
+			"1x1:inf",
+			"99999x99999:1",
+		] {
+			assert!(Kernel::parse(invalid).is_err(), "{invalid}");
+		}
+		let image = RgbaImage::from_fn(3, 2, |x, _| {
+			if x == 2 {
+				Rgba([255, 255, 255, 0])
+			} else {
+				Rgba([(x * 100) as u8, 0, 0, 255])
+			}
+		});
+		let blurred = kernel.apply(&image);
+		assert_eq!(Rgba([25, 0, 0, 255]), *blurred.get_pixel(0, 0));
+		assert_eq!(Rgba([67, 0, 0, 255]), *blurred.get_pixel(1, 1));
+		assert_eq!(Rgba([0, 0, 0, 0]), *blurred.get_pixel(2, 0));
+		let image = RgbaImage::from_pixel(4, 4, Rgba([90, 90, 90, 128]));
+		assert_eq!(image, Kernel::sharpen().apply(&image));
+		assert_eq!(image, Kernel::emboss().apply(&image));
+		assert!(Kernel::edges()
+			.apply(&image)
+			.pixels()
+			.all(|pixel| *pixel == Rgba([0, 0, 0, 128])));
+		let mut image = RgbaImage::from_pixel(3, 3, Rgba([90, 90, 90, 64]));
+		image.put_pixel(1, 1, Rgba([100, 100, 100, 52]));
+		assert_eq!(
+			Rgba([57, 57, 57, 52]),
+			*Kernel::sharpen().apply(&image).get_pixel(1, 1)
+		);
+		let unsharpen = Unsharpen::parse("1.5:3").unwrap();
+		assert_eq!("1.5:3", unsharpen.to_string());
+		assert_eq!(Ok(0), Unsharpen::parse("2").map(|v| v.threshold));
+		for invalid in ["", "0", "1:-1", "1:x", "x:1"] {
+			assert!(Unsharpen::parse(invalid).is_err(), "{invalid}");
+		}
+		let mut image = RgbaImage::from_pixel(6, 6, Rgba([100, 100, 100, 200]));
+		image.put_pixel(3, 3, Rgba([200, 200, 200, 255]));
+		let sharpened = unsharpen.apply(&image);
+		assert_eq!(200, sharpened.get_pixel(0, 0)[3]);
+		assert_eq!(255, sharpened.get_pixel(3, 3)[3]);
+		assert!(sharpened.get_pixel(3, 3)[0] > 200);
+		assert!(sharpened.get_pixel(3, 2)[0] < 100);
+	}
+}
@@ -1,17 +1,73 @@
+pub mod adjust;
+pub mod annotate;
+pub mod border;
+pub mod canvas;
+pub mod convolve;
+pub mod orientation;
+pub mod overlay;
+pub mod pipeline;
+pub mod redact;
+pub mod resize;
+pub mod rotate;
 pub mod settings;
 
+use crate::edit::adjust::Lut;
+use crate::edit::annotate::{Annotation, Shape};
+use crate::edit::convolve::Kernel;
+use crate::edit::orientation::Orientation;
+use crate::edit::overlay::OverlayCache;
+use crate::edit::pipeline::Operation;
 use crate::edit::settings::ColorSettings;
 use crate::edit::settings::{EditSettings, Flip};
 use crate::image::geometry::Geometry;
+use crate::image::padding::Padding;
 use crate::image::Image;
 use image::imageops::{self, colorops};
-use image::{DynamicImage, ImageBuffer, Rgba, RgbaImage};
+use image::{DynamicImage, ExtendedColorType, ImageBuffer, Rgba, RgbaImage};
+
+/* Value of an operation that is computed from all the frames */
+#[derive(Clone, Copy, Debug)]
+enum Shared {
+	Trim((u32, u32), Option<Padding>),
+	Range((u8, u8)),
+}
+
+impl Shared {
+	/* Combine the values of two frames */
+	fn merge(self, other: Self) -> Self {
+		match (self, other) {
+			(Self::Trim(size, padding), Self::Trim(other_size, other_padding))
+				if size == other_size =>
+			{
+				Self::Trim(
+					size,
+					border::get_union([padding, other_padding].into_iter()),
+				)
+			}
+			(
+				Self::Range((black, white)),
+				Self::Range((other_black, other_white)),
+			) => Self::Range((black.min(other_black), white.max(other_white))),
+			_ => self,
+		}
+	}
+}
 
 /* Image processor */
 #[derive(Debug)]
 pub struct ImageOps<'a> {
 	pub image: RgbaImage,
 	pub geometry: Geometry,
+	area: Geometry,
+	orientation: Orientation,
+	frame: usize,
+	step: usize,
+	resize_frame: Option<(u32, u32)>,
+	shared: Vec<Shared>,
+	shared_step: usize,
+	collected: Option<Vec<Shared>>,
+	lut: Option<Lut>,
+	overlay: OverlayCache,
 	settings: &'a EditSettings,
 }
 
@@ -26,50 +82,263 @@ impl<'a> ImageOps<'a> {
 		Self {
 			image: ImageBuffer::new(0, 0),
 			geometry: Geometry::default(),
+			area: Geometry::default(),
+			orientation: Orientation::default(),
+			frame: 0,
+			step: 0,
+			resize_frame: None,
+			shared: Vec::new(),
+			shared_step: 0,
+			collected: None,
+			lut: settings.color.get_lut(),
+			overlay: OverlayCache::default(),
 			settings,
 		}
 	}
 
 	/**
-	 * Set the geometry to use while processing.
+	 * Set the orientation of the image to apply before processing.
+	 *
+	 * @param orientation
+	 */
+	pub fn orient(&mut self, orientation: Orientation) -> &mut Self {
+		if self.settings.image.auto_orient {
+			self.orientation = orientation;
+		}
+		self
+	}
+
+	/**
+	 * Compute the values of the operations that are shared by the frames.
+	 *
+	 * Frames are processed once for each of these operations so that
+	 * the values are computed from the frames at the step of the operation.
+	 * Union of the content of the frames is trimmed so that the frames
+	 * stay aligned and the same range is used for normalizing the colors.
+	 *
+	 * @param images
+	 */
+	pub fn init_frames<'b, I>(&mut self, images: I) -> &mut Self
+	where
+		I: Iterator<Item = &'b RgbaImage> + Clone,
+	{
+		self.shared.clear();
+		while self.shared.len() < self.get_shared_count() {
+			self.collected = Some(Vec::new());
+			for image in images.clone() {
+				self.init(image.dimensions()).process(image.clone());
+			}
+			match self
+				.collected
+				.take()
+				.and_then(|values| values.into_iter().reduce(Shared::merge))
+			{
+				Some(value) => self.shared.push(value),
+				None => break,
+			}
+		}
+		self.frame = 0;
+		debug!("Shared: {:?}", self.shared);
+		self
+	}
+
+	/* Get the number of operations that are shared by the frames */
+	fn get_shared_count(&self) -> usize {
+		match &self.settings.pipeline {
+			Some(pipeline) => pipeline
+				.operations
+				.iter()
+				.filter(|operation| {
+					matches!(operation, Operation::Trim(_) | Operation::Normalize)
+				})
+				.count(),
+			None => {
+				usize::from(self.settings.image.trim.is_some())
+					+ usize::from(self.settings.color.normalize)
+			}
+		}
+	}
+
+	/**
+	 * Get the value of the next operation that is shared by the frames.
+	 *
+	 * Value is computed from the current image if it is not known yet
+	 * and collected for the frames if they are being initialized.
+	 *
+	 * @param  get
+	 * @return Shared
+	 */
+	fn get_shared<F: Fn(&RgbaImage) -> Shared>(&mut self, get: F) -> Shared {
+		let index = self.shared_step;
+		self.shared_step += 1;
+		match self.shared.get(index) {
+			Some(value) => *value,
+			None => {
+				let value = get(&self.image);
+				if index == self.shared.len() {
+					if let Some(collected) = &mut self.collected {
+						collected.push(value);
+					}
+				}
+				value
+			}
+		}
+	}
+
+	/**
+	 * Set the geometry to use for cropping and resizing.
 	 *
 	 * @param size
 	 */
 	pub fn init(&mut self, size: (u32, u32)) -> &mut Self {
-		let (mut width, mut height) = if !self.settings.image.resize.is_zero() {
-			(
-				self.settings.image.resize.width,
-				self.settings.image.resize.height,
-			)
+		self.init_geometry(if self.orientation.is_transposed() {
+			(size.1, size.0)
 		} else {
 			size
-		};
-		if self.settings.image.ratio > 0.
-			&& (self.settings.image.ratio - 1.).abs() > f32::EPSILON
-		{
-			let (w, h) = (width, height);
-			width = (w as f32 * self.settings.image.ratio) as u32;
-			height = (h as f32 * self.settings.image.ratio) as u32;
+		})
+	}
+
+	/**
+	 * Set the geometry from the size of the oriented image.
+	 *
+	 * Free rotation is not included in the geometry since the image
+	 * is resized before it to keep the aspect ratio of the content.
+	 *
+	 * @param size
+	 */
+	fn init_geometry(&mut self, size: (u32, u32)) -> &mut Self {
+		let settings = &self.settings.image;
+		let (mut width, mut height) = size;
+		let mut frame = None;
+		if !settings.resize.is_zero() {
+			let (scaled, resize_frame) = resize::get_size(
+				size,
+				(settings.resize.width, settings.resize.height),
+				settings.resize_mode,
+				settings.letterbox.is_some(),
+			);
+			(width, height) = scaled;
+			if resize_frame != scaled {
+				frame = Some(resize_frame);
+			}
 		}
-		if self.settings.image.rotate == 90 || self.settings.image.rotate == 270 {
+		if settings.ratio > 0. && (settings.ratio - 1.).abs() > f32::EPSILON {
 			let (w, h) = (width, height);
-			width = h;
-			height = w;
+			width = (w as f32 * settings.ratio) as u32;
+			height = (h as f32 * settings.ratio) as u32;
+			frame = frame.map(|(w, h)| {
+				(
+					(w as f32 * settings.ratio) as u32,
+					(h as f32 * settings.ratio) as u32,
+				)
+			});
+		}
+		if !settings.max_dimensions.is_zero() {
+			let factor = resize::get_max_factor(
+				frame.unwrap_or((width, height)),
+				(
+					settings.max_dimensions.width,
+					settings.max_dimensions.height,
+				),
+			);
+			if factor < 1. {
+				(width, height) = resize::scale((width, height), factor);
+				frame = frame.map(|frame| resize::scale(frame, factor));
+			}
 		}
-		self.geometry = Geometry::new(0, 0, width, height)
+		if rotate::is_right_angle(settings.rotate) {
+			(width, height) = rotate::get_size(
+				(width, height),
+				settings.rotate,
+				settings.rotate_crop,
+			);
+			frame = frame.map(|frame| {
+				rotate::get_size(frame, settings.rotate, settings.rotate_crop)
+			});
+		}
+		self.resize_frame = frame;
+		self.area = Geometry::new(0, 0, width, height)
 			.with_padding(self.settings.image.crop);
+		self.geometry = self.get_geometry();
 		debug!("{:?} -> {:?}", size, self.geometry);
 		self
 	}
 
+	/**
+	 * Get the geometry of the processed image.
+	 *
+	 * Free angle rotation is applied after the resize step.
+	 * Position of the cropped area is not kept if the image is
+	 * placed on a larger canvas (margins, shadow or background).
+	 *
+	 * @return Geometry
+	 */
+	fn get_geometry(&self) -> Geometry {
+		let settings = &self.settings.image;
+		let mut size = self
+			.resize_frame
+			.unwrap_or((self.area.width, self.area.height));
+		if !rotate::is_right_angle(settings.rotate) {
+			size = rotate::get_size(size, settings.rotate, settings.rotate_crop);
+		}
+		let mut canvas = size;
+		if let Some(extend) = settings.extend {
+			canvas = extend.get_size(canvas);
+		}
+		if let Some(shadow) = self.settings.canvas.shadow {
+			canvas = shadow.get_size(canvas);
+		}
+		if let Some(background) = self.settings.canvas.background {
+			canvas = background.get_size(canvas);
+		}
+		if canvas == size {
+			Geometry::new(self.area.x, self.area.y, size.0, size.1)
+		} else {
+			Geometry::new(0, 0, canvas.0, canvas.1)
+		}
+	}
+
 	/**
 	 * Process the image.
 	 *
+	 * Operations of the pipeline are applied in order if it is set.
+	 * Each call is counted as a frame for limiting the annotations.
+	 * Geometry is set from the processed image in pipeline mode
+	 * since the size depends on the order of the operations.
+	 *
 	 * @param image
 	 */
 	pub fn process(&mut self, image: RgbaImage) -> &mut Self {
 		self.image = image;
-		self.crop().flip().rotate().resize().blur().update_colors();
+		self.frame += 1;
+		self.step = 0;
+		self.shared_step = 0;
+		self.auto_orient();
+		match &self.settings.pipeline {
+			Some(pipeline) => {
+				for operation in &pipeline.operations {
+					self.apply(operation);
+				}
+				let (width, height) = self.image.dimensions();
+				self.geometry = Geometry::new(0, 0, width, height);
+			}
+			None => {
+				self.redact()
+					.trim()
+					.crop()
+					.flip()
+					.rotate(false)
+					.resize()
+					.rotate(true)
+					.blur()
+					.convolve()
+					.update_colors()
+					.extend()
+					.annotate()
+					.overlay()
+					.decorate();
+			}
+		}
 		self
 	}
 
@@ -91,21 +360,102 @@ impl<'a> ImageOps<'a> {
 		)
 	}
 
-	/* Resize the image */
+	/**
+	 * Process the captured image and keep its position.
+	 *
+	 * @param  image
+	 * @return Image
+	 */
+	pub fn process_image(&mut self, image: Image) -> Image {
+		let (width, height) = (image.geometry.width, image.geometry.height);
+		match RgbaImage::from_raw(
+			width,
+			height,
+			image.get_data(ExtendedColorType::Rgba8),
+		) {
+			Some(buffer) => {
+				let mut processed =
+					self.init(buffer.dimensions()).process(buffer).get_image();
+				processed.geometry.x = image.geometry.x;
+				processed.geometry.y = image.geometry.y;
+				processed
+			}
+			None => image,
+		}
+	}
+
+	/* Apply the EXIF orientation */
+	fn auto_orient(&mut self) -> &mut Self {
+		if self.orientation != Orientation::Normal {
+			info!("Orienting the image... ({:?})", self.orientation);
+			self.image = self.orientation.apply(self.image.clone());
+		}
+		self
+	}
+
+	/* Hide the areas of the image */
+	fn redact(&mut self) -> &mut Self {
+		let settings = self.settings;
+		for redaction in &settings.redactions {
+			self.apply(&Operation::Redact(*redaction));
+		}
+		self
+	}
+
+	/* Trim the uniform borders and update the geometry */
+	fn trim(&mut self) -> &mut Self {
+		if let Some(fuzz) = self.settings.image.trim {
+			self.apply(&Operation::Trim(fuzz));
+			self.init_geometry(self.image.dimensions());
+		}
+		self
+	}
+
+	/* Add the margins */
+	fn extend(&mut self) -> &mut Self {
+		if let Some(extend) = self.settings.image.extend {
+			self.apply(&Operation::Extend(extend));
+		}
+		self
+	}
+
+	/* Resize the image and fit it into the frame */
 	fn resize(&mut self) -> &mut Self {
 		if !self.settings.image.resize.is_zero()
+			|| !self.settings.image.max_dimensions.is_zero()
 			|| (self.settings.image.ratio > 0.
 				&& (self.settings.image.ratio - 1.).abs() > f32::EPSILON)
 		{
-			info!(
-				"Resizing image... ({}x{})",
-				self.geometry.width, self.geometry.height
-			);
-			self.image = imageops::resize(
+			let size = (self.area.width, self.area.height);
+			if self.image.dimensions() != size {
+				self.scale(size);
+			}
+			if let Some(frame) = self.resize_frame {
+				self.fit_frame(frame);
+			}
+		}
+		self
+	}
+
+	/* Scale the image to the given size */
+	fn scale(&mut self, size: (u32, u32)) -> &mut Self {
+		info!("Resizing image... ({}x{})", size.0, size.1);
+		self.image = imageops::resize(
+			&self.image,
+			size.0,
+			size.1,
+			self.settings.image.filter,
+		);
+		self
+	}
+
+	/* Crop or letterbox the image to the size of the frame */
+	fn fit_frame(&mut self, frame: (u32, u32)) -> &mut Self {
+		if self.image.dimensions() != frame {
+			self.image = resize::frame(
 				&self.image,
-				self.geometry.width,
-				self.geometry.height,
-				self.settings.image.filter,
+				frame,
+				self.settings.image.letterbox.unwrap_or(Rgba([0, 0, 0, 0])),
 			);
 		}
 		self
@@ -116,14 +466,14 @@ impl<'a> ImageOps<'a> {
 		if !self.settings.image.crop.is_zero() {
 			info!(
 				"Cropping the image... ({}x{})",
-				self.geometry.width, self.geometry.height
+				self.area.width, self.area.height
 			);
 			self.image = imageops::crop(
 				&mut self.image,
-				self.geometry.x.try_into().unwrap_or_default(),
-				self.geometry.y.try_into().unwrap_or_default(),
-				self.geometry.width,
-				self.geometry.height,
+				self.area.x.try_into().unwrap_or_default(),
+				self.area.y.try_into().unwrap_or_default(),
+				self.area.width,
+				self.area.height,
 			)
 			.to_image();
 		}
@@ -132,31 +482,18 @@ impl<'a> ImageOps<'a> {
 
 	/* Flip the image */
 	fn flip(&mut self) -> &mut Self {
-		match self.settings.image.flip {
-			Some(Flip::Horizontal) => {
-				info!("Flipping the image horizontally...");
-				imageops::flip_horizontal_in_place(&mut self.image)
-			}
-			Some(Flip::Vertical) => {
-				info!("Flipping the image vertically...");
-				imageops::flip_vertical_in_place(&mut self.image)
-			}
-			_ => {}
+		if let Some(flip) = self.settings.image.flip {
+			self.apply(&Operation::Flip(flip));
 		}
 		self
 	}
 
-	/* Rotate the image */
-	fn rotate(&mut self) -> &mut Self {
-		if self.settings.image.rotate == 90 {
-			info!("Rotating the image 90 degrees...");
-			self.image = imageops::rotate90(&self.image);
-		} else if self.settings.image.rotate == 180 {
-			info!("Rotating the image 180 degrees...");
-			self.image = imageops::rotate180(&self.image);
-		} else if self.settings.image.rotate == 270 {
-			info!("Rotating the image 270 degrees...");
-			self.image = imageops::rotate270(&self.image);
+	/* Rotate the image by a right angle before resizing or a free angle after */
+	fn rotate(&mut self, resized: bool) -> &mut Self {
+		if self.settings.image.rotate != 0.
+			&& rotate::is_right_angle(self.settings.image.rotate) != resized
+		{
+			self.apply(&Operation::Rotate(self.settings.image.rotate));
 		}
 		self
 	}
@@ -164,11 +501,28 @@ impl<'a> ImageOps<'a> {
 	/* Blur the image */
 	fn blur(&mut self) -> &mut Self {
 		if self.settings.image.blur > 0. {
-			info!(
-				"Blurring the image... (\u{03C3}={})",
-				self.settings.image.blur
-			);
-			self.image = imageops::blur(&self.image, self.settings.image.blur);
+			self.apply(&Operation::Blur(self.settings.image.blur));
+		}
+		self
+	}
+
+	/* Apply the convolution filters */
+	fn convolve(&mut self) -> &mut Self {
+		let convolution = &self.settings.convolution;
+		if convolution.sharpen {
+			self.apply(&Operation::Sharpen);
+		}
+		if let Some(unsharpen) = convolution.unsharpen {
+			self.apply(&Operation::Unsharpen(unsharpen));
+		}
+		if let Some(kernel) = &convolution.kernel {
+			self.apply(&Operation::Kernel(kernel.clone()));
+		}
+		if convolution.edges {
+			self.apply(&Operation::Edges);
+		}
+		if convolution.emboss {
+			self.apply(&Operation::Emboss);
 		}
 		self
 	}
@@ -180,23 +534,272 @@ impl<'a> ImageOps<'a> {
 		{
 			info!("Updating the colors...");
 		}
-		if self.settings.color.grayscale {
-			self.image = DynamicImage::ImageLuma8(colorops::grayscale(&self.image))
-				.to_rgba8();
+		let color = &self.settings.color;
+		for (enabled, operation) in [
+			(color.grayscale, Operation::Grayscale),
+			(color.invert, Operation::Invert),
+			(
+				color.brightness != 0,
+				Operation::Brightness(color.brightness),
+			),
+			(color.hue != 0, Operation::Hue(color.hue)),
+			(color.contrast != 0., Operation::Contrast(color.contrast)),
+			(color.normalize, Operation::Normalize),
+		] {
+			if enabled {
+				self.apply(&operation);
+			}
 		}
-		if self.settings.color.invert {
-			colorops::invert(&mut self.image);
+		if let Some(lut) = &self.lut {
+			lut.apply(&mut self.image);
 		}
-		if self.settings.color.brightness != 0 {
-			self.image =
-				colorops::brighten(&self.image, self.settings.color.brightness);
+		for (enabled, operation) in [
+			(
+				color.saturation != 0.,
+				Operation::Saturation(color.saturation),
+			),
+			(color.vibrance != 0., Operation::Vibrance(color.vibrance)),
+			(color.sepia, Operation::Sepia),
+		] {
+			if enabled {
+				self.apply(&operation);
+			}
 		}
-		if self.settings.color.hue != 0 {
-			self.image = colorops::huerotate(&self.image, self.settings.color.hue);
+		self
+	}
+
+	/* Draw the annotations */
+	fn annotate(&mut self) -> &mut Self {
+		let settings = self.settings;
+		for annotation in &settings.annotations {
+			self.draw(annotation);
 		}
-		if self.settings.color.contrast != 0. {
-			self.image =
-				colorops::contrast(&self.image, self.settings.color.contrast);
+		self
+	}
+
+	/* Blend the overlay image */
+	fn overlay(&mut self) -> &mut Self {
+		if let Some(overlay) = &self.settings.overlay {
+			info!("Adding the overlay... ({:?})", overlay.path);
+			overlay.apply(&mut self.image, &mut self.overlay);
+		}
+		self
+	}
+
+	/* Round the corners, add shadow and place on the canvas */
+	fn decorate(&mut self) -> &mut Self {
+		let canvas = self.settings.canvas;
+		if canvas.round != 0 {
+			self.apply(&Operation::Round(canvas.round));
+		}
+		if let Some(shadow) = canvas.shadow {
+			self.apply(&Operation::Shadow(shadow));
+		}
+		if let Some(background) = canvas.background {
+			self.apply(&Operation::Canvas(background));
+		}
+		self
+	}
+
+	/* Draw the annotation if it is visible on the current frame */
+	fn draw(&mut self, annotation: &Annotation) -> &mut Self {
+		if annotation.shape == Shape::Step {
+			self.step += 1;
+		}
+		if annotation.is_visible(self.frame) {
+			info!(
+				"Drawing the annotation... ({} {})",
+				annotation.shape.name(),
+				annotation
+			);
+			annotation.draw(&mut self.image, self.step);
+		}
+		self
+	}
+
+	/* Apply the operation to the current image */
+	fn apply(&mut self, operation: &Operation) -> &mut Self {
+		let (width, height) = self.image.dimensions();
+		match *operation {
+			Operation::Crop(padding) => {
+				let geometry =
+					Geometry::new(0, 0, width, height).with_padding(padding);
+				info!(
+					"Cropping the image... ({}x{})",
+					geometry.width, geometry.height
+				);
+				self.image = imageops::crop(
+					&mut self.image,
+					geometry.x.try_into().unwrap_or_default(),
+					geometry.y.try_into().unwrap_or_default(),
+					geometry.width,
+					geometry.height,
+				)
+				.to_image();
+			}
+			Operation::Trim(fuzz) => {
+				let padding = match self.get_shared(|image| {
+					Shared::Trim(image.dimensions(), border::get_trim(image, fuzz))
+				}) {
+					Shared::Trim(size, padding) if size == (width, height) => {
+						padding
+					}
+					_ => border::get_trim(&self.image, fuzz),
+				};
+				if let Some(padding) = padding.filter(|padding| !padding.is_zero()) {
+					self.apply(&Operation::Crop(padding));
+				}
+			}
+			Operation::Extend(extend) => {
+				info!("Extending the image... ({})", extend);
+				self.image = extend.apply(&self.image);
+			}
+			Operation::Resize(geometry) => {
+				let (size, frame) = resize::get_size(
+					(width, height),
+					(geometry.width, geometry.height),
+					self.settings.image.resize_mode,
+					self.settings.image.letterbox.is_some(),
+				);
+				self.scale(size).fit_frame(frame);
+			}
+			Operation::Ratio(ratio) => {
+				self.scale((
+					(width as f32 * ratio) as u32,
+					(height as f32 * ratio) as u32,
+				));
+			}
+			Operation::MaxDimensions(geometry) => {
+				let factor = resize::get_max_factor(
+					(width, height),
+					(geometry.width, geometry.height),
+				);
+				if factor < 1. {
+					self.scale(resize::scale((width, height), factor));
+				}
+			}
+			Operation::Rotate(rotate) => {
+				info!("Rotating the image {} degrees...", rotate);
+				self.image = rotate::rotate(
+					&self.image,
+					rotate,
+					self.settings.image.filter,
+					self.settings.image.rotate_fill,
+					self.settings.image.rotate_crop,
+				);
+			}
+			Operation::Flip(Flip::Horizontal) => {
+				info!("Flipping the image horizontally...");
+				imageops::flip_horizontal_in_place(&mut self.image)
+			}
+			Operation::Flip(Flip::Vertical) => {
+				info!("Flipping the image vertically...");
+				imageops::flip_vertical_in_place(&mut self.image)
+			}
+			Operation::Blur(sigma) => {
+				info!("Blurring the image... (\u{03C3}={})", sigma);
+				self.image = imageops::blur(&self.image, sigma);
+			}
+			Operation::Sharpen => {
+				info!("Sharpening the image...");
+				self.image = Kernel::sharpen().apply(&self.image);
+			}
+			Operation::Unsharpen(unsharpen) => {
+				info!("Applying unsharp mask... ({})", unsharpen);
+				self.image = unsharpen.apply(&self.image);
+			}
+			Operation::Kernel(ref kernel) => {
+				info!("Convolving the image... ({})", kernel);
+				self.image = kernel.apply(&self.image);
+			}
+			Operation::Edges => {
+				info!("Detecting the edges...");
+				self.image = Kernel::edges().apply(&self.image);
+			}
+			Operation::Emboss => {
+				info!("Embossing the image...");
+				self.image = Kernel::emboss().apply(&self.image);
+			}
+			Operation::Grayscale => {
+				self.image =
+					DynamicImage::ImageLuma8(colorops::grayscale(&self.image))
+						.to_rgba8();
+			}
+			Operation::Invert => colorops::invert(&mut self.image),
+			Operation::Hue(hue) => {
+				self.image = colorops::huerotate(&self.image, hue);
+			}
+			Operation::Contrast(contrast) => {
+				self.image = colorops::contrast(&self.image, contrast);
+			}
+			Operation::Brightness(brightness) => {
+				self.image = colorops::brighten(&self.image, brightness);
+			}
+			Operation::Normalize => {
+				info!("Normalizing the colors...");
+				let range = match self
+					.get_shared(|image| Shared::Range(adjust::get_range(image)))
+				{
+					Shared::Range(range) => range,
+					_ => adjust::get_range(&self.image),
+				};
+				Lut::normalize(range).apply(&mut self.image);
+			}
+			Operation::Levels(levels) => {
+				info!("Adjusting the levels... ({})", levels);
+				levels.get_lut().apply(&mut self.image);
+			}
+			Operation::Gamma(gamma) => {
+				info!("Adjusting the gamma... ({})", gamma);
+				Lut::gamma(gamma).apply(&mut self.image);
+			}
+			Operation::Curve(ref curve) => {
+				info!("Applying the curve... ({})", curve);
+				curve.get_lut().apply(&mut self.image);
+			}
+			Operation::Temperature(temperature) => {
+				info!("Adjusting the temperature... ({})", temperature);
+				Lut::temperature(temperature).apply(&mut self.image);
+			}
+			Operation::Tint(tint) => {
+				info!("Adjusting the tint... ({})", tint);
+				Lut::tint(tint).apply(&mut self.image);
+			}
+			Operation::Saturation(saturation) => {
+				info!("Adjusting the saturation... ({})", saturation);
+				adjust::saturate(&mut self.image, saturation, false);
+			}
+			Operation::Vibrance(vibrance) => {
+				info!("Adjusting the vibrance... ({})", vibrance);
+				adjust::saturate(&mut self.image, vibrance, true);
+			}
+			Operation::Sepia => {
+				info!("Applying the sepia tone...");
+				adjust::sepia(&mut self.image);
+			}
+			Operation::Redact(redaction) => {
+				info!("Redacting the image... ({})", redaction);
+				redaction.apply(&mut self.image);
+			}
+			Operation::Round(radius) => {
+				info!("Rounding the corners... (r={})", radius);
+				canvas::round_corners(&mut self.image, radius);
+			}
+			Operation::Shadow(shadow) => {
+				info!("Adding shadow... ({})", shadow);
+				self.image = shadow.apply(&self.image);
+			}
+			Operation::Canvas(background) => {
+				info!("Placing on the canvas... ({})", background);
+				self.image = background.apply(&self.image);
+			}
+			Operation::Annotate(ref annotation) => {
+				self.draw(annotation);
+			}
+			Operation::Overlay(ref overlay) => {
+				info!("Adding the overlay... ({:?})", overlay.path);
+				overlay.apply(&mut self.image, &mut self.overlay);
+			}
 		}
 		self
 	}
@@ -205,9 +808,15 @@ impl<'a> ImageOps<'a> {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::edit::border::Extend;
+	use crate::edit::canvas::{Background, Shadow};
+	use crate::edit::pipeline::Pipeline;
+	use crate::edit::redact::Redaction;
+	use crate::edit::settings::CanvasSettings;
 	use image::{ExtendedColorType, Rgba, RgbaImage};
 	use pretty_assertions::assert_eq;
 	#[test]
+	#[allow(clippy::field_reassign_with_default)]
 	fn test_edit() {
 		let mut image = RgbaImage::new(32, 32);
 		for x in 15..=17 {
@@ -221,7 +830,7 @@ mod tests {
 		settings.image.ratio = 2.;
 		settings.image.resize = Geometry::new(0, 0, 32, 42);
 		settings.image.flip = Some(Flip::Vertical);
-		settings.image.rotate = 270;
+		settings.image.rotate = 270.;
 		settings.image.blur = 1.5;
 		settings.color.grayscale = true;
 		settings.color.invert = true;
@@ -240,5 +849,140 @@ mod tests {
 			width * height * 4,
 			image.get_data(ExtendedColorType::Rgba8).len() as u32
 		);
+		let mut settings = EditSettings::default();
+		settings.image.crop.top = 1;
+		let image = RgbaImage::from_fn(4, 2, |x, _| Rgba([x as u8, 0, 0, 255]));
+		let mut imageops = ImageOps::new(&settings);
+		imageops
+			.orient(Orientation::Rotate90)
+			.init(image.dimensions())
+			.process(image.clone());
+		assert_eq!((2, 3), imageops.image.dimensions());
+		assert_eq!(3, imageops.image.get_pixel(0, 2)[0]);
+		settings.image.auto_orient = false;
+		let mut imageops = ImageOps::new(&settings);
+		imageops
+			.orient(Orientation::Rotate90)
+			.init(image.dimensions())
+			.process(image);
+		assert_eq!((4, 1), imageops.image.dimensions());
+		let image = RgbaImage::from_pixel(4, 2, Rgba([0, 0, 0, 255]));
+		let mut settings = EditSettings::default();
+		settings.pipeline = Some(Pipeline::new(vec![
+			Operation::Crop(Padding::new(0, 1, 0, 0)),
+			Operation::Rotate(90.),
+			Operation::Ratio(2.),
+			Operation::Rotate(90.),
+			Operation::Invert,
+		]));
+		let image = ImageOps::new(&settings)
+			.init(image.dimensions())
+			.process(image)
+			.get_image();
+		assert_eq!(Geometry::new(0, 0, 6, 4), image.geometry);
+		assert_eq!(Some(&255), image.get_data(ExtendedColorType::Rgba8).first());
+		let mut settings = EditSettings::default();
+		settings.annotations = vec![Annotation::parse(
+			Shape::Rectangle,
+			"4x4+0+0 fill=ffffff frames=2-3",
+		)
+		.unwrap()];
+		let mut imageops = ImageOps::new(&settings);
+		for (frame, value) in [0, 255, 255, 0].iter().enumerate() {
+			let image = RgbaImage::new(4, 4);
+			imageops.init(image.dimensions()).process(image);
+			assert_eq!(*value, imageops.image.get_pixel(2, 2)[0], "{frame}");
+		}
+		let settings = EditSettings {
+			redactions: vec![Redaction::parse("2x1+1+0:fill=ffffff").unwrap()],
+			..EditSettings::default()
+		};
+		let image = ImageOps::new(&settings).process_image(Image::new(
+			vec![Rgba([0, 0, 0, 0]); 6],
+			false,
+			Geometry::new(5, 5, 3, 2),
+		));
+		assert_eq!(Geometry::new(5, 5, 3, 2), image.geometry);
+		assert_eq!(
+			vec![0, 255, 255, 0, 0, 0],
+			image.get_data(ExtendedColorType::L8)
+		);
+		let mut settings = EditSettings {
+			canvas: CanvasSettings::new(
+				1,
+				Shadow::parse("0:1:1").ok(),
+				Background::parse("2,ffffff").ok(),
+			),
+			..EditSettings::default()
+		};
+		settings.image.crop = Padding::new(1, 0, 0, 0);
+		let image = ImageOps::new(&settings)
+			.init((4, 3))
+			.process(RgbaImage::from_pixel(4, 3, Rgba([0, 0, 0, 255])))
+			.get_image();
+		assert_eq!(Geometry::new(0, 0, 10, 8), image.geometry);
+		assert_eq!(10 * 8, image.get_data(ExtendedColorType::L8).len());
+		let mut settings = EditSettings::default();
+		settings.image.resize = Geometry::new(0, 0, 40, 20);
+		settings.image.rotate = -30.;
+		settings.image.rotate_fill = Rgba([255, 255, 255, 255]);
+		let image = RgbaImage::from_fn(30, 10, |x, _| {
+			Rgba(if x < 15 {
+				[255, 0, 0, 255]
+			} else {
+				[0, 0, 255, 255]
+			})
+		});
+		let mut imageops = ImageOps::new(&settings);
+		imageops.init((30, 10));
+		assert_eq!(Geometry::new(0, 0, 45, 38), imageops.geometry);
+		imageops.process(image.clone());
+		assert_eq!((45, 38), imageops.image.dimensions());
+		assert_eq!(Geometry::new(0, 0, 45, 38), imageops.get_image().geometry);
+		assert_eq!(Rgba([255, 255, 255, 255]), *imageops.image.get_pixel(0, 0));
+		assert_eq!(
+			rotate::rotate(
+				&imageops::resize(&image, 40, 20, settings.image.filter),
+				-30.,
+				settings.image.filter,
+				settings.image.rotate_fill,
+				false,
+			),
+			imageops.image
+		);
+		assert_eq!(Rgba([255, 0, 0, 255]), *imageops.image.get_pixel(12, 24));
+		assert_eq!(Rgba([0, 0, 255, 255]), *imageops.image.get_pixel(32, 12));
+		settings.image.rotate_crop = true;
+		let mut imageops = ImageOps::new(&settings);
+		imageops.init((30, 10));
+		imageops.process(RgbaImage::from_pixel(30, 10, Rgba([0, 0, 0, 255])));
+		assert_eq!(Rgba([0, 0, 0, 255]), *imageops.image.get_pixel(0, 0));
+		let mut settings = EditSettings::default();
+		settings.image.ratio = 0.5;
+		let mut imageops = ImageOps::new(&settings);
+		imageops.init((5, 3));
+		assert_eq!(Geometry::new(0, 0, 2, 1), imageops.geometry);
+		let mut settings = EditSettings::default();
+		settings.image.trim = Some(0.);
+		settings.image.crop = Padding::new(0, 0, 0, 1);
+		settings.image.extend = Extend::parse("0:0:1:0:ffffff").ok();
+		let images = [(1, 1), (3, 2)].map(|(x, y)| {
+			let mut image = RgbaImage::new(5, 4);
+			image.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+			image
+		});
+		let mut imageops = ImageOps::new(&settings);
+		let blank = RgbaImage::new(5, 4);
+		imageops.init_frames(images.iter().chain([&blank]));
+		for image in images.clone() {
+			let image = imageops.init(image.dimensions()).process(image).get_image();
+			assert_eq!(Geometry::new(0, 0, 2, 3), image.geometry);
+		}
+		assert_eq!(Rgba([255, 0, 0, 255]), *imageops.image.get_pixel(1, 1));
+		assert_eq!(Rgba([255, 255, 255, 255]), *imageops.image.get_pixel(0, 2));
+		settings.image.crop = Padding::default();
+		let mut imageops = ImageOps::new(&settings);
+		imageops.init((5, 4)).process(images[0].clone());
+		assert_eq!((1, 2), imageops.image.dimensions());
 	}
 }
@@ -0,0 +1,159 @@
+use exif::{In, Reader as ExifReader, Tag};
+use image::imageops;
+use image::RgbaImage;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/* EXIF orientation of the image */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Orientation {
+	#[default]
+	Normal,
+	FlipHorizontal,
+	Rotate180,
+	FlipVertical,
+	Transpose,
+	Rotate90,
+	Transverse,
+	Rotate270,
+}
+
+impl Orientation {
+	/**
+	 * Create an Orientation from the value of the EXIF tag.
+	 *
+	 * @param  value
+	 * @return Orientation (Option)
+	 */
+	pub fn from_exif(value: u32) -> Option<Self> {
+		match value {
+			1 => Some(Self::Normal),
+			2 => Some(Self::FlipHorizontal),
+			3 => Some(Self::Rotate180),
+			4 => Some(Self::FlipVertical),
+			5 => Some(Self::Transpose),
+			6 => Some(Self::Rotate90),
+			7 => Some(Self::Transverse),
+			8 => Some(Self::Rotate270),
+			_ => None,
+		}
+	}
+
+	/**
+	 * Read the orientation from the EXIF data of the file.
+	 *
+	 * @param  path
+	 * @return Orientation
+	 */
+	pub fn read(path: &Path) -> Self {
+		File::open(path)
+			.ok()
+			.and_then(|file| {
+				ExifReader::new()
+					.read_from_container(&mut BufReader::new(file))
+					.ok()
+			})
+			.and_then(|exif| {
+				exif.get_field(Tag::Orientation, In::PRIMARY)?
+					.value
+					.get_uint(0)
+			})
+			.and_then(Self::from_exif)
+			.unwrap_or_default()
+	}
+
+	/**
+	 * Check if the width and height are swapped by the orientation.
+	 *
+	 * @return bool
+	 */
+	pub fn is_transposed(&self) -> bool {
+		matches!(
+			self,
+			Self::Transpose | Self::Rotate90 | Self::Transverse | Self::Rotate270
+		)
+	}
+
+	/**
+	 * Rotate/flip the image to the normal orientation.
+	 *
+	 * @param  image
+	 * @return RgbaImage
+	 */
+	pub fn apply(&self, mut image: RgbaImage) -> RgbaImage {
+		match self {
+			Self::Normal => {}
+			Self::FlipHorizontal => imageops::flip_horizontal_in_place(&mut image),
+			Self::Rotate180 => imageops::rotate180_in_place(&mut image),
+			Self::FlipVertical => imageops::flip_vertical_in_place(&mut image),
+			Self::Transpose => {
+				image = imageops::rotate90(&image);
+				imageops::flip_horizontal_in_place(&mut image);
+			}
+			Self::Rotate90 => image = imageops::rotate90(&image),
+			Self::Transverse => {
+				image = imageops::rotate270(&image);
+				imageops::flip_horizontal_in_place(&mut image);
+			}
+			Self::Rotate270 => image = imageops::rotate270(&image),
+		}
+		image
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use image::codecs::jpeg::JpegEncoder;
+	use image::{ColorType, ImageEncoder, Rgba};
+	use pretty_assertions::assert_eq;
+	use std::fs;
+	#[test]
+	fn test_orientation() -> image::ImageResult<()> {
+		let image =
+			RgbaImage::from_fn(3, 2, |x, y| Rgba([x as u8, y as u8, 0, 255]));
+		for (value, pixels) in [
+			(1, [[0, 0], [2, 1]]),
+			(2, [[2, 0], [0, 1]]),
+			(3, [[2, 1], [0, 0]]),
+			(4, [[0, 1], [2, 0]]),
+			(5, [[0, 0], [2, 1]]),
+			(6, [[0, 1], [2, 0]]),
+			(7, [[2, 1], [0, 0]]),
+			(8, [[2, 0], [0, 1]]),
+		] {
+			let orientation = Orientation::from_exif(value).unwrap_or_default();
+			let oriented = orientation.apply(image.clone());
+			assert_eq!(orientation.is_transposed(), oriented.dimensions() == (2, 3));
+			let (width, height) = oriented.dimensions();
+			assert_eq!(
+				pixels,
+				[
+					[oriented.get_pixel(0, 0)[0], oriented.get_pixel(0, 0)[1]],
+					[
+						oriented.get_pixel(width - 1, height - 1)[0],
+						oriented.get_pixel(width - 1, height - 1)[1]
+					]
+				]
+			);
+		}
+		let mut data = Vec::new();
+		JpegEncoder::new(&mut data).write_image(
+			&[0; 3 * 2 * 3],
+			3,
+			2,
+			ColorType::Rgb8,
+		)?;
+		let mut segment = vec![0xFF, 0xE1, 0, 34];
+		segment.extend(b"Exif\0\0II*\0\x08\0\0\0\x01\0");
+		segment.extend([0x12, 0x01, 3, 0, 1, 0, 0, 0, 6, 0, 0, 0, 0, 0, 0, 0]);
+		data.splice(2..2, segment);
+		let path = Path::new("test_orientation.jpg");
+		fs::write(path, data)?;
+		assert_eq!(Orientation::Rotate90, Orientation::read(path));
+		fs::remove_file(path)?;
+		assert_eq!(Orientation::Normal, Orientation::read(path));
+		Ok(())
+	}
+}
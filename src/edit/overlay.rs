@@ -0,0 +1,386 @@
+use crate::image::qoi;
+use image::imageops::{self, FilterType};
+use image::RgbaImage;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+/* Position of the overlay on the image */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Gravity {
+	NorthWest,
+	North,
+	NorthEast,
+	West,
+	Center,
+	East,
+	SouthWest,
+	South,
+	#[default]
+	SouthEast,
+}
+
+impl Gravity {
+	/**
+	 * Create a Gravity from the given name.
+	 *
+	 * @param  name
+	 * @return Gravity (Option)
+	 */
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"northwest" => Some(Self::NorthWest),
+			"north" => Some(Self::North),
+			"northeast" => Some(Self::NorthEast),
+			"west" => Some(Self::West),
+			"center" => Some(Self::Center),
+			"east" => Some(Self::East),
+			"southwest" => Some(Self::SouthWest),
+			"south" => Some(Self::South),
+			"southeast" => Some(Self::SouthEast),
+			_ => None,
+		}
+	}
+
+	/**
+	 * Get the name of the Gravity.
+	 *
+	 * @return str
+	 */
+	pub fn name(&self) -> &str {
+		match self {
+			Self::NorthWest => "northwest",
+			Self::North => "north",
+			Self::NorthEast => "northeast",
+			Self::West => "west",
+			Self::Center => "center",
+			Self::East => "east",
+			Self::SouthWest => "southwest",
+			Self::South => "south",
+			Self::SouthEast => "southeast",
+		}
+	}
+
+	/**
+	 * Get the position of the overlay.
+	 *
+	 * @param  size
+	 * @param  overlay_size
+	 * @param  offset
+	 * @return position
+	 */
+	pub fn get_position(
+		&self,
+		size: (u32, u32),
+		overlay_size: (u32, u32),
+		offset: (i32, i32),
+	) -> (i64, i64) {
+		let get_value = |align: i8, size: u32, overlay_size: u32, offset: i32| {
+			let space = i64::from(size) - i64::from(overlay_size);
+			match align {
+				-1 => i64::from(offset),
+				0 => space / 2 + i64::from(offset),
+				_ => space - i64::from(offset),
+			}
+		};
+		let (horizontal, vertical) = match self {
+			Self::NorthWest => (-1, -1),
+			Self::North => (0, -1),
+			Self::NorthEast => (1, -1),
+			Self::West => (-1, 0),
+			Self::Center => (0, 0),
+			Self::East => (1, 0),
+			Self::SouthWest => (-1, 1),
+			Self::South => (0, 1),
+			Self::SouthEast => (1, 1),
+		};
+		(
+			get_value(horizontal, size.0, overlay_size.0, offset.0),
+			get_value(vertical, size.1, overlay_size.1, offset.1),
+		)
+	}
+}
+
+/* Overlay image settings */
+#[derive(Clone, PartialEq)]
+pub struct Overlay {
+	pub path: PathBuf,
+	pub gravity: Gravity,
+	pub offset: (i32, i32),
+	pub opacity: f32,
+	pub scale: Option<f32>,
+	image: RgbaImage,
+}
+
+/* Debug implementation without the pixels of the image */
+impl fmt::Debug for Overlay {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Overlay")
+			.field("path", &self.path)
+			.field("gravity", &self.gravity)
+			.field("offset", &self.offset)
+			.field("opacity", &self.opacity)
+			.field("scale", &self.scale)
+			.finish_non_exhaustive()
+	}
+}
+
+/* Display implementation for user-facing output */
+impl fmt::Display for Overlay {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{} gravity={} offset={},{} opacity={}",
+			self.path.display(),
+			self.gravity.name(),
+			self.offset.0,
+			self.offset.1,
+			self.opacity
+		)?;
+		if let Some(scale) = self.scale {
+			write!(f, " scale={scale}")?;
+		}
+		Ok(())
+	}
+}
+
+impl Overlay {
+	/**
+	 * Create a new Overlay object and load the image.
+	 *
+	 * @param  path
+	 * @param  gravity
+	 * @param  offset
+	 * @param  opacity
+	 * @param  scale (Option)
+	 * @return Overlay (Result)
+	 */
+	pub fn new(
+		path: PathBuf,
+		gravity: Gravity,
+		offset: (i32, i32),
+		opacity: f32,
+		scale: Option<f32>,
+	) -> Result<Self, String> {
+		let mut overlay = Self {
+			path,
+			gravity,
+			offset,
+			opacity,
+			scale,
+			image: RgbaImage::default(),
+		};
+		overlay.image = overlay.load()?;
+		Ok(overlay)
+	}
+
+	/**
+	 * Create an Overlay from the path and the values of the options.
+	 *
+	 * @param  path
+	 * @param  gravity (Option)
+	 * @param  offset (Option)
+	 * @param  opacity (Option)
+	 * @param  scale (Option)
+	 * @return Overlay (Result)
+	 */
+	pub fn from_values(
+		path: &str,
+		gravity: Option<&str>,
+		offset: Option<&str>,
+		opacity: Option<&str>,
+		scale: Option<&str>,
+	) -> Result<Self, String> {
+		let invalid = |name: &str, value: &str| {
+			format!("Invalid {name} for the overlay: {value:?}")
+		};
+		if path.is_empty() {
+			return Err(String::from("Overlay file is not specified"));
+		}
+		Self::new(
+			PathBuf::from(path),
+			match gravity {
+				Some(gravity) => Gravity::from_name(gravity)
+					.ok_or_else(|| invalid("gravity", gravity))?,
+				None => Gravity::default(),
+			},
+			match offset {
+				Some(offset) => Self::parse_offset(offset)
+					.ok_or_else(|| invalid("offset", offset))?,
+				None => (0, 0),
+			},
+			match opacity {
+				Some(opacity) => opacity
+					.parse()
+					.ok()
+					.filter(|v| (0. ..=1.).contains(v))
+					.ok_or_else(|| invalid("opacity", opacity))?,
+				None => 1.,
+			},
+			match scale {
+				Some(scale) => Some(
+					scale
+						.parse()
+						.ok()
+						.filter(|v: &f32| *v > 0.)
+						.ok_or_else(|| invalid("scale", scale))?,
+				),
+				None => None,
+			},
+		)
+	}
+
+	/**
+	 * Parse an Overlay from `FILE [gravity=G] [offset=X,Y] [opacity=O] [scale=S]`.
+	 *
+	 * @param  value
+	 * @return Overlay (Result)
+	 */
+	pub fn parse(value: &str) -> Result<Self, String> {
+		let mut path = value.trim();
+		let (mut gravity, mut offset, mut opacity, mut scale) =
+			(None, None, None, None);
+		while let Some((rest, option)) = path.rsplit_once(char::is_whitespace) {
+			match option.split_once('=') {
+				Some(("gravity", value)) => gravity = Some(value),
+				Some(("offset", value)) => offset = Some(value),
+				Some(("opacity", value)) => opacity = Some(value),
+				Some(("scale", value)) => scale = Some(value),
+				_ => break,
+			}
+			path = rest.trim_end();
+		}
+		Self::from_values(path, gravity, offset, opacity, scale)
+	}
+
+	/**
+	 * Parse the offset from `X,Y`.
+	 *
+	 * @param  offset
+	 * @return offset (Option)
+	 */
+	pub fn parse_offset(offset: &str) -> Option<(i32, i32)> {
+		let (x, y) = offset.split_once(',')?;
+		Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+	}
+
+	/**
+	 * Load the overlay image with the opacity applied.
+	 *
+	 * @return RgbaImage (Result)
+	 */
+	fn load(&self) -> Result<RgbaImage, String> {
+		debug!("Loading the overlay from {:?}", self.path);
+		let mut image = qoi::read_image(&self.path)
+			.map_err(|e| format!("Failed to load the overlay {:?}: {e}", self.path))?
+			.to_rgba8();
+		if self.opacity < 1. {
+			image.pixels_mut().for_each(|pixel| {
+				pixel[3] = (f32::from(pixel[3]) * self.opacity.max(0.)).round() as u8
+			});
+		}
+		Ok(image)
+	}
+
+	/**
+	 * Blend the overlay onto the image.
+	 *
+	 * @param image
+	 * @param cache
+	 */
+	pub fn apply(&self, image: &mut RgbaImage, cache: &mut OverlayCache) {
+		let source = &self.image;
+		let size = match self.scale {
+			Some(scale) if source.width() != 0 => {
+				let width = ((image.width() as f32 * scale).round() as u32).max(1);
+				(
+					width,
+					((source.height() * width) as f32 / source.width() as f32)
+						.round()
+						.max(1.) as u32,
+				)
+			}
+			_ => source.dimensions(),
+		};
+		let overlay = if size == source.dimensions() {
+			source
+		} else {
+			cache
+				.scaled
+				.entry((self.path.clone(), self.opacity.to_bits(), size))
+				.or_insert_with(|| {
+					imageops::resize(source, size.0, size.1, FilterType::Lanczos3)
+				})
+		};
+		let (x, y) =
+			self.gravity
+				.get_position(image.dimensions(), size, self.offset);
+		imageops::overlay(image, overlay, x, y);
+	}
+}
+
+/* Scaled overlay images that are reused for each frame */
+#[derive(Debug, Default)]
+pub struct OverlayCache {
+	scaled: HashMap<(PathBuf, u32, (u32, u32)), RgbaImage>,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use image::Rgba;
+	use pretty_assertions::assert_eq;
+	use std::fs;
+	#[test]
+	fn test_overlay() -> image::ImageResult<()> {
+		assert_eq!(Some(Gravity::South), Gravity::from_name("south"));
+		assert_eq!(None, Gravity::from_name("up"));
+		assert_eq!(
+			(85, 40),
+			Gravity::SouthEast.get_position((100, 50), (10, 5), (5, 5))
+		);
+		assert_eq!(
+			(45, 20),
+			Gravity::Center.get_position((100, 50), (10, 10), (0, 0))
+		);
+		assert_eq!(
+			(-1, 2),
+			Gravity::NorthWest.get_position((100, 50), (10, 10), (-1, 2))
+		);
+		assert_eq!(Some((10, -5)), Overlay::parse_offset("10, -5"));
+		assert_eq!(None, Overlay::parse_offset("10"));
+		let path = PathBuf::from("test_overlay.png");
+		RgbaImage::from_pixel(4, 2, Rgba([255, 0, 0, 255])).save(&path)?;
+		let overlay =
+			Overlay::new(path.clone(), Gravity::NorthEast, (1, 0), 0.5, Some(0.5))
+				.unwrap();
+		assert_eq!(
+			"test_overlay.png gravity=northeast offset=1,0 opacity=0.5 scale=0.5",
+			overlay.to_string()
+		);
+		assert_eq!(Ok(overlay.clone()), Overlay::parse(&overlay.to_string()));
+		assert_eq!(
+			Gravity::SouthEast,
+			Overlay::parse("test_overlay.png").unwrap().gravity
+		);
+		assert!(Overlay::parse("test_overlay.png opacity=2").is_err());
+		assert!(Overlay::parse("gravity=north").is_err());
+		let mut cache = OverlayCache::default();
+		let mut image = RgbaImage::from_pixel(8, 4, Rgba([0, 0, 255, 255]));
+		overlay.apply(&mut image, &mut cache);
+		assert_eq!(Rgba([0, 0, 255, 255]), *image.get_pixel(2, 0));
+		assert_eq!(Rgba([128, 0, 127, 254]), *image.get_pixel(3, 0));
+		assert_eq!(Rgba([128, 0, 127, 254]), *image.get_pixel(6, 1));
+		assert_eq!(Rgba([0, 0, 255, 255]), *image.get_pixel(7, 0));
+		assert_eq!(Rgba([0, 0, 255, 255]), *image.get_pixel(3, 2));
+		let mut image = RgbaImage::from_pixel(8, 4, Rgba([0, 0, 255, 255]));
+		overlay.apply(&mut image, &mut cache);
+		assert_eq!(Rgba([128, 0, 127, 254]), *image.get_pixel(3, 0));
+		let mut image = RgbaImage::new(16, 8);
+		overlay.apply(&mut image, &mut cache);
+		assert!(cache.scaled.keys().any(|(_, _, size)| *size == (8, 4)));
+		fs::remove_file(&path)?;
+		assert!(Overlay::parse("test_overlay.png").is_err());
+		Ok(())
+	}
+}
@@ -0,0 +1,473 @@
+use crate::edit::adjust::{Curve, Levels};
+use crate::edit::annotate::{Annotation, Shape};
+use crate::edit::border::Extend;
+use crate::edit::canvas::{Background, Shadow};
+use crate::edit::convolve::{Kernel, Unsharpen};
+use crate::edit::overlay::Overlay;
+use crate::edit::redact::Redaction;
+use crate::edit::settings::Flip;
+use crate::image::geometry::Geometry;
+use crate::image::padding::Padding;
+use clap::ArgMatches;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/* Names of the arguments that correspond to an operation */
+pub const OPERATIONS: &[&str] = &[
+	"trim",
+	"crop",
+	"extend",
+	"resize",
+	"ratio",
+	"max-dimensions",
+	"rotate",
+	"flip",
+	"blur",
+	"sharpen",
+	"unsharpen",
+	"kernel",
+	"edges",
+	"emboss",
+	"grayscale",
+	"invert",
+	"hue",
+	"contrast",
+	"brightness",
+	"normalize",
+	"levels",
+	"gamma",
+	"curve",
+	"temperature",
+	"tint",
+	"saturation",
+	"vibrance",
+	"sepia",
+	"redact",
+	"round",
+	"shadow",
+	"canvas",
+	"rect",
+	"ellipse",
+	"line",
+	"arrow",
+	"step",
+	"text",
+	"overlay",
+];
+
+/* Image operation */
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operation {
+	Trim(f32),
+	Crop(Padding),
+	Extend(Extend),
+	Resize(Geometry),
+	Ratio(f32),
+	MaxDimensions(Geometry),
+	Rotate(f32),
+	Flip(Flip),
+	Blur(f32),
+	Sharpen,
+	Unsharpen(Unsharpen),
+	Kernel(Kernel),
+	Edges,
+	Emboss,
+	Grayscale,
+	Invert,
+	Hue(i32),
+	Contrast(f32),
+	Brightness(i32),
+	Normalize,
+	Levels(Levels),
+	Gamma(f32),
+	Curve(Curve),
+	Temperature(f32),
+	Tint(f32),
+	Saturation(f32),
+	Vibrance(f32),
+	Sepia,
+	Redact(Redaction),
+	Round(u32),
+	Shadow(Shadow),
+	Canvas(Background),
+	Annotate(Annotation),
+	Overlay(Overlay),
+}
+
+/* Display implementation for user-facing output */
+impl fmt::Display for Operation {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Trim(fuzz) => write!(f, "trim {fuzz}"),
+			Self::Crop(padding) => write!(f, "crop {padding}"),
+			Self::Extend(extend) => write!(f, "extend {extend}"),
+			Self::Resize(geometry) => write!(f, "resize {geometry}"),
+			Self::Ratio(ratio) => write!(f, "ratio {ratio}"),
+			Self::MaxDimensions(geometry) => write!(f, "max-dimensions {geometry}"),
+			Self::Rotate(rotate) => write!(f, "rotate {rotate}"),
+			Self::Flip(Flip::Horizontal) => write!(f, "flip horizontal"),
+			Self::Flip(Flip::Vertical) => write!(f, "flip vertical"),
+			Self::Blur(sigma) => write!(f, "blur {sigma}"),
+			Self::Sharpen => write!(f, "sharpen"),
+			Self::Unsharpen(unsharpen) => write!(f, "unsharpen {unsharpen}"),
+			Self::Kernel(kernel) => write!(f, "kernel {kernel}"),
+			Self::Edges => write!(f, "edges"),
+			Self::Emboss => write!(f, "emboss"),
+			Self::Grayscale => write!(f, "grayscale"),
+			Self::Invert => write!(f, "invert"),
+			Self::Hue(hue) => write!(f, "hue {hue}"),
+			Self::Contrast(contrast) => write!(f, "contrast {contrast}"),
+			Self::Brightness(brightness) => write!(f, "brightness {brightness}"),
+			Self::Normalize => write!(f, "normalize"),
+			Self::Levels(levels) => write!(f, "levels {levels}"),
+			Self::Gamma(gamma) => write!(f, "gamma {gamma}"),
+			Self::Curve(curve) => write!(f, "curve {curve}"),
+			Self::Temperature(temperature) => write!(f, "temperature {temperature}"),
+			Self::Tint(tint) => write!(f, "tint {tint}"),
+			Self::Saturation(saturation) => write!(f, "saturation {saturation}"),
+			Self::Vibrance(vibrance) => write!(f, "vibrance {vibrance}"),
+			Self::Sepia => write!(f, "sepia"),
+			Self::Redact(redaction) => write!(f, "redact {redaction}"),
+			Self::Round(radius) => write!(f, "round {radius}"),
+			Self::Shadow(shadow) => write!(f, "shadow {shadow}"),
+			Self::Canvas(background) => write!(f, "canvas {background}"),
+			Self::Annotate(annotation) => {
+				write!(f, "{} {annotation}", annotation.shape.name())
+			}
+			Self::Overlay(overlay) => write!(f, "overlay {overlay}"),
+		}
+	}
+}
+
+impl Operation {
+	/**
+	 * Parse an operation from its name and value.
+	 *
+	 * @param  name
+	 * @param  value (Option)
+	 * @return Operation (Result)
+	 */
+	pub fn parse(name: &str, value: Option<&str>) -> Result<Self, String> {
+		fn get_value<T: FromStr>(
+			name: &str,
+			value: Option<&str>,
+		) -> Result<T, String> {
+			value
+				.and_then(|v| v.parse().ok())
+				.ok_or_else(|| format!("Invalid value for {name:?}: {value:?}"))
+		}
+		Ok(match name {
+			"trim" => match value {
+				Some(_) => match get_value::<f32>(name, value)? {
+					fuzz if (0. ..=100.).contains(&fuzz) => Self::Trim(fuzz),
+					_ => {
+						return Err(format!("Invalid value for {name:?}: {value:?}"))
+					}
+				},
+				None => Self::Trim(0.),
+			},
+			"crop" => Self::Crop(Padding::parse(
+				get_value::<String>(name, value)?.as_str(),
+			)),
+			"extend" => Self::Extend(Extend::parse(value.unwrap_or_default())?),
+			"resize" => Self::Resize(Geometry::parse(
+				get_value::<String>(name, value)?.as_str(),
+			)),
+			"ratio" => Self::Ratio(get_value(name, value)?),
+			"max-dimensions" => Self::MaxDimensions(Geometry::parse(
+				get_value::<String>(name, value)?.as_str(),
+			)),
+			"rotate" => match get_value::<f32>(name, value)? {
+				rotate if rotate.is_finite() => Self::Rotate(rotate),
+				_ => return Err(format!("Invalid value for {name:?}: {value:?}")),
+			},
+			"flip" => match value {
+				Some("horizontal") => Self::Flip(Flip::Horizontal),
+				Some("vertical") => Self::Flip(Flip::Vertical),
+				_ => return Err(format!("Invalid value for {name:?}: {value:?}")),
+			},
+			"blur" => Self::Blur(get_value(name, value)?),
+			"sharpen" => Self::Sharpen,
+			"unsharpen" => {
+				Self::Unsharpen(Unsharpen::parse(value.unwrap_or_default())?)
+			}
+			"kernel" => Self::Kernel(Kernel::parse(value.unwrap_or_default())?),
+			"edges" => Self::Edges,
+			"emboss" => Self::Emboss,
+			"grayscale" => Self::Grayscale,
+			"invert" => Self::Invert,
+			"hue" => Self::Hue(get_value(name, value)?),
+			"contrast" => Self::Contrast(get_value(name, value)?),
+			"brightness" => Self::Brightness(get_value(name, value)?),
+			"normalize" => Self::Normalize,
+			"levels" => Self::Levels(Levels::parse(value.unwrap_or_default())?),
+			"gamma" => match get_value::<f32>(name, value)? {
+				gamma if gamma > 0. => Self::Gamma(gamma),
+				_ => return Err(format!("Invalid value for {name:?}: {value:?}")),
+			},
+			"curve" => Self::Curve(Curve::parse(value.unwrap_or_default())?),
+			"temperature" => Self::Temperature(get_value(name, value)?),
+			"tint" => Self::Tint(get_value(name, value)?),
+			"saturation" => Self::Saturation(get_value(name, value)?),
+			"vibrance" => Self::Vibrance(get_value(name, value)?),
+			"sepia" => Self::Sepia,
+			"redact" => Self::Redact(Redaction::parse(value.unwrap_or_default())?),
+			"round" => Self::Round(get_value(name, value)?),
+			"shadow" => Self::Shadow(Shadow::parse(value.unwrap_or_default())?),
+			"canvas" => Self::Canvas(Background::parse(value.unwrap_or_default())?),
+			"overlay" => Self::Overlay(Overlay::parse(value.unwrap_or_default())?),
+			_ => match Shape::from_name(name) {
+				Some(shape) => Self::Annotate(
+					Annotation::parse(shape, value.unwrap_or_default())
+						.map_err(|e| format!("Invalid value for {name:?}: {e}"))?,
+				),
+				None => return Err(format!("Unknown operation: {name:?}")),
+			},
+		})
+	}
+}
+
+/* Ordered list of image operations */
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Pipeline {
+	pub operations: Vec<Operation>,
+}
+
+/* Display implementation for user-facing output */
+impl fmt::Display for Pipeline {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for operation in &self.operations {
+			writeln!(f, "{operation}")?;
+		}
+		Ok(())
+	}
+}
+
+impl Pipeline {
+	/**
+	 * Create a new Pipeline object.
+	 *
+	 * @param  operations
+	 * @return Pipeline
+	 */
+	pub fn new(operations: Vec<Operation>) -> Self {
+		Self { operations }
+	}
+
+	/**
+	 * Get the indices of the occurrences that are given without a value.
+	 *
+	 * Such occurrences are not indexed by the parser, so they take
+	 * the positions that are not used by the other arguments or by
+	 * the names of the options.
+	 *
+	 * @param  args
+	 * @param  name
+	 * @return Vector of usize
+	 */
+	fn get_bare_indices(args: &ArgMatches<'_>, name: &str) -> Vec<usize> {
+		let count = (args.occurrences_of(name) as usize).saturating_sub(
+			args.indices_of(name).map_or(0, |indices| indices.count()),
+		);
+		if count == 0 {
+			return Vec::new();
+		}
+		let mut used = HashSet::new();
+		for (arg, matched) in args.args.iter().filter(|(_, v)| v.occurs != 0) {
+			for index in &matched.indices {
+				used.insert(*index);
+				if !matched.vals.is_empty()
+					&& *arg != "file"
+					&& !matched.indices.contains(&index.saturating_sub(1))
+				{
+					used.insert(index.saturating_sub(1));
+				}
+			}
+		}
+		(1..)
+			.filter(|index| !used.contains(index))
+			.take(count)
+			.collect()
+	}
+
+	/**
+	 * Create a Pipeline from the operations in the order given on the command line.
+	 *
+	 * Options of the overlay are taken from their own arguments.
+	 *
+	 * @param  args
+	 * @return Pipeline (Result)
+	 */
+	pub fn from_args(args: &ArgMatches<'_>) -> Result<Self, String> {
+		let mut operations = Vec::new();
+		for name in OPERATIONS {
+			if args.occurrences_of(name) == 0 {
+				continue;
+			}
+			let values = args
+				.values_of(name)
+				.map(|values| values.collect::<Vec<&str>>())
+				.unwrap_or_default();
+			for (i, index) in args.indices_of(name).into_iter().flatten().enumerate()
+			{
+				let value = values.get(i).copied();
+				operations.push((
+					index,
+					match *name {
+						"overlay" => Operation::Overlay(Overlay::from_values(
+							value.unwrap_or_default(),
+							args.value_of("gravity"),
+							args.value_of("offset"),
+							args.value_of("opacity"),
+							args.value_of("scale"),
+						)?),
+						_ => Operation::parse(name, value)?,
+					},
+				));
+			}
+			for index in Self::get_bare_indices(args, name) {
+				operations.push((index, Operation::parse(name, None)?));
+			}
+		}
+		operations.sort_by_key(|(index, _)| *index);
+		Ok(Self::new(operations.into_iter().map(|(_, v)| v).collect()))
+	}
+
+	/**
+	 * Parse a Pipeline from the lines of a recipe.
+	 *
+	 * @param  recipe
+	 * @return Pipeline (Result)
+	 */
+	pub fn parse(recipe: &str) -> Result<Self, String> {
+		let mut operations = Vec::new();
+		for (i, line) in recipe.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+			let mut values = line.splitn(2, char::is_whitespace);
+			operations.push(
+				Operation::parse(
+					values.next().unwrap_or_default(),
+					values.next().map(str::trim),
+				)
+				.map_err(|e| format!("{e} (line {})", i + 1))?,
+			);
+		}
+		Ok(Self::new(operations))
+	}
+
+	/**
+	 * Load a Pipeline from the recipe file.
+	 *
+	 * @param  path
+	 * @return Pipeline (Result)
+	 */
+	pub fn load(path: &Path) -> Result<Self, String> {
+		Self::parse(&fs::read_to_string(path).map_err(|e| format!("{path:?}: {e}"))?)
+	}
+
+	/**
+	 * Save the Pipeline as a recipe file.
+	 *
+	 * @param  path
+	 * @return Result
+	 */
+	pub fn save(&self, path: &Path) -> std::io::Result<()> {
+		fs::write(path, self.to_string())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::{App, Arg};
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_pipeline() {
+		let app = App::new("test")
+			.arg(Arg::with_name("grayscale").long("grayscale").multiple(true))
+			.args(
+				&["crop", "rotate", "blur", "ratio"]
+					.iter()
+					.map(|name| {
+						Arg::with_name(name)
+							.long(name)
+							.multiple(true)
+							.number_of_values(1)
+							.takes_value(true)
+					})
+					.collect::<Vec<Arg<'_, '_>>>(),
+			);
+		let args = app.clone().get_matches_from(vec![
+			"test",
+			"--rotate",
+			"90",
+			"--crop",
+			"1:2:3:4",
+			"--grayscale",
+			"--rotate",
+			"180",
+		]);
+		let pipeline = Pipeline::from_args(&args).unwrap();
+		assert_eq!(
+			vec![
+				Operation::Rotate(90.),
+				Operation::Crop(Padding::new(1, 2, 3, 4)),
+				Operation::Grayscale,
+				Operation::Rotate(180.),
+			],
+			pipeline.operations
+		);
+		assert_eq!(
+			"rotate 90\ncrop 1:2:3:4\ngrayscale\nrotate 180\n",
+			pipeline.to_string()
+		);
+		let args = app.get_matches_from(vec!["test", "--grayscale", "--blur", "x"]);
+		assert!(Pipeline::from_args(&args).is_err());
+		assert_eq!(Ok(pipeline.clone()), Pipeline::parse(&pipeline.to_string()));
+		assert_eq!(
+			Ok(Pipeline::new(vec![
+				Operation::Flip(Flip::Vertical),
+				Operation::Resize(Geometry::new(0, 0, 20, 10)),
+				Operation::Contrast(-5.5),
+			])),
+			Pipeline::parse(
+				"# recipe\n\n  flip   vertical\nresize 20x10\ncontrast -5.5\n"
+			)
+		);
+		let recipe =
+			"arrow -10x5+20+0 color=00ff00\nstep +5+5\ntext +1+1 size=30 a  b\n";
+		assert_eq!(recipe, Pipeline::parse(recipe).unwrap().to_string());
+		assert!(Pipeline::parse("text +1+1").is_err());
+		assert!(Pipeline::parse("rotate x").is_err());
+		assert!(Pipeline::parse("unsharpen 0").is_err());
+		assert!(Pipeline::parse("kernel 2x2:1,1,1,1").is_err());
+		assert!(Pipeline::parse("gamma 0").is_err());
+		assert!(Pipeline::parse("trim 101").is_err());
+		let recipe = "resize 640x0\nratio 0.5\nmax-dimensions 0x480\n";
+		assert_eq!(recipe, Pipeline::parse(recipe).unwrap().to_string());
+		assert_eq!(
+			Ok(Pipeline::new(vec![
+				Operation::Trim(0.),
+				Operation::Extend(Extend::parse("1:2:3:4:ffffff").unwrap()),
+			])),
+			Pipeline::parse("trim\nextend 1:2:3:4:ffffff")
+		);
+		assert!(Pipeline::parse("levels 10").is_err());
+		let recipe = "normalize\nlevels 10:240\ngamma 2.2\ncurve 64:48,192:208\n\
+			temperature -20\ntint 5\nsaturation 30\nvibrance -10\nsepia\n";
+		assert_eq!(recipe, Pipeline::parse(recipe).unwrap().to_string());
+		let recipe = "sharpen\nunsharpen 2:5\nkernel 1x3:1,-2,1\nedges\nemboss\n";
+		assert_eq!(recipe, Pipeline::parse(recipe).unwrap().to_string());
+		let path = Path::new("test.ops");
+		pipeline.save(path).unwrap();
+		assert_eq!(Ok(pipeline), Pipeline::load(path));
+		fs::remove_file(path).unwrap();
+		assert!(Pipeline::load(path).is_err());
+	}
+}
@@ -0,0 +1,217 @@
+use crate::edit::annotate::{format_color, parse_color};
+use crate::image::geometry::Geometry;
+use image::imageops;
+use image::{GenericImage, GenericImageView, Rgba, RgbaImage};
+use std::fmt;
+
+/* Redaction mode */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RedactMode {
+	Pixelate(u32),
+	Blur(f32),
+	Fill(Rgba<u8>),
+}
+
+/* Default initialization values for RedactMode */
+impl Default for RedactMode {
+	fn default() -> Self {
+		Self::Pixelate(10)
+	}
+}
+
+/* Display implementation for user-facing output */
+impl fmt::Display for RedactMode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Pixelate(size) => write!(f, "pixelate={size}"),
+			Self::Blur(sigma) => write!(f, "blur={sigma}"),
+			Self::Fill(color) => write!(f, "fill={}", format_color(*color)),
+		}
+	}
+}
+
+impl RedactMode {
+	/**
+	 * Parse a RedactMode from `MODE[=VALUE]`.
+	 *
+	 * @param  mode
+	 * @return RedactMode (Option)
+	 */
+	pub fn parse(mode: &str) -> Option<Self> {
+		let (name, value) = match mode.split_once('=') {
+			Some((name, value)) => (name, Some(value)),
+			None => (mode, None),
+		};
+		match (name, value) {
+			("pixelate", None) => Some(Self::default()),
+			("pixelate", Some(size)) => size
+				.parse()
+				.ok()
+				.filter(|size| *size > 0)
+				.map(Self::Pixelate),
+			("blur", None) => Some(Self::Blur(8.)),
+			("blur", Some(sigma)) => sigma
+				.parse()
+				.ok()
+				.filter(|sigma| *sigma > 0.)
+				.map(Self::Blur),
+			("fill", None) => Some(Self::Fill(Rgba([0, 0, 0, 255]))),
+			("fill", Some(color)) => parse_color(color).map(Self::Fill),
+			_ => None,
+		}
+	}
+}
+
+/* Area of the image to hide */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Redaction {
+	pub geometry: Geometry,
+	pub mode: RedactMode,
+}
+
+/* Display implementation for user-facing output */
+impl fmt::Display for Redaction {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{}+{}+{}:{}",
+			self.geometry, self.geometry.x, self.geometry.y, self.mode
+		)
+	}
+}
+
+impl Redaction {
+	/**
+	 * Create a new Redaction object.
+	 *
+	 * @param  geometry
+	 * @param  mode
+	 * @return Redaction
+	 */
+	pub fn new(geometry: Geometry, mode: RedactMode) -> Self {
+		Self { geometry, mode }
+	}
+
+	/**
+	 * Parse a Redaction from `WxH+X+Y[:MODE[=VALUE]]`.
+	 *
+	 * @param  redaction
+	 * @return Redaction (Result)
+	 */
+	pub fn parse(redaction: &str) -> Result<Self, String> {
+		let invalid = || format!("Invalid redaction: {redaction:?}");
+		let (area, mode) = match redaction.split_once(':') {
+			Some((area, mode)) => {
+				(area, RedactMode::parse(mode).ok_or_else(invalid)?)
+			}
+			None => (redaction, RedactMode::default()),
+		};
+		let values = area.split('+').collect::<Vec<&str>>();
+		let mut geometry = Geometry::parse(values[0]);
+		if geometry.width == 0 || geometry.height == 0 {
+			return Err(invalid());
+		}
+		match values[1..] {
+			[] => {}
+			[x, y] => {
+				geometry.x = x.parse::<u32>().map_err(|_| invalid())? as i32;
+				geometry.y = y.parse::<u32>().map_err(|_| invalid())? as i32;
+			}
+			_ => return Err(invalid()),
+		}
+		Ok(Self::new(geometry, mode))
+	}
+
+	/**
+	 * Hide the area of the image.
+	 *
+	 * @param image
+	 */
+	pub fn apply(&self, image: &mut RgbaImage) {
+		let (x, y) = (self.geometry.x as u32, self.geometry.y as u32);
+		if x >= image.width() || y >= image.height() {
+			return;
+		}
+		let width = self.geometry.width.min(image.width() - x);
+		let height = self.geometry.height.min(image.height() - y);
+		let mut area = imageops::crop_imm(image, x, y, width, height).to_image();
+		match self.mode {
+			RedactMode::Pixelate(size) => {
+				for block_y in (0..height).step_by(size as usize) {
+					for block_x in (0..width).step_by(size as usize) {
+						let mut block =
+							imageops::crop(&mut area, block_x, block_y, size, size);
+						let (w, h) = block.dimensions();
+						let mut sum = [0_u64; 4];
+						for (_, _, pixel) in block.pixels() {
+							for (i, value) in sum.iter_mut().enumerate() {
+								*value += u64::from(pixel[i]);
+							}
+						}
+						let count = u64::from(w * h);
+						let average = Rgba(sum.map(|value| (value / count) as u8));
+						for by in 0..h {
+							for bx in 0..w {
+								block.put_pixel(bx, by, average);
+							}
+						}
+					}
+				}
+			}
+			RedactMode::Blur(sigma) => area = imageops::blur(&area, sigma),
+			RedactMode::Fill(color) => {
+				area.pixels_mut().for_each(|pixel| *pixel = color)
+			}
+		}
+		imageops::replace(image, &area, i64::from(x), i64::from(y));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_redact() {
+		let redaction = Redaction::parse("4x2+1+1:fill=ff0000").unwrap();
+		assert_eq!(Geometry::new(1, 1, 4, 2), redaction.geometry);
+		assert_eq!(RedactMode::Fill(Rgba([255, 0, 0, 255])), redaction.mode);
+		assert_eq!("4x2+1+1:fill=ff0000", redaction.to_string());
+		assert_eq!(
+			Ok(Redaction::new(
+				Geometry::new(0, 0, 3, 3),
+				RedactMode::Blur(8.)
+			)),
+			Redaction::parse("3x3:blur")
+		);
+		assert_eq!(
+			Ok(RedactMode::Pixelate(10)),
+			Redaction::parse("3x3+0+0").map(|v| v.mode)
+		);
+		for invalid in ["", "0x3", "3x3+1", "3x3+-1+0", "3x3:blur=0", "3x3:x"] {
+			assert!(Redaction::parse(invalid).is_err(), "{invalid}");
+		}
+		let mut image =
+			RgbaImage::from_fn(6, 4, |x, y| Rgba([(x * 10) as u8, y as u8, 0, 255]));
+		let original = image.clone();
+		redaction.apply(&mut image);
+		assert_eq!(Rgba([255, 0, 0, 255]), *image.get_pixel(1, 1));
+		assert_eq!(Rgba([255, 0, 0, 255]), *image.get_pixel(4, 2));
+		assert_eq!(original.get_pixel(0, 0), image.get_pixel(0, 0));
+		assert_eq!(original.get_pixel(5, 3), image.get_pixel(5, 3));
+		let mut image = original.clone();
+		Redaction::parse("4x4+4+0:pixelate=2")
+			.unwrap()
+			.apply(&mut image);
+		assert_eq!(Rgba([45, 0, 0, 255]), *image.get_pixel(4, 0));
+		assert_eq!(Rgba([45, 0, 0, 255]), *image.get_pixel(5, 1));
+		assert_eq!(Rgba([45, 2, 0, 255]), *image.get_pixel(5, 3));
+		assert_eq!(original.get_pixel(3, 0), image.get_pixel(3, 0));
+		let mut image = original.clone();
+		Redaction::parse("2x4+0+0:blur=2")
+			.unwrap()
+			.apply(&mut image);
+		assert_ne!(original.get_pixel(0, 0), image.get_pixel(0, 0));
+		assert_eq!(original.get_pixel(2, 0), image.get_pixel(2, 0));
+	}
+}
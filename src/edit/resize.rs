@@ -0,0 +1,165 @@
+use image::{imageops, Rgba, RgbaImage};
+
+/* Resize mode */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResizeMode {
+	#[default]
+	Exact,
+	Fit,
+	Fill,
+	Cover,
+}
+
+impl ResizeMode {
+	/**
+	 * Create a ResizeMode from the given name.
+	 *
+	 * @param  name
+	 * @return ResizeMode (Option)
+	 */
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"exact" => Some(Self::Exact),
+			"fit" => Some(Self::Fit),
+			"fill" => Some(Self::Fill),
+			"cover" => Some(Self::Cover),
+			_ => None,
+		}
+	}
+}
+
+/**
+ * Scale the size by the given factor.
+ *
+ * @param  size
+ * @param  factor
+ * @return size
+ */
+pub fn scale(size: (u32, u32), factor: f32) -> (u32, u32) {
+	(
+		(size.0 as f32 * factor).round().max(1.) as u32,
+		(size.1 as f32 * factor).round().max(1.) as u32,
+	)
+}
+
+/**
+ * Get the size to scale the image to and the size of its frame.
+ *
+ * Missing side of the target (zero) is calculated from the aspect ratio.
+ * Frame is the target size if the scaled image is going to be
+ * cropped (fill) or letterboxed (fit), otherwise it is the scaled size.
+ *
+ * @param  size
+ * @param  target
+ * @param  mode
+ * @param  letterbox
+ * @return (size, frame)
+ */
+pub fn get_size(
+	size: (u32, u32),
+	target: (u32, u32),
+	mode: ResizeMode,
+	letterbox: bool,
+) -> ((u32, u32), (u32, u32)) {
+	if size.0 == 0 || size.1 == 0 {
+		return (target, target);
+	}
+	let factor_x = target.0 as f32 / size.0 as f32;
+	let factor_y = target.1 as f32 / size.1 as f32;
+	let scaled = if target.0 == 0 {
+		scale(size, factor_y)
+	} else if target.1 == 0 {
+		scale(size, factor_x)
+	} else {
+		match mode {
+			ResizeMode::Exact => target,
+			ResizeMode::Fit => scale(size, factor_x.min(factor_y)),
+			ResizeMode::Fill | ResizeMode::Cover => {
+				scale(size, factor_x.max(factor_y))
+			}
+		}
+	};
+	let frame = if target.0 == 0 || target.1 == 0 {
+		scaled
+	} else {
+		match mode {
+			ResizeMode::Fill => target,
+			ResizeMode::Fit if letterbox => target,
+			_ => scaled,
+		}
+	};
+	(scaled, frame)
+}
+
+/**
+ * Get the factor for shrinking the size to the maximum size.
+ *
+ * Sides of the maximum size that are zero are not limited.
+ *
+ * @param  size
+ * @param  max_size
+ * @return f32
+ */
+pub fn get_max_factor(size: (u32, u32), max_size: (u32, u32)) -> f32 {
+	[(size.0, max_size.0), (size.1, max_size.1)]
+		.iter()
+		.filter(|(value, max)| *max != 0 && *value > *max)
+		.map(|(value, max)| *max as f32 / *value as f32)
+		.fold(1., f32::min)
+}
+
+/**
+ * Center the image in a frame of the given size.
+ *
+ * Image is cropped where it is larger than the frame
+ * and filled with the given color where it is smaller.
+ *
+ * @param  image
+ * @param  size
+ * @param  color
+ * @return RgbaImage
+ */
+pub fn frame(image: &RgbaImage, size: (u32, u32), color: Rgba<u8>) -> RgbaImage {
+	let (width, height) = image.dimensions();
+	let mut framed = RgbaImage::from_pixel(size.0, size.1, color);
+	imageops::replace(
+		&mut framed,
+		image,
+		(i64::from(size.0) - i64::from(width)) / 2,
+		(i64::from(size.1) - i64::from(height)) / 2,
+	);
+	framed
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_resize() {
+		assert_eq!(Some(ResizeMode::Fill), ResizeMode::from_name("fill"));
+		assert_eq!(None, ResizeMode::from_name("x"));
+		let size = (200, 100);
+		for (target, mode, letterbox, expected) in [
+			((50, 50), ResizeMode::Exact, false, ((50, 50), (50, 50))),
+			((50, 50), ResizeMode::Fit, false, ((50, 25), (50, 25))),
+			((50, 50), ResizeMode::Fit, true, ((50, 25), (50, 50))),
+			((50, 50), ResizeMode::Fill, true, ((100, 50), (50, 50))),
+			((50, 50), ResizeMode::Cover, false, ((100, 50), (100, 50))),
+			((50, 0), ResizeMode::Exact, false, ((50, 25), (50, 25))),
+			((0, 300), ResizeMode::Fill, false, ((600, 300), (600, 300))),
+		] {
+			assert_eq!(expected, get_size(size, target, mode, letterbox));
+		}
+		assert_eq!(1., get_max_factor(size, (0, 0)));
+		assert_eq!(1., get_max_factor(size, (400, 400)));
+		assert_eq!(0.5, get_max_factor(size, (100, 0)));
+		assert_eq!(0.25, get_max_factor(size, (100, 25)));
+		assert_eq!((50, 25), scale(size, 0.25));
+		let image = RgbaImage::from_fn(4, 2, |x, _| Rgba([x as u8, 0, 0, 255]));
+		let framed = frame(&image, (2, 4), Rgba([0, 0, 255, 255]));
+		assert_eq!(Rgba([0, 0, 255, 255]), *framed.get_pixel(0, 0));
+		assert_eq!(Rgba([1, 0, 0, 255]), *framed.get_pixel(0, 1));
+		assert_eq!(Rgba([2, 0, 0, 255]), *framed.get_pixel(1, 2));
+	}
+}
@@ -0,0 +1,257 @@
+use image::imageops::{self, FilterType};
+use image::{Rgba, RgbaImage};
+
+/**
+ * Normalize the angle to the range of [0, 360).
+ *
+ * @param  angle
+ * @return f32
+ */
+fn normalize(angle: f32) -> f32 {
+	let angle = angle.rem_euclid(360.);
+	if (360. - angle) < 1e-4 {
+		0.
+	} else {
+		angle
+	}
+}
+
+/**
+ * Check if the angle is a multiple of 90 degrees.
+ *
+ * @param  angle
+ * @return bool
+ */
+pub fn is_right_angle(angle: f32) -> bool {
+	normalize(angle) % 90. == 0.
+}
+
+/**
+ * Get the size of the image after rotating it.
+ *
+ * If `crop` is set, size of the largest rectangle that fits
+ * inside the rotated image is returned.
+ *
+ * @param  size
+ * @param  angle
+ * @param  crop
+ * @return size
+ */
+pub fn get_size(size: (u32, u32), angle: f32, crop: bool) -> (u32, u32) {
+	let angle = normalize(angle);
+	if angle == 0. || angle == 180. {
+		return size;
+	} else if angle == 90. || angle == 270. {
+		return (size.1, size.0);
+	}
+	let (width, height) = (size.0 as f32, size.1 as f32);
+	let (sin, cos) = angle.to_radians().sin_cos();
+	let (sin, cos) = (sin.abs(), cos.abs());
+	let (width, height) = if crop {
+		let (long, short) = if width >= height {
+			(width, height)
+		} else {
+			(height, width)
+		};
+		if short <= 2. * sin * cos * long || (sin - cos).abs() < 1e-6 {
+			let half = short / 2.;
+			if width >= height {
+				(half / sin, half / cos)
+			} else {
+				(half / cos, half / sin)
+			}
+		} else {
+			let cos_2a = cos * cos - sin * sin;
+			(
+				(width * cos - height * sin) / cos_2a,
+				(height * cos - width * sin) / cos_2a,
+			)
+		}
+	} else {
+		(width * cos + height * sin, width * sin + height * cos)
+	};
+	let round = |value: f32| {
+		if crop {
+			(value + 1e-3).floor()
+		} else {
+			(value - 1e-3).ceil()
+		}
+		.max(1.) as u32
+	};
+	(round(width), round(height))
+}
+
+/**
+ * Get the weights of the cubic (Catmull-Rom) kernel.
+ *
+ * @param  t
+ * @return weights
+ */
+fn cubic_weights(t: f32) -> [f32; 4] {
+	let (t2, t3) = (t * t, t * t * t);
+	[
+		(-t3 + 2. * t2 - t) / 2.,
+		(3. * t3 - 5. * t2 + 2.) / 2.,
+		(-3. * t3 + 4. * t2 + t) / 2.,
+		(t3 - t2) / 2.,
+	]
+}
+
+/**
+ * Sample the image at the given position.
+ *
+ * Pixels outside of the image are taken as the fill color
+ * and the colors are weighted by their alpha values.
+ *
+ * @param  image
+ * @param  x
+ * @param  y
+ * @param  filter
+ * @param  fill
+ * @return Rgba
+ */
+fn sample(
+	image: &RgbaImage,
+	x: f32,
+	y: f32,
+	filter: FilterType,
+	fill: Rgba<u8>,
+) -> Rgba<u8> {
+	let get_pixel = |x: i64, y: i64| {
+		if x < 0
+			|| y < 0 || x >= i64::from(image.width())
+			|| y >= i64::from(image.height())
+		{
+			fill
+		} else {
+			*image.get_pixel(x as u32, y as u32)
+		}
+	};
+	if filter == FilterType::Nearest {
+		return get_pixel(x.round() as i64, y.round() as i64);
+	}
+	let (x0, y0) = (x.floor(), y.floor());
+	let (tx, ty) = (x - x0, y - y0);
+	let (x0, y0) = (x0 as i64, y0 as i64);
+	let (weights_x, weights_y, start) = if filter == FilterType::Triangle {
+		([1. - tx, tx, 0., 0.], [1. - ty, ty, 0., 0.], 0)
+	} else {
+		(cubic_weights(tx), cubic_weights(ty), -1)
+	};
+	let mut sum = [0_f32; 4];
+	for (j, weight_y) in weights_y.iter().enumerate() {
+		for (i, weight_x) in weights_x.iter().enumerate() {
+			let weight = weight_x * weight_y;
+			if weight == 0. {
+				continue;
+			}
+			let pixel = get_pixel(x0 + start + i as i64, y0 + start + j as i64);
+			let alpha = f32::from(pixel[3]) * weight;
+			for (c, value) in sum.iter_mut().take(3).enumerate() {
+				*value += f32::from(pixel[c]) * alpha;
+			}
+			sum[3] += alpha;
+		}
+	}
+	if sum[3] <= 0. {
+		return Rgba([fill[0], fill[1], fill[2], 0]);
+	}
+	Rgba([
+		(sum[0] / sum[3]).round().clamp(0., 255.) as u8,
+		(sum[1] / sum[3]).round().clamp(0., 255.) as u8,
+		(sum[2] / sum[3]).round().clamp(0., 255.) as u8,
+		sum[3].round().clamp(0., 255.) as u8,
+	])
+}
+
+/**
+ * Rotate the image clockwise by the given angle in degrees.
+ *
+ * Nearest and triangle filters are used for nearest-neighbor and
+ * bilinear sampling while the others are used for bicubic sampling.
+ * Canvas is expanded and filled with the given color
+ * unless `crop` is set for cropping to the largest inner rectangle.
+ *
+ * @param  image
+ * @param  angle
+ * @param  filter
+ * @param  fill
+ * @param  crop
+ * @return RgbaImage
+ */
+pub fn rotate(
+	image: &RgbaImage,
+	angle: f32,
+	filter: FilterType,
+	fill: Rgba<u8>,
+	crop: bool,
+) -> RgbaImage {
+	let angle = normalize(angle);
+	if angle == 0. {
+		return image.clone();
+	} else if angle == 90. {
+		return imageops::rotate90(image);
+	} else if angle == 180. {
+		return imageops::rotate180(image);
+	} else if angle == 270. {
+		return imageops::rotate270(image);
+	}
+	let (width, height) = get_size(image.dimensions(), angle, crop);
+	let (sin, cos) = angle.to_radians().sin_cos();
+	let center = (image.width() as f32 / 2., image.height() as f32 / 2.);
+	RgbaImage::from_fn(width, height, |x, y| {
+		let dx = x as f32 + 0.5 - width as f32 / 2.;
+		let dy = y as f32 + 0.5 - height as f32 / 2.;
+		sample(
+			image,
+			dx * cos + dy * sin + center.0 - 0.5,
+			dy * cos - dx * sin + center.1 - 0.5,
+			filter,
+			fill,
+		)
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_rotate() {
+		assert!(is_right_angle(-270.));
+		assert!(!is_right_angle(30.));
+		assert_eq!((4, 2), get_size((4, 2), -360., false));
+		assert_eq!((2, 4), get_size((4, 2), -90., true));
+		assert_eq!((3, 3), get_size((2, 2), 45., false));
+		assert_eq!((1, 1), get_size((2, 2), 45., true));
+		assert_eq!((100, 100), get_size((100, 100), 360.00001, false));
+		assert_eq!((70, 70), get_size((100, 100), 45., true));
+		assert_eq!((57, 10), get_size((60, 20), 10., true));
+		let image =
+			RgbaImage::from_fn(4, 2, |x, y| Rgba([x as u8, y as u8, 0, 255]));
+		let rotated = rotate(&image, 90., FilterType::Lanczos3, Rgba([0; 4]), false);
+		assert_eq!(imageops::rotate90(&image), rotated);
+		let image = RgbaImage::from_pixel(20, 10, Rgba([255, 0, 0, 255]));
+		for filter in [
+			FilterType::Nearest,
+			FilterType::Triangle,
+			FilterType::CatmullRom,
+		] {
+			let fill = Rgba([0, 0, 255, 255]);
+			let rotated = rotate(&image, 30., filter, fill, false);
+			assert_eq!((23, 19), rotated.dimensions());
+			assert_eq!(fill, *rotated.get_pixel(0, 0));
+			assert_eq!(Rgba([255, 0, 0, 255]), *rotated.get_pixel(11, 9));
+			let rotated = rotate(&image, -30., filter, Rgba([0; 4]), true);
+			assert_eq!(get_size((20, 10), 30., true), rotated.dimensions());
+			assert!(rotated
+				.pixels()
+				.all(|pixel| pixel[0] > 200 && pixel[3] > 200));
+		}
+		let rotated = rotate(&image, 45., FilterType::Triangle, Rgba([0; 4]), false);
+		assert_eq!(0, rotated.get_pixel(0, 0)[3]);
+		assert!(rotated
+			.pixels()
+			.all(|pixel| pixel[3] == 0 || pixel[0] == 255));
+	}
+}
@@ -1,34 +1,62 @@
 use crate::args::matches::ArgMatches;
 use crate::args::parser::ArgParser;
+use crate::edit::adjust::{Curve, Levels, Lut};
+use crate::edit::annotate::{parse_color, Annotation};
+use crate::edit::border::Extend;
+use crate::edit::canvas::{Background, Shadow};
+use crate::edit::convolve::{Kernel, Unsharpen};
+use crate::edit::overlay::Overlay;
+use crate::edit::pipeline::{Operation, Pipeline};
+use crate::edit::redact::Redaction;
+use crate::edit::resize::ResizeMode;
 use crate::edit::ImageOps;
+use crate::file::metadata::ImageMetadata;
 use crate::image::geometry::Geometry;
 use crate::image::padding::Padding;
+use clap::Error as ClapError;
 use image::imageops::FilterType;
-use std::path::PathBuf;
+use image::Rgba;
+use std::path::{Path, PathBuf};
 
 /* Image settings */
 #[derive(Clone, Copy, Debug)]
 pub struct ImageSettings {
+	pub trim: Option<f32>,
 	pub crop: Padding,
+	pub extend: Option<Extend>,
 	pub resize: Geometry,
+	pub resize_mode: ResizeMode,
+	pub letterbox: Option<Rgba<u8>>,
 	pub ratio: f32,
+	pub max_dimensions: Geometry,
 	pub flip: Option<Flip>,
-	pub rotate: u32,
+	pub rotate: f32,
+	pub rotate_fill: Rgba<u8>,
+	pub rotate_crop: bool,
 	pub blur: f32,
 	pub filter: FilterType,
+	pub auto_orient: bool,
 }
 
 /* Default initialization values for ImageSettings */
 impl Default for ImageSettings {
 	fn default() -> Self {
 		Self {
+			trim: None,
 			crop: Padding::default(),
+			extend: None,
 			resize: Geometry::default(),
+			resize_mode: ResizeMode::default(),
+			letterbox: None,
 			ratio: 1.,
+			max_dimensions: Geometry::default(),
 			flip: None,
-			rotate: 0,
+			rotate: 0.,
+			rotate_fill: Rgba([0, 0, 0, 0]),
+			rotate_crop: false,
 			blur: 0.,
 			filter: FilterType::Lanczos3,
+			auto_orient: true,
 		}
 	}
 }
@@ -37,43 +65,77 @@ impl ImageSettings {
 	/**
 	 * Create a new ImageSettings object.
 	 *
+	 * @param  trim (Option)
 	 * @param  crop
+	 * @param  extend (Option)
 	 * @param  resize
+	 * @param  resize_mode
+	 * @param  letterbox (Option)
 	 * @param  ratio
+	 * @param  max_dimensions
 	 * @param  flip (Option)
 	 * @param  rotate
+	 * @param  rotate_fill
+	 * @param  rotate_crop
 	 * @param  blur
 	 * @param  filter
+	 * @param  auto_orient
 	 */
+	#[allow(clippy::too_many_arguments)]
 	pub fn new(
+		trim: Option<f32>,
 		crop: Padding,
+		extend: Option<Extend>,
 		resize: Geometry,
+		resize_mode: ResizeMode,
+		letterbox: Option<Rgba<u8>>,
 		ratio: f32,
+		max_dimensions: Geometry,
 		flip: Option<Flip>,
-		rotate: u32,
+		rotate: f32,
+		rotate_fill: Rgba<u8>,
+		rotate_crop: bool,
 		blur: f32,
 		filter: FilterType,
+		auto_orient: bool,
 	) -> Self {
 		Self {
+			trim,
 			crop,
+			extend,
 			resize,
+			resize_mode,
+			letterbox,
 			ratio,
+			max_dimensions,
 			flip,
 			rotate,
+			rotate_fill,
+			rotate_crop,
 			blur,
 			filter,
+			auto_orient,
 		}
 	}
 }
 
 /* Image color settings */
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct ColorSettings {
 	pub grayscale: bool,
 	pub invert: bool,
 	pub hue: i32,
 	pub contrast: f32,
 	pub brightness: i32,
+	pub normalize: bool,
+	pub levels: Option<Levels>,
+	pub gamma: f32,
+	pub curve: Option<Curve>,
+	pub temperature: f32,
+	pub tint: f32,
+	pub saturation: f32,
+	pub vibrance: f32,
+	pub sepia: bool,
 }
 
 /* Default initialization values for ColorSettings */
@@ -85,6 +147,15 @@ impl Default for ColorSettings {
 			hue: 0,
 			contrast: 0.,
 			brightness: 0,
+			normalize: false,
+			levels: None,
+			gamma: 1.,
+			curve: None,
+			temperature: 0.,
+			tint: 0.,
+			saturation: 0.,
+			vibrance: 0.,
+			sepia: false,
 		}
 	}
 }
@@ -98,14 +169,33 @@ impl ColorSettings {
 	 * @param  hue
 	 * @param  contrast
 	 * @param  brightness
+	 * @param  normalize
+	 * @param  levels (Option)
+	 * @param  gamma
+	 * @param  curve (Option)
+	 * @param  temperature
+	 * @param  tint
+	 * @param  saturation
+	 * @param  vibrance
+	 * @param  sepia
 	 * @return ColorSettings
 	 */
+	#[allow(clippy::too_many_arguments)]
 	pub fn new(
 		grayscale: bool,
 		invert: bool,
 		hue: i32,
 		contrast: f32,
 		brightness: i32,
+		normalize: bool,
+		levels: Option<Levels>,
+		gamma: f32,
+		curve: Option<Curve>,
+		temperature: f32,
+		tint: f32,
+		saturation: f32,
+		vibrance: f32,
+		sepia: bool,
 	) -> Self {
 		Self {
 			grayscale,
@@ -113,6 +203,146 @@ impl ColorSettings {
 			hue,
 			contrast,
 			brightness,
+			normalize,
+			levels,
+			gamma,
+			curve,
+			temperature,
+			tint,
+			saturation,
+			vibrance,
+			sepia,
+		}
+	}
+
+	/**
+	 * Get the Lut that combines the levels, gamma, curve,
+	 * temperature and tint adjustments.
+	 *
+	 * @return Lut (Option)
+	 */
+	pub fn get_lut(&self) -> Option<Lut> {
+		[
+			self.levels.map(|levels| levels.get_lut()),
+			((self.gamma - 1.).abs() > f32::EPSILON).then(|| Lut::gamma(self.gamma)),
+			self.curve.as_ref().map(Curve::get_lut),
+			(self.temperature != 0.).then(|| Lut::temperature(self.temperature)),
+			(self.tint != 0.).then(|| Lut::tint(self.tint)),
+		]
+		.into_iter()
+		.flatten()
+		.reduce(|lut, next| lut.then(&next))
+	}
+}
+
+/* Settings for convolution filters */
+#[derive(Clone, Debug, Default)]
+pub struct ConvolutionSettings {
+	pub sharpen: bool,
+	pub unsharpen: Option<Unsharpen>,
+	pub kernel: Option<Kernel>,
+	pub edges: bool,
+	pub emboss: bool,
+}
+
+impl ConvolutionSettings {
+	/**
+	 * Create a new ConvolutionSettings object.
+	 *
+	 * @param  sharpen
+	 * @param  unsharpen (Option)
+	 * @param  kernel (Option)
+	 * @param  edges
+	 * @param  emboss
+	 * @return ConvolutionSettings
+	 */
+	pub fn new(
+		sharpen: bool,
+		unsharpen: Option<Unsharpen>,
+		kernel: Option<Kernel>,
+		edges: bool,
+		emboss: bool,
+	) -> Self {
+		Self {
+			sharpen,
+			unsharpen,
+			kernel,
+			edges,
+			emboss,
+		}
+	}
+}
+
+/* Image metadata settings */
+#[derive(Clone, Debug, Default)]
+pub struct MetadataSettings {
+	pub strip: bool,
+	pub set_tags: Vec<(String, String)>,
+	pub remove_tags: Vec<String>,
+}
+
+impl MetadataSettings {
+	/**
+	 * Create a new MetadataSettings object.
+	 *
+	 * @param  strip
+	 * @param  set_tags
+	 * @param  remove_tags
+	 * @return MetadataSettings
+	 */
+	pub fn new(
+		strip: bool,
+		set_tags: Vec<(String, String)>,
+		remove_tags: Vec<String>,
+	) -> Self {
+		Self {
+			strip,
+			set_tags,
+			remove_tags,
+		}
+	}
+
+	/**
+	 * Apply the tag changes to the given metadata.
+	 *
+	 * @param  metadata
+	 */
+	pub fn apply(&self, metadata: &mut ImageMetadata) {
+		for (key, value) in &self.set_tags {
+			metadata.set_field(key, value);
+		}
+		for key in &self.remove_tags {
+			metadata.remove_field(key);
+		}
+	}
+}
+
+/* Settings for placing the image on a canvas */
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CanvasSettings {
+	pub round: u32,
+	pub shadow: Option<Shadow>,
+	pub background: Option<Background>,
+}
+
+impl CanvasSettings {
+	/**
+	 * Create a new CanvasSettings object.
+	 *
+	 * @param  round
+	 * @param  shadow (Option)
+	 * @param  background (Option)
+	 * @return CanvasSettings
+	 */
+	pub fn new(
+		round: u32,
+		shadow: Option<Shadow>,
+		background: Option<Background>,
+	) -> Self {
+		Self {
+			round,
+			shadow,
+			background,
 		}
 	}
 }
@@ -131,6 +361,14 @@ pub struct EditSettings {
 	pub convert: bool,
 	pub image: ImageSettings,
 	pub color: ColorSettings,
+	pub convolution: ConvolutionSettings,
+	pub canvas: CanvasSettings,
+	pub metadata: MetadataSettings,
+	pub redactions: Vec<Redaction>,
+	pub annotations: Vec<Annotation>,
+	pub overlay: Option<Overlay>,
+	pub pipeline: Option<Pipeline>,
+	pub save_recipe: Option<PathBuf>,
 }
 
 /* Default initialization values for EditSettings */
@@ -141,6 +379,14 @@ impl Default for EditSettings {
 			convert: false,
 			image: ImageSettings::default(),
 			color: ColorSettings::default(),
+			convolution: ConvolutionSettings::default(),
+			canvas: CanvasSettings::default(),
+			metadata: MetadataSettings::default(),
+			redactions: Vec::new(),
+			annotations: Vec::new(),
+			overlay: None,
+			pipeline: None,
+			save_recipe: None,
 		}
 	}
 }
@@ -153,30 +399,173 @@ impl EditSettings {
 	 * @param  convert
 	 * @param  image
 	 * @param  color
+	 * @param  convolution
+	 * @param  canvas
+	 * @param  metadata
+	 * @param  redactions
+	 * @param  annotations
+	 * @param  overlay (Option)
+	 * @param  pipeline (Option)
+	 * @param  save_recipe (Option)
 	 * @return EditSettings
 	 */
+	#[allow(clippy::too_many_arguments)]
 	pub fn new(
 		path: PathBuf,
 		convert: bool,
 		image: ImageSettings,
 		color: ColorSettings,
+		convolution: ConvolutionSettings,
+		canvas: CanvasSettings,
+		metadata: MetadataSettings,
+		redactions: Vec<Redaction>,
+		annotations: Vec<Annotation>,
+		overlay: Option<Overlay>,
+		pipeline: Option<Pipeline>,
+		save_recipe: Option<PathBuf>,
 	) -> Self {
 		Self {
 			path,
 			convert,
 			image,
 			color,
+			convolution,
+			canvas,
+			metadata,
+			redactions,
+			annotations,
+			overlay,
+			pipeline,
+			save_recipe,
 		}
 	}
 
 	/**
 	 * Create a new EditSettings object from arguments.
 	 *
+	 * Frames of the `make` subcommand are edited with the given recipe
+	 * and the captured images are only redacted and overlaid.
+	 *
 	 * @param  matches
 	 * @return EditSettings
 	 */
 	pub fn from_args(matches: &ArgMatches<'_>) -> Self {
-		Self::from_parser(ArgParser::from_subcommand(matches, "edit"))
+		if matches.is_present("capture") || matches.is_present("record") {
+			match ArgParser::from_subcommand(
+				matches,
+				if matches.is_present("capture") {
+					"capture"
+				} else {
+					"record"
+				},
+			)
+			.args
+			{
+				Some(matches) => Self {
+					redactions: Self::get_redactions(&matches),
+					overlay: Self::get_overlay(&matches),
+					..Self::default()
+				},
+				None => Self::default(),
+			}
+		} else if matches.is_present("make") {
+			Self {
+				pipeline: ArgParser::from_subcommand(matches, "make")
+					.args
+					.and_then(|matches| Self::get_pipeline(&matches)),
+				..Self::default()
+			}
+		} else {
+			Self::from_parser(ArgParser::from_subcommand(matches, "edit"))
+		}
+	}
+
+	/**
+	 * Get the areas to redact from parsed arguments.
+	 *
+	 * @param  matches
+	 * @return Vector of Redaction
+	 */
+	fn get_redactions(matches: &ArgMatches<'_>) -> Vec<Redaction> {
+		matches
+			.values_of("redact")
+			.map(|values| {
+				values
+					.filter_map(|value| Redaction::parse(value).ok())
+					.collect()
+			})
+			.unwrap_or_default()
+	}
+
+	/**
+	 * Get the overlay settings from parsed arguments.
+	 *
+	 * The image is loaded here so that the errors are reported
+	 * in the same way as the argument errors.
+	 *
+	 * @param  matches
+	 * @return Overlay (Option)
+	 */
+	fn get_overlay(matches: &ArgMatches<'_>) -> Option<Overlay> {
+		matches.value_of("overlay").map(|path| {
+			Overlay::from_values(
+				path,
+				matches.value_of("gravity"),
+				matches.value_of("offset"),
+				matches.value_of("opacity"),
+				matches.value_of("scale"),
+			)
+			.unwrap_or_else(|e| ClapError::value_validation_auto(e).exit())
+		})
+	}
+
+	/**
+	 * Check if the captured images need to be edited.
+	 *
+	 * @return bool
+	 */
+	pub fn is_capture_edited(&self) -> bool {
+		!self.redactions.is_empty() || self.overlay.is_some()
+	}
+
+	/**
+	 * Get the pipeline of operations from parsed arguments.
+	 *
+	 * Operations are loaded from the recipe file (if given) and
+	 * followed by the operations in the order given on the command line.
+	 *
+	 * @param  matches
+	 * @return Pipeline (Option)
+	 */
+	fn get_pipeline(matches: &ArgMatches<'_>) -> Option<Pipeline> {
+		let recipe = matches.value_of("recipe").map(|path| {
+			Pipeline::load(Path::new(path))
+				.unwrap_or_else(|e| ClapError::value_validation_auto(e).exit())
+		});
+		if recipe.is_some()
+			|| matches.is_present("pipeline")
+			|| matches.is_present("save-recipe")
+		{
+			let mut pipeline = recipe.unwrap_or_default();
+			pipeline.operations.extend(Self::get_operations(matches));
+			Some(pipeline)
+		} else {
+			None
+		}
+	}
+
+	/**
+	 * Get the operations in the order given on the command line.
+	 *
+	 * Invalid values are reported in the same way as the argument errors.
+	 *
+	 * @param  matches
+	 * @return Vector of Operation
+	 */
+	fn get_operations(matches: &ArgMatches<'_>) -> Vec<Operation> {
+		Pipeline::from_args(matches.args)
+			.unwrap_or_else(|e| ClapError::value_validation_auto(e).exit())
+			.operations
 	}
 
 	/**
@@ -196,17 +585,38 @@ impl EditSettings {
 					PathBuf::from(file),
 					matches.is_present("convert"),
 					ImageSettings::new(
+						if matches.occurrences_of("trim") != 0 {
+							Some(parser.parse("trim", 0.))
+						} else {
+							None
+						},
 						Padding::parse(matches.value_of("crop").unwrap_or_default()),
+						matches
+							.value_of("extend")
+							.and_then(|value| Extend::parse(value).ok()),
 						Geometry::parse(
 							matches.value_of("resize").unwrap_or_default(),
 						),
+						matches
+							.value_of("resize-mode")
+							.and_then(ResizeMode::from_name)
+							.unwrap_or_default(),
+						matches.value_of("letterbox").and_then(parse_color),
 						parser.parse("ratio", ImageSettings::default().ratio),
+						Geometry::parse(
+							matches.value_of("max-dimensions").unwrap_or_default(),
+						),
 						match matches.value_of("flip") {
 							Some("horizontal") => Some(Flip::Horizontal),
 							Some("vertical") => Some(Flip::Vertical),
 							_ => None,
 						},
 						parser.parse("rotate", ImageSettings::default().rotate),
+						matches
+							.value_of("rotate-fill")
+							.and_then(parse_color)
+							.unwrap_or(ImageSettings::default().rotate_fill),
+						matches.is_present("rotate-crop"),
 						parser.parse("blur", ImageSettings::default().blur),
 						match matches.value_of("filter") {
 							Some("nearest") => FilterType::Nearest,
@@ -215,6 +625,7 @@ impl EditSettings {
 							Some("gaussian") => FilterType::Gaussian,
 							_ => FilterType::Lanczos3,
 						},
+						!matches.is_present("no-auto-orient"),
 					),
 					ColorSettings::new(
 						matches.is_present("grayscale"),
@@ -225,7 +636,76 @@ impl EditSettings {
 							"brightness",
 							ColorSettings::default().brightness,
 						),
+						matches.is_present("normalize"),
+						matches
+							.value_of("levels")
+							.and_then(|value| Levels::parse(value).ok()),
+						parser.parse("gamma", ColorSettings::default().gamma),
+						matches
+							.value_of("curve")
+							.and_then(|value| Curve::parse(value).ok()),
+						parser.parse(
+							"temperature",
+							ColorSettings::default().temperature,
+						),
+						parser.parse("tint", ColorSettings::default().tint),
+						parser.parse(
+							"saturation",
+							ColorSettings::default().saturation,
+						),
+						parser.parse("vibrance", ColorSettings::default().vibrance),
+						matches.is_present("sepia"),
+					),
+					ConvolutionSettings::new(
+						matches.is_present("sharpen"),
+						matches
+							.value_of("unsharpen")
+							.and_then(|value| Unsharpen::parse(value).ok()),
+						matches
+							.value_of("kernel")
+							.and_then(|value| Kernel::parse(value).ok()),
+						matches.is_present("edges"),
+						matches.is_present("emboss"),
+					),
+					CanvasSettings::new(
+						parser.parse("round", CanvasSettings::default().round),
+						matches
+							.value_of("shadow")
+							.and_then(|value| Shadow::parse(value).ok()),
+						matches
+							.value_of("canvas")
+							.and_then(|value| Background::parse(value).ok()),
+					),
+					MetadataSettings::new(
+						matches.is_present("strip-metadata"),
+						matches
+							.values_of("set-tag")
+							.map(|values| {
+								values
+									.filter_map(|value| value.split_once('='))
+									.filter(|(key, _)| !key.is_empty())
+									.map(|(key, value)| {
+										(key.to_string(), value.to_string())
+									})
+									.collect()
+							})
+							.unwrap_or_default(),
+						matches
+							.values_of("remove-tag")
+							.map(|values| values.map(String::from).collect())
+							.unwrap_or_default(),
 					),
+					Self::get_redactions(matches),
+					Self::get_operations(matches)
+						.into_iter()
+						.filter_map(|operation| match operation {
+							Operation::Annotate(annotation) => Some(annotation),
+							_ => None,
+						})
+						.collect(),
+					Self::get_overlay(matches),
+					Self::get_pipeline(matches),
+					matches.value_of("save-recipe").map(PathBuf::from),
 				)
 			}
 			None => Self::default(),
@@ -245,6 +725,8 @@ impl EditSettings {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::args::Args;
+	use crate::edit::overlay::Gravity;
 	use clap::{App, Arg};
 	use pretty_assertions::assert_eq;
 	#[test]
@@ -256,10 +738,49 @@ mod tests {
 			.arg(Arg::with_name("invert").long("invert"))
 			.arg(Arg::with_name("crop").long("crop").takes_value(true))
 			.arg(Arg::with_name("resize").long("resize").takes_value(true))
+			.arg(
+				Arg::with_name("resize-mode")
+					.long("resize-mode")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("letterbox")
+					.long("letterbox")
+					.takes_value(true),
+			)
 			.arg(Arg::with_name("ratio").long("ratio").takes_value(true))
+			.arg(
+				Arg::with_name("max-dimensions")
+					.long("max-dimensions")
+					.takes_value(true),
+			)
 			.arg(Arg::with_name("flip").long("flip").takes_value(true))
 			.arg(Arg::with_name("rotate").long("rotate").takes_value(true))
+			.arg(
+				Arg::with_name("rotate-fill")
+					.long("rotate-fill")
+					.takes_value(true),
+			)
+			.arg(Arg::with_name("rotate-crop").long("rotate-crop"))
 			.arg(Arg::with_name("blur").long("blur").takes_value(true))
+			.arg(Arg::with_name("sharpen").long("sharpen"))
+			.arg(Arg::with_name("levels").long("levels").takes_value(true))
+			.arg(Arg::with_name("gamma").long("gamma").takes_value(true))
+			.arg(Arg::with_name("curve").long("curve").takes_value(true))
+			.arg(
+				Arg::with_name("temperature")
+					.long("temperature")
+					.allow_hyphen_values(true)
+					.takes_value(true),
+			)
+			.arg(Arg::with_name("sepia").long("sepia"))
+			.arg(
+				Arg::with_name("unsharpen")
+					.long("unsharpen")
+					.takes_value(true),
+			)
+			.arg(Arg::with_name("kernel").long("kernel").takes_value(true))
+			.arg(Arg::with_name("emboss").long("emboss"))
 			.arg(Arg::with_name("hue").long("hue").takes_value(true))
 			.arg(
 				Arg::with_name("contrast")
@@ -273,6 +794,22 @@ mod tests {
 					.takes_value(true),
 			)
 			.arg(Arg::with_name("filter").long("filter").takes_value(true))
+			.arg(Arg::with_name("strip-metadata").long("strip-metadata"))
+			.arg(Arg::with_name("no-auto-orient").long("no-auto-orient"))
+			.arg(
+				Arg::with_name("set-tag")
+					.long("set-tag")
+					.multiple(true)
+					.number_of_values(1)
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("remove-tag")
+					.long("remove-tag")
+					.multiple(true)
+					.number_of_values(1)
+					.takes_value(true),
+			)
 			.get_matches_from(vec![
 				"test",
 				"x",
@@ -281,14 +818,38 @@ mod tests {
 				"10",
 				"--resize",
 				"100:100",
+				"--resize-mode",
+				"fit",
+				"--letterbox",
+				"000000",
 				"--ratio",
 				"0.5",
+				"--max-dimensions",
+				"640x",
 				"--flip",
 				"horizontal",
 				"--rotate",
 				"90",
+				"--rotate-fill",
+				"ff0000",
+				"--rotate-crop",
 				"--blur",
 				"1.5",
+				"--sharpen",
+				"--levels",
+				"10:250",
+				"--gamma",
+				"2.2",
+				"--curve",
+				"0:10",
+				"--temperature",
+				"-30",
+				"--sepia",
+				"--unsharpen",
+				"2:5",
+				"--kernel",
+				"1x1:2",
+				"--emboss",
 				"--grayscale",
 				"--invert",
 				"--hue",
@@ -299,20 +860,194 @@ mod tests {
 				"2",
 				"--filter",
 				"triangle",
+				"--strip-metadata",
+				"--no-auto-orient",
+				"--set-tag",
+				"Artist=menyoki",
+				"--set-tag",
+				"invalid",
+				"--set-tag",
+				"Comment=a=b",
+				"--remove-tag",
+				"Title",
 			]);
 		let edit_settings = EditSettings::from_parser(ArgParser::from_args(&args));
 		assert_eq!(PathBuf::from("x"), edit_settings.path);
 		assert_eq!(true, edit_settings.convert);
 		assert_eq!(10, edit_settings.image.crop.top);
+		assert_eq!(ResizeMode::Fit, edit_settings.image.resize_mode);
+		assert_eq!(Some(Rgba([0, 0, 0, 255])), edit_settings.image.letterbox);
 		assert_eq!(0.5, edit_settings.image.ratio);
+		assert_eq!(
+			Geometry::new(0, 0, 640, 0),
+			edit_settings.image.max_dimensions
+		);
 		assert_eq!(Some(Flip::Horizontal), edit_settings.image.flip);
-		assert_eq!(90, edit_settings.image.rotate);
+		assert_eq!(90., edit_settings.image.rotate);
+		assert_eq!(Rgba([255, 0, 0, 255]), edit_settings.image.rotate_fill);
+		assert_eq!(true, edit_settings.image.rotate_crop);
 		assert_eq!(1.5, edit_settings.image.blur);
+		assert_eq!(Some(Levels::new(10, 250)), edit_settings.color.levels);
+		assert_eq!(2.2, edit_settings.color.gamma);
+		assert_eq!(
+			Ok(edit_settings.color.curve.clone().unwrap()),
+			Curve::parse("0:10")
+		);
+		assert_eq!(-30., edit_settings.color.temperature);
+		assert_eq!(0., edit_settings.color.saturation);
+		assert_eq!(true, edit_settings.color.sepia);
+		assert_eq!(true, edit_settings.convolution.sharpen);
+		assert_eq!(
+			Some(String::from("2:5")),
+			edit_settings.convolution.unsharpen.map(|v| v.to_string())
+		);
+		assert_eq!(
+			Some(Kernel::new(1, 1, vec![2.])),
+			edit_settings.convolution.kernel
+		);
+		assert_eq!(false, edit_settings.convolution.edges);
+		assert_eq!(true, edit_settings.convolution.emboss);
 		assert_eq!(true, edit_settings.color.grayscale);
 		assert_eq!(true, edit_settings.color.invert);
 		assert_eq!(2, edit_settings.color.brightness);
 		assert_eq!(3, edit_settings.color.hue);
 		assert_eq!(-5., edit_settings.color.contrast);
 		assert_eq!("Triangle", format!("{:?}", edit_settings.image.filter));
+		assert_eq!(false, edit_settings.image.auto_orient);
+		assert_eq!(true, edit_settings.metadata.strip);
+		assert_eq!(
+			vec![String::from("Title")],
+			edit_settings.metadata.remove_tags
+		);
+		let mut metadata =
+			ImageMetadata::new(Some(String::from("x")), None, None, None);
+		edit_settings.metadata.apply(&mut metadata);
+		assert_eq!(None, metadata.title);
+		assert_eq!(
+			vec![
+				(String::from("Artist"), String::from("menyoki")),
+				(String::from("Comment"), String::from("a=b"))
+			],
+			metadata.tags
+		);
+		assert_eq!(None, edit_settings.pipeline);
+		let recipe = PathBuf::from("test_settings.ops");
+		Pipeline::new(vec![Operation::Invert])
+			.save(&recipe)
+			.unwrap();
+		let overlay = PathBuf::from("test_settings.png");
+		image::RgbaImage::new(2, 2).save(&overlay).unwrap();
+		let overlay_operation = Operation::Overlay(
+			Overlay::new(overlay.clone(), Gravity::North, (0, 0), 0.5, None)
+				.unwrap(),
+		);
+		for (args, operations) in [
+			(
+				vec![
+					"edit",
+					"x",
+					"--rotate",
+					"90",
+					"--grayscale",
+					"--rotate",
+					"180",
+					"--pipeline",
+					"--recipe",
+					"test_settings.ops",
+				],
+				vec![
+					Operation::Invert,
+					Operation::Rotate(90.),
+					Operation::Grayscale,
+					Operation::Rotate(180.),
+				],
+			),
+			(
+				vec!["make", "x", "--recipe", "test_settings.ops"],
+				vec![Operation::Invert],
+			),
+			(
+				vec!["edit", "x", "--recipe", "test_settings.ops", "--ratio", "2"],
+				vec![Operation::Invert, Operation::Ratio(2.)],
+			),
+			(
+				vec![
+					"edit",
+					"x",
+					"--trim",
+					"--crop",
+					"1",
+					"--trim=5",
+					"--pipeline",
+				],
+				vec![
+					Operation::Trim(0.),
+					Operation::Crop(Padding::parse("1")),
+					Operation::Trim(5.),
+				],
+			),
+			(
+				vec![
+					"edit",
+					"--grayscale",
+					"--trim",
+					"x",
+					"--crop=2",
+					"--pipeline",
+					"--trim",
+				],
+				vec![
+					Operation::Grayscale,
+					Operation::Trim(0.),
+					Operation::Crop(Padding::parse("2")),
+					Operation::Trim(0.),
+				],
+			),
+			(
+				vec![
+					"edit",
+					"x",
+					"--grayscale",
+					"--overlay",
+					"test_settings.png",
+					"--gravity",
+					"north",
+					"--invert",
+					"--opacity=0.5",
+					"--pipeline",
+				],
+				vec![
+					Operation::Grayscale,
+					overlay_operation.clone(),
+					Operation::Invert,
+				],
+			),
+		] {
+			let args =
+				Args::get_app().get_matches_from([vec!["menyoki"], args].concat());
+			let edit_settings = EditSettings::from_args(&ArgMatches::new(&args));
+			assert_eq!(Some(Pipeline::new(operations)), edit_settings.pipeline);
+		}
+		assert_eq!(
+			Ok(Pipeline::new(vec![overlay_operation.clone()])),
+			Pipeline::parse(&Pipeline::new(vec![overlay_operation]).to_string())
+		);
+		std::fs::remove_file(recipe).unwrap();
+		std::fs::remove_file(overlay).unwrap();
+		let args = Args::get_app().get_matches_from(vec![
+			"menyoki",
+			"capture",
+			"--redact",
+			"10x10+5+5:fill",
+			"--redact",
+			"20x5",
+		]);
+		assert_eq!(
+			vec![
+				Redaction::parse("10x10+5+5:fill=000000").unwrap(),
+				Redaction::parse("20x5+0+0:pixelate=10").unwrap()
+			],
+			EditSettings::from_args(&ArgMatches::new(&args)).redactions
+		);
 	}
 }
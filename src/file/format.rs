@@ -1,7 +1,11 @@
+use crate::anim::AnimFormat;
 use crate::args::matches::ArgMatches;
 use crate::file::File;
 use image::codecs::pnm::PnmSubtype;
 use std::fmt;
+use std::fs;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
 use std::str::FromStr;
 
 /* Format of the output file */
@@ -10,16 +14,24 @@ pub enum FileFormat {
 	Any,
 	Gif,
 	Apng,
+	AnimWebP,
+	AnimTiff,
+	Y4m,
+	Rgba,
 	Png,
 	Jpg,
 	WebP,
+	Avif,
 	Bmp,
 	Ico,
+	Icns,
 	Tiff,
 	Tga,
 	Pnm(String),
 	Ff,
 	Exr,
+	Qoi,
+	Pdf,
 	Txt,
 }
 
@@ -40,19 +52,38 @@ impl FromStr for FileFormat {
 			"png" => Ok(Self::Png),
 			"jpg" => Ok(Self::Jpg),
 			"webp" => Ok(Self::WebP),
+			"avif" => Ok(Self::Avif),
 			"bmp" => Ok(Self::Bmp),
 			"ico" => Ok(Self::Ico),
+			"icns" => Ok(Self::Icns),
 			"tiff" => Ok(Self::Tiff),
 			"tga" => Ok(Self::Tga),
 			"pnm" => Ok(Self::Pnm(String::from("ppm"))),
 			"ff" => Ok(Self::Ff),
 			"exr" => Ok(Self::Exr),
+			"qoi" => Ok(Self::Qoi),
+			"pdf" => Ok(Self::Pdf),
 			"txt" => Ok(Self::Txt),
 			_ => Err("Unrecognized file format"),
 		}
 	}
 }
 
+/* Conversion from the animation format */
+impl From<AnimFormat> for FileFormat {
+	fn from(format: AnimFormat) -> Self {
+		match format {
+			AnimFormat::Gif => Self::Gif,
+			AnimFormat::Apng => Self::Apng,
+			AnimFormat::WebP => Self::AnimWebP,
+			AnimFormat::Tiff => Self::AnimTiff,
+			AnimFormat::Y4m => Self::Y4m,
+			AnimFormat::Rgba => Self::Rgba,
+			AnimFormat::Pdf => Self::Pdf,
+		}
+	}
+}
+
 impl FileFormat {
 	/**
 	 * Create a FileFormat enum from parsed arguments.
@@ -83,10 +114,20 @@ impl FileFormat {
 					}
 				} else if matches.is_present("gif") {
 					Self::Gif
+				} else if matches.is_present("apng") {
+					Self::Apng
+				} else if matches.is_present("y4m") {
+					Self::Y4m
+				} else if matches.is_present("rgba") {
+					Self::Rgba
 				} else if matches.is_present("ff") {
 					Self::Ff
 				} else if matches.is_present("exr") {
 					Self::Exr
+				} else if matches.is_present("qoi") {
+					Self::Qoi
+				} else if matches.is_present("pdf") {
+					Self::Pdf
 				} else if matches.is_present("tiff") {
 					Self::Tiff
 				} else if matches.is_present("tga") {
@@ -103,10 +144,14 @@ impl FileFormat {
 					Self::Bmp
 				} else if matches.is_present("ico") {
 					Self::Ico
+				} else if matches.is_present("icns") {
+					Self::Icns
 				} else if matches.is_present("jpg") {
 					Self::Jpg
 				} else if matches.is_present("webp") {
 					Self::WebP
+				} else if matches.is_present("avif") {
+					Self::Avif
 				} else {
 					Self::Png
 				}
@@ -115,6 +160,14 @@ impl FileFormat {
 				if let Some(matches) = args.subcommand_matches("record") {
 					if matches.is_present("apng") {
 						Self::Apng
+					} else if matches.is_present("webp") {
+						Self::AnimWebP
+					} else if matches.is_present("tiff") {
+						Self::AnimTiff
+					} else if matches.is_present("y4m") {
+						Self::Y4m
+					} else if matches.is_present("rgba") {
+						Self::Rgba
 					} else {
 						Self::Gif
 					}
@@ -151,7 +204,12 @@ impl FileFormat {
 		String::from(match self {
 			Self::Any => "output",
 			Self::Txt => "report",
-			Self::Gif | Self::Apng => "rec",
+			Self::Gif
+			| Self::Apng
+			| Self::AnimWebP
+			| Self::AnimTiff
+			| Self::Y4m
+			| Self::Rgba => "rec",
 			_ => "cap",
 		})
 	}
@@ -162,7 +220,122 @@ impl FileFormat {
 	 * @return bool
 	 */
 	pub fn is_animation(&self) -> bool {
-		self == &Self::Gif || self == &Self::Apng
+		matches!(
+			self,
+			Self::Gif
+				| Self::Apng | Self::AnimWebP
+				| Self::AnimTiff
+				| Self::Y4m | Self::Rgba
+		)
+	}
+
+	/**
+	 * Check if the given file contains an animation.
+	 *
+	 * Only the headers are read from the file.
+	 *
+	 * @param  path
+	 * @return bool
+	 */
+	pub fn is_animated_file(path: &Path) -> bool {
+		fs::File::open(path)
+			.and_then(|file| Self::read_animation(&mut BufReader::new(file)))
+			.unwrap_or(false)
+	}
+
+	/**
+	 * Read the headers to check if there are multiple frames.
+	 *
+	 * GIF frames are counted while the other formats are checked
+	 * for their animation control (APNG), next page (TIFF) and
+	 * animation flag (WebP) fields.
+	 *
+	 * @param  reader
+	 * @return bool (Result)
+	 */
+	fn read_animation<R: Read + Seek>(reader: &mut R) -> io::Result<bool> {
+		fn read<R: Read, const N: usize>(reader: &mut R) -> io::Result<[u8; N]> {
+			let mut buffer = [0; N];
+			reader.read_exact(&mut buffer)?;
+			Ok(buffer)
+		}
+		fn skip_sub_blocks<R: Read + Seek>(reader: &mut R) -> io::Result<()> {
+			loop {
+				match read::<R, 1>(reader)?[0] {
+					0 => return Ok(()),
+					size => reader.seek(SeekFrom::Current(i64::from(size)))?,
+				};
+			}
+		}
+		let header = read::<R, 8>(reader)?;
+		if header.starts_with(b"GIF8") {
+			let screen = read::<R, 5>(reader)?;
+			if screen[2] & 0x80 != 0 {
+				reader.seek(SeekFrom::Current(3 << ((screen[2] & 0x07) + 1)))?;
+			}
+			let mut frames = 0;
+			loop {
+				match read::<R, 1>(reader)?[0] {
+					0x21 => {
+						read::<R, 1>(reader)?;
+						skip_sub_blocks(reader)?;
+					}
+					0x2C => {
+						frames += 1;
+						if frames > 1 {
+							return Ok(true);
+						}
+						let descriptor = read::<R, 9>(reader)?;
+						if descriptor[8] & 0x80 != 0 {
+							reader.seek(SeekFrom::Current(
+								3 << ((descriptor[8] & 0x07) + 1),
+							))?;
+						}
+						read::<R, 1>(reader)?;
+						skip_sub_blocks(reader)?;
+					}
+					_ => return Ok(false),
+				}
+			}
+		} else if &header == b"\x89PNG\r\n\x1a\n" {
+			loop {
+				let chunk = read::<R, 8>(reader)?;
+				match &chunk[4..8] {
+					b"acTL" => return Ok(true),
+					b"IDAT" => return Ok(false),
+					_ => {
+						let length = u32::from_be_bytes([
+							chunk[0], chunk[1], chunk[2], chunk[3],
+						]);
+						reader.seek(SeekFrom::Current(i64::from(length) + 4))?;
+					}
+				}
+			}
+		} else if header.starts_with(b"II*\0") || header.starts_with(b"MM\0*") {
+			let little_endian = header[0] == b'I';
+			let get_u32 = |bytes: [u8; 4]| {
+				if little_endian {
+					u32::from_le_bytes(bytes)
+				} else {
+					u32::from_be_bytes(bytes)
+				}
+			};
+			let offset = get_u32([header[4], header[5], header[6], header[7]]);
+			reader.seek(SeekFrom::Start(u64::from(offset)))?;
+			let entries = read::<R, 2>(reader)?;
+			let entries = if little_endian {
+				u16::from_le_bytes(entries)
+			} else {
+				u16::from_be_bytes(entries)
+			};
+			reader.seek(SeekFrom::Current(i64::from(entries) * 12))?;
+			Ok(get_u32(read::<R, 4>(reader)?) != 0)
+		} else if header.starts_with(b"RIFF") {
+			let chunk = read::<R, 13>(reader)?;
+			Ok(&chunk[..8] == b"WEBPVP8X" && chunk[12] & 0x02 != 0)
+		} else {
+			Ok(false)
+		}
 	}
 
 	/**
@@ -174,6 +347,8 @@ impl FileFormat {
 		match self {
 			Self::Any => String::from("*"),
 			Self::Pnm(v) => v.to_string(),
+			Self::AnimWebP => String::from("webp"),
+			Self::AnimTiff => String::from("tiff"),
 			_ => self.to_string(),
 		}
 		.to_lowercase()
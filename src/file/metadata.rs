@@ -0,0 +1,912 @@
+use crate::file::format::FileFormat;
+use crate::image::geometry::Geometry;
+use chrono::{DateTime, Local, SecondsFormat};
+use exif::{In, Reader as ExifReader, Tag, Value};
+use flate2::Crc;
+use std::io::Cursor;
+
+/* Signature of the PNG files */
+const PNG_SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+
+/* Identifier of the EXIF data in JPEG and WebP files */
+const EXIF_HEADER: &[u8; 6] = b"Exif\0\0";
+
+/* Character code of the EXIF user comment */
+const EXIF_ASCII_PREFIX: &[u8; 8] = b"ASCII\0\0\0";
+
+/* TIFF tags that are used for storing the metadata */
+const TAG_IMAGE_DESCRIPTION: u16 = 0x010E;
+const TAG_MAKE: u16 = 0x010F;
+const TAG_MODEL: u16 = 0x0110;
+const TAG_ORIENTATION: u16 = 0x0112;
+const TAG_SOFTWARE: u16 = 0x0131;
+const TAG_DATE_TIME: u16 = 0x0132;
+const TAG_ARTIST: u16 = 0x013B;
+const TAG_COPYRIGHT: u16 = 0x8298;
+const TAG_EXIF_IFD: u16 = 0x8769;
+const TAG_USER_COMMENT: u16 = 0x9286;
+
+/* EXIF fields of the source image that are kept as tags */
+const EXIF_FIELDS: &[(Tag, &[&str])] = &[
+	(Tag::Make, &["Make"]),
+	(Tag::Model, &["Model"]),
+	(Tag::Artist, &["Artist", "Author"]),
+	(Tag::Copyright, &["Copyright"]),
+	(Tag::DateTimeOriginal, &["DateTimeOriginal"]),
+	(Tag::LensMake, &["LensMake"]),
+	(Tag::LensModel, &["LensModel"]),
+	(Tag::BodySerialNumber, &["BodySerialNumber"]),
+	(Tag::GPSLatitudeRef, &["GPSLatitudeRef"]),
+	(Tag::GPSLatitude, &["GPSLatitude"]),
+	(Tag::GPSLongitudeRef, &["GPSLongitudeRef"]),
+	(Tag::GPSLongitude, &["GPSLongitude"]),
+	(Tag::GPSAltitude, &["GPSAltitude"]),
+];
+
+/* TIFF field types */
+const TYPE_ASCII: u16 = 2;
+const TYPE_SHORT: u16 = 3;
+const TYPE_LONG: u16 = 4;
+const TYPE_UNDEFINED: u16 = 7;
+
+/* Entry of an image file directory (tag, type, count, value) */
+type IfdEntry = (u16, u16, u32, Vec<u8>);
+
+/* Provenance information of the saved image */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImageMetadata {
+	pub title: Option<String>,
+	pub geometry: Option<String>,
+	pub monitor: Option<String>,
+	pub timestamp: Option<String>,
+	pub software: Option<String>,
+	pub command: Option<String>,
+	pub tags: Vec<(String, String)>,
+}
+
+impl ImageMetadata {
+	/**
+	 * Create a new ImageMetadata object with the current time.
+	 *
+	 * @param  title (Option)
+	 * @param  geometry (Option)
+	 * @param  monitor (Option)
+	 * @param  command (Option)
+	 * @return ImageMetadata
+	 */
+	pub fn new(
+		title: Option<String>,
+		geometry: Option<Geometry>,
+		monitor: Option<usize>,
+		command: Option<&str>,
+	) -> Self {
+		Self {
+			title,
+			geometry: geometry.map(|geometry| {
+				format!(
+					"{}x{}+{}+{}",
+					geometry.width, geometry.height, geometry.x, geometry.y
+				)
+			}),
+			monitor: monitor.map(|monitor| monitor.to_string()),
+			timestamp: Some(Local::now().to_rfc3339_opts(SecondsFormat::Secs, true)),
+			software: Some(format!(
+				"{} v{}",
+				env!("CARGO_PKG_NAME"),
+				env!("CARGO_PKG_VERSION")
+			)),
+			command: command.map(String::from),
+			tags: Vec::new(),
+		}
+	}
+
+	/**
+	 * Get the available fields as key-value pairs.
+	 *
+	 * @return Vector of tuple (key, value)
+	 */
+	pub fn get_fields(&self) -> Vec<(&str, &String)> {
+		[
+			("Title", &self.title),
+			("Geometry", &self.geometry),
+			("Monitor", &self.monitor),
+			("Timestamp", &self.timestamp),
+			("Software", &self.software),
+			("Command", &self.command),
+		]
+		.into_iter()
+		.filter_map(|(key, value)| value.as_ref().map(|value| (key, value)))
+		.chain(self.tags.iter().map(|(key, value)| (key.as_str(), value)))
+		.collect()
+	}
+
+	/**
+	 * Get the known field with the given key (case-insensitive).
+	 *
+	 * @param  key
+	 * @return Option of String (Option)
+	 */
+	fn get_field_mut(&mut self, key: &str) -> Option<&mut Option<String>> {
+		match key.to_lowercase().as_str() {
+			"title" => Some(&mut self.title),
+			"geometry" => Some(&mut self.geometry),
+			"monitor" => Some(&mut self.monitor),
+			"timestamp" => Some(&mut self.timestamp),
+			"software" => Some(&mut self.software),
+			"command" => Some(&mut self.command),
+			_ => None,
+		}
+	}
+
+	/**
+	 * Check if the key can be used as a tag.
+	 *
+	 * Keys follow the rules of the PNG text keywords: 1-79 printable
+	 * Latin-1 characters without leading, trailing or consecutive spaces.
+	 *
+	 * @param  key
+	 * @return bool
+	 */
+	pub fn is_valid_key(key: &str) -> bool {
+		(1..=79).contains(&key.chars().count())
+			&& key
+				.chars()
+				.all(|c| matches!(c, ' '..='~' | '\u{A1}'..='\u{FF}'))
+			&& !key.starts_with(' ')
+			&& !key.ends_with(' ')
+			&& !key.contains("  ")
+	}
+
+	/**
+	 * Set the value of the field with the given key.
+	 *
+	 * Unknown keys are stored as custom tags.
+	 *
+	 * @param  key
+	 * @param  value
+	 */
+	pub fn set_field(&mut self, key: &str, value: &str) {
+		if let Some(field) = self.get_field_mut(key) {
+			*field = Some(value.to_string());
+		} else if let Some(tag) = self
+			.tags
+			.iter_mut()
+			.find(|(k, _)| k.eq_ignore_ascii_case(key))
+		{
+			tag.1 = value.to_string();
+		} else {
+			self.tags.push((key.to_string(), value.to_string()));
+		}
+	}
+
+	/**
+	 * Remove the field with the given key.
+	 *
+	 * @param  key
+	 */
+	pub fn remove_field(&mut self, key: &str) {
+		if let Some(field) = self.get_field_mut(key) {
+			*field = None;
+		}
+		self.tags.retain(|(k, _)| !k.eq_ignore_ascii_case(key));
+	}
+
+	/**
+	 * Get the value of the custom tag with one of the given keys.
+	 *
+	 * @param  keys
+	 * @return String (Option)
+	 */
+	fn get_tag(&self, keys: &[&str]) -> Option<&String> {
+		self.tags.iter().find_map(|(key, value)| {
+			keys.iter()
+				.any(|v| key.eq_ignore_ascii_case(v))
+				.then_some(value)
+		})
+	}
+
+	/**
+	 * Check if none of the fields are set.
+	 *
+	 * @return bool
+	 */
+	pub fn is_empty(&self) -> bool {
+		self.get_fields().is_empty()
+	}
+
+	/**
+	 * Get the fields as "key: value" lines.
+	 *
+	 * @return String
+	 */
+	fn to_text(&self) -> String {
+		self.get_fields()
+			.iter()
+			.map(|(key, value)| format!("{key}: {}", value.replace('\n', " ")))
+			.collect::<Vec<String>>()
+			.join("\n")
+	}
+
+	/**
+	 * Parse the fields from "key: value" lines.
+	 *
+	 * @param  text
+	 * @return ImageMetadata
+	 */
+	fn from_text(text: &str) -> Self {
+		let mut metadata = Self::default();
+		for line in text.lines() {
+			if let Some((key, value)) = line.split_once(": ") {
+				metadata.set_field(key, value);
+			}
+		}
+		metadata
+	}
+
+	/**
+	 * Check if the data has the PNG signature.
+	 *
+	 * @param  data
+	 * @return bool
+	 */
+	fn is_png(data: &[u8]) -> bool {
+		data.starts_with(PNG_SIGNATURE)
+	}
+
+	/**
+	 * Check if the data has the GIF signature.
+	 *
+	 * @param  data
+	 * @return bool
+	 */
+	fn is_gif(data: &[u8]) -> bool {
+		data.starts_with(b"GIF8")
+	}
+
+	/**
+	 * Check if the data has the JPEG start of image marker.
+	 *
+	 * @param  data
+	 * @return bool
+	 */
+	fn is_jpeg(data: &[u8]) -> bool {
+		data.starts_with(&[0xFF, 0xD8])
+	}
+
+	/**
+	 * Check if the data has the WebP (RIFF) header.
+	 *
+	 * @param  data
+	 * @return bool
+	 */
+	fn is_webp(data: &[u8]) -> bool {
+		data.len() > 20 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP"
+	}
+
+	/**
+	 * Check if the data has the (little-endian) TIFF header.
+	 *
+	 * @param  data
+	 * @return bool
+	 */
+	fn is_tiff(data: &[u8]) -> bool {
+		data.starts_with(b"II*\0")
+	}
+
+	/**
+	 * Embed the metadata into the encoded image data.
+	 *
+	 * PNG files get text chunks, GIF files get a comment extension
+	 * and JPEG, WebP and TIFF files get EXIF data.
+	 * Data of the other formats is returned as is.
+	 *
+	 * @param  data
+	 * @param  format
+	 * @return Vector of u8
+	 */
+	pub fn embed(&self, data: Vec<u8>, format: &FileFormat) -> Vec<u8> {
+		if self.is_empty() {
+			return data;
+		}
+		match format {
+			FileFormat::Png | FileFormat::Apng if Self::is_png(&data) => {
+				self.embed_png(data)
+			}
+			FileFormat::Gif if Self::is_gif(&data) => self.embed_gif(data),
+			FileFormat::Jpg if Self::is_jpeg(&data) => self.embed_jpeg(data),
+			FileFormat::WebP | FileFormat::AnimWebP if Self::is_webp(&data) => {
+				self.embed_webp(data)
+			}
+			FileFormat::Tiff | FileFormat::AnimTiff if Self::is_tiff(&data) => {
+				self.embed_tiff(data)
+			}
+			_ => data,
+		}
+	}
+
+	/**
+	 * Read the metadata from the encoded image data.
+	 *
+	 * @param  data
+	 * @return ImageMetadata (Option)
+	 */
+	pub fn read(data: &[u8]) -> Option<Self> {
+		let mut metadata = if Self::is_png(data) {
+			Self::read_png(data)
+		} else if Self::is_gif(data) {
+			Self::read_gif(data)
+		} else {
+			Self::default()
+		};
+		if !Self::is_gif(data) {
+			metadata.read_exif(data);
+		}
+		(!metadata.is_empty()).then_some(metadata)
+	}
+
+	/**
+	 * Create a PNG chunk.
+	 *
+	 * @param  chunk_type
+	 * @param  chunk_data
+	 * @return Vector of u8
+	 */
+	fn get_png_chunk(chunk_type: &[u8; 4], chunk_data: &[u8]) -> Vec<u8> {
+		let mut crc = Crc::new();
+		crc.update(chunk_type);
+		crc.update(chunk_data);
+		let mut chunk = (chunk_data.len() as u32).to_be_bytes().to_vec();
+		chunk.extend(chunk_type);
+		chunk.extend(chunk_data);
+		chunk.extend(crc.sum().to_be_bytes());
+		chunk
+	}
+
+	/**
+	 * Insert text chunks after the PNG header chunk.
+	 *
+	 * @param  data
+	 * @return Vector of u8
+	 */
+	fn embed_png(&self, mut data: Vec<u8>) -> Vec<u8> {
+		let position = PNG_SIGNATURE.len() + 25;
+		if data.len() < position {
+			return data;
+		}
+		let chunks = self
+			.get_fields()
+			.into_iter()
+			.flat_map(|(key, value)| {
+				let mut chunk_data = key
+					.chars()
+					.filter_map(|c| u8::try_from(c).ok())
+					.collect::<Vec<u8>>();
+				if value.is_ascii() {
+					chunk_data.push(0);
+					chunk_data.extend(value.as_bytes());
+					Self::get_png_chunk(b"tEXt", &chunk_data)
+				} else {
+					chunk_data.extend([0, 0, 0, 0, 0]);
+					chunk_data.extend(value.as_bytes());
+					Self::get_png_chunk(b"iTXt", &chunk_data)
+				}
+			})
+			.collect::<Vec<u8>>();
+		data.splice(position..position, chunks);
+		data
+	}
+
+	/**
+	 * Decode the Latin-1 text of the PNG chunks.
+	 *
+	 * @param  text
+	 * @return String
+	 */
+	fn get_latin1(text: &[u8]) -> String {
+		text.iter().map(|v| char::from(*v)).collect()
+	}
+
+	/**
+	 * Read the fields from the text chunks of PNG data.
+	 *
+	 * @param  data
+	 * @return ImageMetadata
+	 */
+	fn read_png(data: &[u8]) -> Self {
+		let mut metadata = Self::default();
+		let mut chunks = &data[PNG_SIGNATURE.len()..];
+		while chunks.len() >= 12 {
+			let length =
+				u32::from_be_bytes([chunks[0], chunks[1], chunks[2], chunks[3]])
+					as usize;
+			let chunk_data = chunks.get(8..8 + length).unwrap_or_default();
+			let mut values = chunk_data.split(|v| *v == 0);
+			match &chunks[4..8] {
+				b"tEXt" => {
+					if let (Some(key), Some(value)) = (values.next(), values.next())
+					{
+						metadata.set_field(
+							&Self::get_latin1(key),
+							&Self::get_latin1(value),
+						);
+					}
+				}
+				b"iTXt" => {
+					let key = values.next().unwrap_or_default();
+					let compressed = chunk_data.get(key.len() + 1) != Some(&0);
+					if let Some(text) = chunk_data
+						.get(key.len() + 3..)
+						.and_then(|v| v.splitn(3, |v| *v == 0).nth(2))
+						.filter(|_| !compressed)
+					{
+						metadata.set_field(
+							&Self::get_latin1(key),
+							&String::from_utf8_lossy(text),
+						);
+					}
+				}
+				b"IEND" => break,
+				_ => {}
+			}
+			chunks = chunks.get(length.saturating_add(12)..).unwrap_or_default();
+		}
+		metadata
+	}
+
+	/**
+	 * Get the position of the first block after the GIF header.
+	 *
+	 * @param  data
+	 * @return usize
+	 */
+	fn get_gif_block_position(data: &[u8]) -> usize {
+		match data.get(10) {
+			Some(flags) if flags & 0x80 != 0 => 13 + 3 * (2 << (flags & 0x07)),
+			_ => 13,
+		}
+	}
+
+	/**
+	 * Insert a comment extension before the GIF trailer.
+	 *
+	 * @param  data
+	 * @return Vector of u8
+	 */
+	fn embed_gif(&self, mut data: Vec<u8>) -> Vec<u8> {
+		if data.last() != Some(&0x3B) {
+			return data;
+		}
+		let mut extension = vec![0x21, 0xFE];
+		for block in self.to_text().as_bytes().chunks(255) {
+			extension.push(block.len() as u8);
+			extension.extend(block);
+		}
+		extension.push(0);
+		let position = data.len() - 1;
+		data.splice(position..position, extension);
+		data
+	}
+
+	/**
+	 * Read the fields from the comment extensions of GIF data.
+	 *
+	 * @param  data
+	 * @return ImageMetadata
+	 */
+	fn read_gif(data: &[u8]) -> Self {
+		let mut comment = Vec::new();
+		let mut position = Self::get_gif_block_position(data);
+		loop {
+			let is_comment = match data.get(position) {
+				Some(0x21) => {
+					position += 2;
+					data.get(position - 1) == Some(&0xFE)
+				}
+				Some(0x2C) => {
+					let flags = data.get(position + 9).copied().unwrap_or_default();
+					position += 11;
+					if flags & 0x80 != 0 {
+						position += 3 * (2 << (flags & 0x07));
+					}
+					false
+				}
+				_ => break,
+			};
+			while let Some(length) = data.get(position).map(|v| usize::from(*v)) {
+				position += 1;
+				if length == 0 {
+					break;
+				}
+				if is_comment {
+					comment.extend(
+						data.get(position..position + length).unwrap_or_default(),
+					);
+				}
+				position += length;
+			}
+		}
+		Self::from_text(&String::from_utf8_lossy(&comment))
+	}
+
+	/**
+	 * Write an image file directory (little endian) to the TIFF data.
+	 *
+	 * @param  data
+	 * @param  entries
+	 * @param  next
+	 * @return u32
+	 */
+	fn write_ifd(data: &mut Vec<u8>, mut entries: Vec<IfdEntry>, next: u32) -> u32 {
+		entries.sort_by_key(|entry| entry.0);
+		if !data.len().is_multiple_of(2) {
+			data.push(0);
+		}
+		let offset = data.len();
+		let mut value_offset = offset + 2 + entries.len() * 12 + 4;
+		let mut values = Vec::new();
+		data.extend((entries.len() as u16).to_le_bytes());
+		for (tag, field_type, count, value) in entries {
+			data.extend(tag.to_le_bytes());
+			data.extend(field_type.to_le_bytes());
+			data.extend(count.to_le_bytes());
+			if value.len() <= 4 {
+				data.extend(&value);
+				data.extend(vec![0; 4 - value.len()]);
+			} else {
+				data.extend((value_offset as u32).to_le_bytes());
+				value_offset += value.len() + value.len() % 2;
+				values.extend(&value);
+				values.extend(vec![0; value.len() % 2]);
+			}
+		}
+		data.extend(next.to_le_bytes());
+		data.extend(values);
+		offset as u32
+	}
+
+	/**
+	 * Get the TIFF entries that hold the metadata.
+	 *
+	 * @param  data
+	 * @return Vector of IfdEntry
+	 */
+	fn get_ifd_entries(&self, data: &mut Vec<u8>) -> Vec<IfdEntry> {
+		let ascii = |tag: u16, value: &str| {
+			let mut value = value.as_bytes().to_vec();
+			value.push(0);
+			(tag, TYPE_ASCII, value.len() as u32, value)
+		};
+		let mut comment = EXIF_ASCII_PREFIX.to_vec();
+		comment.extend(self.to_text().as_bytes());
+		let exif_ifd = Self::write_ifd(
+			data,
+			vec![(
+				TAG_USER_COMMENT,
+				TYPE_UNDEFINED,
+				comment.len() as u32,
+				comment,
+			)],
+			0,
+		);
+		let mut entries = vec![
+			(TAG_EXIF_IFD, TYPE_LONG, 1, exif_ifd.to_le_bytes().to_vec()),
+			(TAG_ORIENTATION, TYPE_SHORT, 1, 1_u16.to_le_bytes().to_vec()),
+		];
+		if let Some(description) = self
+			.title
+			.as_ref()
+			.or_else(|| self.get_tag(&["Description", "ImageDescription"]))
+		{
+			entries.push(ascii(TAG_IMAGE_DESCRIPTION, description));
+		}
+		for (tag, keys) in [
+			(TAG_MAKE, &["Make"][..]),
+			(TAG_MODEL, &["Model"]),
+			(TAG_ARTIST, &["Artist", "Author"]),
+			(TAG_COPYRIGHT, &["Copyright"]),
+		] {
+			if let Some(value) = self.get_tag(keys) {
+				entries.push(ascii(tag, value));
+			}
+		}
+		if let Some(software) = &self.software {
+			entries.push(ascii(TAG_SOFTWARE, software));
+		}
+		if let Some(timestamp) = &self.timestamp {
+			entries.push(ascii(
+				TAG_DATE_TIME,
+				&DateTime::parse_from_rfc3339(timestamp)
+					.map(|date| date.format("%Y:%m:%d %H:%M:%S").to_string())
+					.unwrap_or_else(|_| timestamp.to_string()),
+			));
+		}
+		entries
+	}
+
+	/**
+	 * Get the metadata as EXIF (TIFF) data.
+	 *
+	 * @return Vector of u8
+	 */
+	fn get_exif(&self) -> Vec<u8> {
+		let mut data = b"II*\0\0\0\0\0".to_vec();
+		let entries = self.get_ifd_entries(&mut data);
+		let offset = Self::write_ifd(&mut data, entries, 0);
+		data[4..8].copy_from_slice(&offset.to_le_bytes());
+		data
+	}
+
+	/**
+	 * Insert an EXIF segment into the JPEG data.
+	 *
+	 * @param  data
+	 * @return Vector of u8
+	 */
+	fn embed_jpeg(&self, mut data: Vec<u8>) -> Vec<u8> {
+		let exif = self.get_exif();
+		let length = 2 + EXIF_HEADER.len() + exif.len();
+		if length > usize::from(u16::MAX) {
+			warn!("Metadata is too large for the JPEG segment.");
+			return data;
+		}
+		let mut segment = vec![0xFF, 0xE1];
+		segment.extend((length as u16).to_be_bytes());
+		segment.extend(EXIF_HEADER);
+		segment.extend(exif);
+		let position = if data.get(2..4) == Some(&[0xFF, 0xE0]) {
+			4 + usize::from(u16::from_be_bytes([data[4], data[5]]))
+		} else {
+			2
+		};
+		data.splice(position..position, segment);
+		data
+	}
+
+	/**
+	 * Add an EXIF chunk to the WebP data.
+	 *
+	 * Simple (lossy/lossless) files are converted to the extended format.
+	 *
+	 * @param  data
+	 * @return Vector of u8
+	 */
+	fn embed_webp(&self, mut data: Vec<u8>) -> Vec<u8> {
+		let canvas = match &data[12..16] {
+			b"VP8 " if data.len() > 30 => Some((
+				u32::from(u16::from_le_bytes([data[26], data[27]]) & 0x3FFF),
+				u32::from(u16::from_le_bytes([data[28], data[29]]) & 0x3FFF),
+				false,
+			)),
+			b"VP8L" if data.len() > 25 => {
+				let bits =
+					u32::from_le_bytes([data[21], data[22], data[23], data[24]]);
+				Some((
+					(bits & 0x3FFF) + 1,
+					((bits >> 14) & 0x3FFF) + 1,
+					bits & (1 << 28) != 0,
+				))
+			}
+			_ => None,
+		};
+		if let Some((width, height, alpha)) = canvas {
+			let mut chunk = b"VP8X".to_vec();
+			chunk.extend(10_u32.to_le_bytes());
+			chunk.extend([if alpha { 0x10 } else { 0 }, 0, 0, 0]);
+			chunk.extend(&(width - 1).to_le_bytes()[..3]);
+			chunk.extend(&(height - 1).to_le_bytes()[..3]);
+			data.splice(12..12, chunk);
+		} else if &data[12..16] != b"VP8X" {
+			return data;
+		}
+		data[20] |= 0x08;
+		let exif = self.get_exif();
+		data.extend(b"EXIF");
+		data.extend((exif.len() as u32).to_le_bytes());
+		data.extend(&exif);
+		if !exif.len().is_multiple_of(2) {
+			data.push(0);
+		}
+		let riff_size = (data.len() - 8) as u32;
+		data[4..8].copy_from_slice(&riff_size.to_le_bytes());
+		data
+	}
+
+	/**
+	 * Add the metadata tags to the first directory of the TIFF data.
+	 *
+	 * @param  data
+	 * @return Vector of u8
+	 */
+	fn embed_tiff(&self, mut data: Vec<u8>) -> Vec<u8> {
+		let entries = self.get_ifd_entries(&mut data);
+		Self::rewrite_tiff(data, entries)
+	}
+
+	/**
+	 * Rewrite the first directory of the TIFF data at the end of the file.
+	 *
+	 * @param  data
+	 * @param  entries
+	 * @return Vector of u8
+	 */
+	fn rewrite_tiff(mut data: Vec<u8>, mut entries: Vec<IfdEntry>) -> Vec<u8> {
+		let read_u16 = |data: &[u8], offset: usize| {
+			data.get(offset..offset + 2)
+				.map(|v| u16::from_le_bytes([v[0], v[1]]))
+		};
+		let read_u32 = |data: &[u8], offset: usize| {
+			data.get(offset..offset + 4)
+				.map(|v| u32::from_le_bytes([v[0], v[1], v[2], v[3]]))
+		};
+		let offset = read_u32(&data, 4).unwrap_or_default() as usize;
+		let count = usize::from(read_u16(&data, offset).unwrap_or_default());
+		let (ifd_entries, next) = match (
+			data.get(offset + 2..offset + 2 + count * 12),
+			read_u32(&data, offset + 2 + count * 12),
+		) {
+			(Some(entries), Some(next)) => (entries.to_vec(), next),
+			_ => return data,
+		};
+		for entry in ifd_entries.chunks_exact(12) {
+			let tag = u16::from_le_bytes([entry[0], entry[1]]);
+			let entry = (
+				tag,
+				u16::from_le_bytes([entry[2], entry[3]]),
+				u32::from_le_bytes([entry[4], entry[5], entry[6], entry[7]]),
+				entry[8..12].to_vec(),
+			);
+			if !entries.iter().any(|v| v.0 == tag) {
+				entries.push(entry);
+			}
+		}
+		let offset = Self::write_ifd(&mut data, entries, next);
+		data[4..8].copy_from_slice(&offset.to_le_bytes());
+		data
+	}
+
+	/**
+	 * Read the fields from the EXIF data.
+	 *
+	 * Fields are parsed from the user comment and the camera
+	 * fields (such as the location) are kept as tags.
+	 *
+	 * @param  data
+	 */
+	fn read_exif(&mut self, data: &[u8]) {
+		let exif =
+			match ExifReader::new().read_from_container(&mut Cursor::new(data)) {
+				Ok(exif) => exif,
+				Err(_) => return,
+			};
+		if let Some(Value::Undefined(comment, _)) = exif
+			.get_field(Tag::UserComment, In::PRIMARY)
+			.map(|field| &field.value)
+		{
+			if let Some(text) = comment.strip_prefix(EXIF_ASCII_PREFIX) {
+				for (key, value) in
+					Self::from_text(&String::from_utf8_lossy(text)).get_fields()
+				{
+					self.set_field(key, value);
+				}
+			}
+		}
+		for (tag, keys) in EXIF_FIELDS {
+			if let Some(field) = exif.get_field(*tag, In::PRIMARY) {
+				if self.get_tag(keys).is_none() {
+					self.tags.push((
+						tag.to_string(),
+						field
+							.display_value()
+							.with_unit(&exif)
+							.to_string()
+							.trim_matches('"')
+							.to_string(),
+					));
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::image::Image;
+	use image::codecs::gif::GifEncoder;
+	use image::codecs::jpeg::JpegEncoder;
+	use image::codecs::png::PngEncoder;
+	use image::codecs::tiff::TiffEncoder;
+	use image::codecs::webp::{WebPEncoder, WebPQuality};
+	use image::{ColorType, DynamicImage, ExtendedColorType, ImageEncoder, Rgba};
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_image_metadata() -> image::ImageResult<()> {
+		let geometry = Geometry::new(5, 10, 4, 3);
+		let mut metadata = ImageMetadata::new(
+			Some(String::from("Terminal \u{2014} menyoki")),
+			Some(geometry),
+			Some(2),
+			Some("sleep 1"),
+		);
+		assert_eq!(Some(String::from("4x3+5+10")), metadata.geometry);
+		assert_eq!(6, metadata.get_fields().len());
+		metadata.set_field("Artist", "orhun");
+		metadata.set_field("artist", "menyoki");
+		metadata.set_field("Copyright", "GPL-3.0");
+		metadata.set_field("MONITOR", "1");
+		metadata.remove_field("copyright");
+		assert_eq!(Some(String::from("1")), metadata.monitor);
+		assert_eq!(
+			vec![(String::from("Artist"), String::from("menyoki"))],
+			metadata.tags
+		);
+		let image = Image::new(vec![Rgba([255, 0, 0, 255]); 12], false, geometry);
+		let rgba = image.get_data(ExtendedColorType::Rgba8);
+		let rgb = image.get_data(ExtendedColorType::Rgb8);
+		let mut outputs = vec![Vec::new(); 6];
+		PngEncoder::new(&mut outputs[0]).write_image(
+			&rgba,
+			4,
+			3,
+			ColorType::Rgba8,
+		)?;
+		JpegEncoder::new(&mut outputs[1]).write_image(
+			&rgb,
+			4,
+			3,
+			ColorType::Rgb8,
+		)?;
+		WebPEncoder::new_with_quality(&mut outputs[2], WebPQuality::lossy(80))
+			.write_image(&rgb, 4, 3, ColorType::Rgb8)?;
+		WebPEncoder::new_with_quality(&mut outputs[3], WebPQuality::lossless())
+			.write_image(&rgba, 4, 3, ColorType::Rgba8)?;
+		GifEncoder::new(&mut outputs[4]).encode(&rgba, 4, 3, ColorType::Rgba8)?;
+		let mut tiff = Cursor::new(Vec::new());
+		TiffEncoder::new(&mut tiff).write_image(&rgba, 4, 3, ColorType::Rgba8)?;
+		outputs[5] = tiff.into_inner();
+		let formats = [
+			FileFormat::Png,
+			FileFormat::Jpg,
+			FileFormat::WebP,
+			FileFormat::WebP,
+			FileFormat::Gif,
+			FileFormat::Tiff,
+		];
+		for (output, format) in outputs.into_iter().zip(formats.iter()) {
+			assert_eq!(None, ImageMetadata::read(&output));
+			let output = metadata.embed(output, format);
+			assert_eq!(Some(metadata.clone()), ImageMetadata::read(&output));
+			if !output.starts_with(PNG_SIGNATURE) && !output.starts_with(b"GIF8") {
+				let exif = ExifReader::new()
+					.read_from_container(&mut Cursor::new(&output))
+					.map_err(|e| {
+						image::ImageError::IoError(std::io::Error::other(e))
+					})?;
+				assert!(exif.get_field(Tag::Artist, In::PRIMARY).is_some());
+			}
+			let decoded = image::load_from_memory(&output)?;
+			assert_eq!((4, 3), (decoded.width(), decoded.height()));
+			assert!(matches!(
+				decoded,
+				DynamicImage::ImageRgb8(_) | DynamicImage::ImageRgba8(_)
+			));
+		}
+		let mut png = Vec::new();
+		PngEncoder::new(&mut png).write_image(&rgba, 4, 3, ColorType::Rgba8)?;
+		let mut metadata = ImageMetadata::default();
+		metadata.set_field("Caf\u{E9}", "\u{2014}");
+		let png = metadata.embed(png, &FileFormat::Png);
+		assert!(png.windows(5).any(|v| v == b"Caf\xE9\0"));
+		assert_eq!(Some(metadata.clone()), ImageMetadata::read(&png));
+		for key in ["Artist", "Caf\u{E9}", "a b"] {
+			assert!(ImageMetadata::is_valid_key(key), "{key}");
+		}
+		for key in ["", " a", "a ", "a  b", "\u{2014}", "a\tb", &"a".repeat(80)] {
+			assert!(!ImageMetadata::is_valid_key(key), "{key}");
+		}
+		for data in [vec![0xFF, 0xD8, 0, 255], b"II*\0".to_vec()] {
+			assert_eq!(data, metadata.embed(data.clone(), &FileFormat::Rgba));
+		}
+		Ok(())
+	}
+}
@@ -1,5 +1,6 @@
 pub mod format;
 pub mod info;
+pub mod metadata;
 pub mod settings;
 
 use crate::file::format::FileFormat;
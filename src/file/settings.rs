@@ -1,3 +1,4 @@
+use crate::anim::AnimFormat;
 use crate::args::matches::ArgMatches;
 use crate::args::parser::ArgParser;
 use crate::edit::settings::EditSettings;
@@ -5,6 +6,7 @@ use crate::file::format::FileFormat;
 use crate::file::info::FileInfo;
 use crate::file::File;
 use crate::image::settings::PnmSettings;
+use bytesize::ByteSize;
 use std::path::PathBuf;
 use std::str::FromStr;
 
@@ -12,6 +14,9 @@ use std::str::FromStr;
 #[derive(Debug)]
 pub struct SaveSettings {
 	pub file: File,
+	pub pipe: Option<(String, FileFormat)>,
+	pub max_size: Option<u64>,
+	pub metadata: bool,
 }
 
 impl SaveSettings {
@@ -19,10 +24,23 @@ impl SaveSettings {
 	 * Create a new SaveSettings object.
 	 *
 	 * @param  file
+	 * @param  pipe (Option)
+	 * @param  max_size (Option)
+	 * @param  metadata
 	 * @return SaveSettings
 	 */
-	pub fn new(file: File) -> Self {
-		Self { file }
+	pub fn new(
+		file: File,
+		pipe: Option<(String, FileFormat)>,
+		max_size: Option<u64>,
+		metadata: bool,
+	) -> Self {
+		Self {
+			file,
+			pipe,
+			max_size,
+			metadata,
+		}
 	}
 
 	/**
@@ -40,24 +58,33 @@ impl SaveSettings {
 		let format =
 			if let Some(args) = ArgParser::from_subcommand(matches, "make").args {
 				args.value_of("format").map_or(FileFormat::Gif, |f| {
-					FileFormat::from_str(f).unwrap_or(FileFormat::Gif)
+					AnimFormat::from_str(f).map_or(FileFormat::Gif, FileFormat::from)
 				})
 			} else {
 				FileFormat::from_args(matches, Some(pnm.subtype))
 			};
+		let format = if edit.convert {
+			format
+		} else {
+			FileFormat::from_str(
+				edit.path
+					.extension()
+					.unwrap_or_default()
+					.to_str()
+					.unwrap_or_default(),
+			)
+			.unwrap_or(format)
+		};
 		Self::from_parser(
 			ArgParser::from_subcommand(matches, "save"),
-			if edit.convert {
-				format
-			} else {
-				FileFormat::from_str(
-					edit.path
-						.extension()
-						.unwrap_or_default()
-						.to_str()
-						.unwrap_or_default(),
-				)
-				.unwrap_or(format)
+			match format {
+				FileFormat::WebP if FileFormat::is_animated_file(&edit.path) => {
+					FileFormat::AnimWebP
+				}
+				FileFormat::Tiff if FileFormat::is_animated_file(&edit.path) => {
+					FileFormat::AnimTiff
+				}
+				_ => format,
 			},
 		)
 	}
@@ -91,13 +118,31 @@ impl SaveSettings {
 						))
 					));
 				}
-				Self::new(File::new(
-					path,
-					file_format,
-					matches.is_present("with-extension"),
-				))
+				Self::new(
+					File::new(
+						path,
+						file_format,
+						matches.is_present("with-extension"),
+					),
+					matches.value_of("pipe").map(|command| {
+						(
+							command.to_string(),
+							match matches.value_of("pipe-format") {
+								Some("png") => FileFormat::Png,
+								Some("qoi") => FileFormat::Qoi,
+								Some("y4m") => FileFormat::Y4m,
+								_ => FileFormat::Rgba,
+							},
+						)
+					}),
+					matches
+						.value_of("max-size")
+						.and_then(|size| ByteSize::from_str(size).ok())
+						.map(|size| size.as_u64()),
+					!matches.is_present("no-metadata"),
+				)
 			}
-			None => Self::new(file_format.into_file()),
+			None => Self::new(file_format.into_file(), None, None, true),
 		}
 	}
 }
@@ -120,19 +165,58 @@ mod tests {
 									.long("file")
 									.takes_value(true),
 							)
-							.arg(Arg::with_name("date").long("date")),
+							.arg(Arg::with_name("date").long("date"))
+							.arg(
+								Arg::with_name("pipe")
+									.long("pipe")
+									.takes_value(true),
+							)
+							.arg(
+								Arg::with_name("pipe-format")
+									.long("pipe-format")
+									.takes_value(true),
+							)
+							.arg(
+								Arg::with_name("max-size")
+									.long("max-size")
+									.takes_value(true),
+							)
+							.arg(Arg::with_name("no-metadata").long("no-metadata")),
 					),
 				),
 			)
 			.get_matches_from(vec![
-				"test", "capture", "jpg", "save", "--file", "test.jpg", "--date",
+				"test",
+				"capture",
+				"jpg",
+				"save",
+				"--file",
+				"test.jpg",
+				"--date",
+				"--pipe",
+				"cat",
+				"--pipe-format",
+				"y4m",
+				"--max-size",
+				"10MB",
+				"--no-metadata",
 			]);
 		let matches = ArgMatches::new(&args);
+		let save_settings = SaveSettings::from_parser(
+			ArgParser::from_subcommand(&matches, "save"),
+			FileFormat::Gif,
+		);
+		assert_eq!(Some(10_000_000), save_settings.max_size);
 		let save_settings = SaveSettings::from_parser(
 			ArgParser::from_subcommand(&matches, "save"),
 			FileFormat::from_args(&matches, None),
 		);
 		assert!(save_settings.file.path.to_str().unwrap().contains("test_"));
 		assert_eq!(FileFormat::Jpg, save_settings.file.format);
+		assert_eq!(
+			Some((String::from("cat"), FileFormat::Y4m)),
+			save_settings.pipe
+		);
+		assert!(!save_settings.metadata);
 	}
 }
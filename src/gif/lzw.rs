@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+
+/* Maximum number of bits that a GIF LZW code can have */
+const MAX_CODE_SIZE: u8 = 12;
+
+/* Maximum number of entries in the LZW dictionary */
+const MAX_CODES: u16 = 1 << MAX_CODE_SIZE;
+
+/* Bit writer for packing variable length codes (LSB first) */
+#[derive(Debug, Default)]
+struct BitWriter {
+	buffer: Vec<u8>,
+	acc: u32,
+	bits: u8,
+}
+
+impl BitWriter {
+	/**
+	 * Write a code with the given size.
+	 *
+	 * @param code
+	 * @param size
+	 */
+	fn write(&mut self, code: u16, size: u8) {
+		self.acc |= u32::from(code) << self.bits;
+		self.bits += size;
+		while self.bits >= 8 {
+			self.buffer.push((self.acc & 0xFF) as u8);
+			self.acc >>= 8;
+			self.bits -= 8;
+		}
+	}
+
+	/**
+	 * Flush the remaining bits and return the buffer.
+	 *
+	 * @return Vector of u8
+	 */
+	fn finish(mut self) -> Vec<u8> {
+		if self.bits > 0 {
+			self.buffer.push((self.acc & 0xFF) as u8);
+		}
+		self.buffer
+	}
+}
+
+/* LZW encoder with optional lossy compression */
+#[derive(Debug)]
+pub struct LzwEncoder<'a> {
+	min_code_size: u8,
+	palette: &'a [u8],
+	transparent: Option<u8>,
+	lossy: u32,
+	codes: HashMap<(u16, u8), u16>,
+	children: Vec<Vec<(u8, u16)>>,
+}
+
+impl<'a> LzwEncoder<'a> {
+	/**
+	 * Create a new LzwEncoder object.
+	 *
+	 * @param  palette
+	 * @param  transparent (Option)
+	 * @param  lossy
+	 * @return LzwEncoder
+	 */
+	pub fn new(palette: &'a [u8], transparent: Option<u8>, lossy: u32) -> Self {
+		let colors = palette.len() / 3;
+		let mut min_code_size = 2;
+		while (1 << min_code_size) < colors && min_code_size < 8 {
+			min_code_size += 1;
+		}
+		Self {
+			min_code_size,
+			palette,
+			transparent,
+			lossy,
+			codes: HashMap::new(),
+			children: Vec::new(),
+		}
+	}
+
+	/**
+	 * Get the clear code of the dictionary.
+	 *
+	 * @return u16
+	 */
+	fn clear_code(&self) -> u16 {
+		1 << self.min_code_size
+	}
+
+	/* Reset the dictionary to its initial state. */
+	fn reset(&mut self) {
+		self.codes.clear();
+		self.children.clear();
+		self.children
+			.resize(usize::from(self.clear_code()) + 2, Vec::new());
+	}
+
+	/**
+	 * Get the distance between two palette colors.
+	 *
+	 * @param  a
+	 * @param  b
+	 * @return u32
+	 */
+	fn get_distance(&self, a: u8, b: u8) -> u32 {
+		if a == b {
+			return 0;
+		} else if self.transparent == Some(a) || self.transparent == Some(b) {
+			return u32::MAX;
+		}
+		let color = |index: u8| {
+			self.palette
+				.get(usize::from(index) * 3..usize::from(index) * 3 + 3)
+				.unwrap_or(&[0, 0, 0])
+				.iter()
+				.map(|v| i32::from(*v))
+				.collect::<Vec<i32>>()
+		};
+		color(a)
+			.iter()
+			.zip(color(b).iter())
+			.map(|(a, b)| ((a - b) * (a - b)) as u32)
+			.sum()
+	}
+
+	/**
+	 * Find the next code for extending the current string.
+	 *
+	 * @param  code
+	 * @param  index
+	 * @return u16 (Option)
+	 */
+	fn find_next(&self, code: u16, index: u8) -> Option<u16> {
+		if let Some(next) = self.codes.get(&(code, index)) {
+			return Some(*next);
+		} else if self.lossy == 0 {
+			return None;
+		}
+		let threshold = self.lossy.saturating_mul(self.lossy);
+		self.children[usize::from(code)]
+			.iter()
+			.map(|(i, next)| (self.get_distance(*i, index), *next))
+			.filter(|(distance, _)| *distance <= threshold)
+			.min_by_key(|(distance, _)| *distance)
+			.map(|(_, next)| next)
+	}
+
+	/**
+	 * Encode the indexed pixels with the minimum code size prepended.
+	 *
+	 * @param  indices
+	 * @return Vector of u8
+	 */
+	pub fn encode(&mut self, indices: &[u8]) -> Vec<u8> {
+		let clear_code = self.clear_code();
+		let end_code = clear_code + 1;
+		let mut writer = BitWriter::default();
+		let mut next_code = end_code + 1;
+		let mut code_size = self.min_code_size + 1;
+		self.reset();
+		writer.write(clear_code, code_size);
+		let mut pixels = indices.iter();
+		if let Some(first) = pixels.next() {
+			let mut code = u16::from(*first);
+			for index in pixels {
+				if let Some(next) = self.find_next(code, *index) {
+					code = next;
+					continue;
+				}
+				writer.write(code, code_size);
+				self.codes.insert((code, *index), next_code);
+				self.children[usize::from(code)].push((*index, next_code));
+				self.children.push(Vec::new());
+				next_code += 1;
+				if next_code > (1 << code_size) && code_size < MAX_CODE_SIZE {
+					code_size += 1;
+				}
+				if next_code == MAX_CODES {
+					writer.write(clear_code, code_size);
+					self.reset();
+					next_code = end_code + 1;
+					code_size = self.min_code_size + 1;
+				}
+				code = u16::from(*index);
+			}
+			writer.write(code, code_size);
+		}
+		writer.write(end_code, code_size);
+		let mut buffer = vec![self.min_code_size];
+		buffer.extend(writer.finish());
+		buffer
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use gif::{DecodeOptions, Encoder, Frame};
+	use pretty_assertions::assert_eq;
+	use std::borrow::Cow;
+	fn decode(width: u16, height: u16, palette: &[u8], data: Vec<u8>) -> Vec<u8> {
+		let mut output = Vec::new();
+		{
+			let mut encoder = Encoder::new(&mut output, width, height, &[]).unwrap();
+			let frame = Frame {
+				width,
+				height,
+				palette: Some(palette.to_vec()),
+				buffer: Cow::Owned(data),
+				..Frame::default()
+			};
+			encoder.write_lzw_pre_encoded_frame(&frame).unwrap();
+		}
+		let mut options = DecodeOptions::new();
+		options.set_color_output(gif::ColorOutput::Indexed);
+		let mut decoder = options.read_info(output.as_slice()).unwrap();
+		decoder.read_next_frame().unwrap().unwrap().buffer.to_vec()
+	}
+	#[test]
+	fn test_lzw_encoder() {
+		let palette = (0..=255).flat_map(|i| [i, i, i]).collect::<Vec<u8>>();
+		let indices = (0..200 * 100)
+			.map(|i| ((i % 200) / 7 + (i / 200) % 3) as u8)
+			.collect::<Vec<u8>>();
+		let data = LzwEncoder::new(&palette, None, 0).encode(&indices);
+		assert_eq!(8, data[0]);
+		assert_eq!(indices, decode(200, 100, &palette, data.clone()));
+		let lossy_data = LzwEncoder::new(&palette, None, 5).encode(&indices);
+		assert!(lossy_data.len() < data.len());
+		let decoded = decode(200, 100, &palette, lossy_data);
+		assert_eq!(indices.len(), decoded.len());
+		assert!(indices
+			.iter()
+			.zip(decoded.iter())
+			.all(|(a, b)| a.abs_diff(*b) <= 5));
+		let data = LzwEncoder::new(&palette[..6], None, 0).encode(&[0, 1, 1, 0]);
+		assert_eq!(2, data[0]);
+		assert_eq!(vec![0, 1, 1, 0], decode(2, 2, &palette[..6], data));
+	}
+}
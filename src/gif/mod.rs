@@ -1,14 +1,17 @@
 pub mod encoder;
+pub mod lzw;
 #[cfg(feature = "ski")]
 pub mod ski;
 
 use crate::anim::settings::AnimSettings;
 use crate::app::AppResult;
 use crate::gif::encoder::{Encoder, EncoderConfig};
+use crate::gif::lzw::LzwEncoder;
 use crate::image::Image;
 use crate::util::state::InputState;
 use gif::{Encoder as BaseEncoder, Frame, Repeat};
 use image::ExtendedColorType;
+use std::borrow::Cow;
 use std::io::{self, Write};
 
 /* GIF encoder and settings */
@@ -85,7 +88,14 @@ impl<'a, Output: Write> Encoder<'a, Output> for GifEncoder<'a, Output> {
 				speed,
 			);
 			frame.delay = (1e2 / self.fps as f32) as u16;
-			frame.make_lzw_pre_encoded();
+			frame.buffer = Cow::Owned(
+				LzwEncoder::new(
+					frame.palette.as_deref().unwrap_or_default(),
+					frame.transparent,
+					self.settings.lossy,
+				)
+				.encode(&frame.buffer),
+			);
 			self.encoder.write_lzw_pre_encoded_frame(&frame)?;
 		}
 		info!("\n");
@@ -0,0 +1,89 @@
+use crate::image::settings::AvifSettings;
+use image::error::{
+	EncodingError, ImageFormatHint, UnsupportedError, UnsupportedErrorKind,
+};
+use image::{ColorType, ImageEncoder, ImageError, ImageFormat, ImageResult};
+use ravif::{Encoder, Img, RGBA8};
+use std::io::Write;
+
+/* AVIF encoder and settings */
+pub struct AvifEncoder<Output: Write> {
+	output: Output,
+	settings: AvifSettings,
+}
+
+impl<Output: Write> AvifEncoder<Output> {
+	/**
+	 * Create a new AvifEncoder object.
+	 *
+	 * @param  output
+	 * @param  settings
+	 * @return AvifEncoder
+	 */
+	pub fn new(output: Output, settings: AvifSettings) -> Self {
+		Self { output, settings }
+	}
+}
+
+/* Image encoder implementation for AvifEncoder */
+impl<Output: Write> ImageEncoder for AvifEncoder<Output> {
+	fn write_image(
+		mut self,
+		buf: &[u8],
+		width: u32,
+		height: u32,
+		color_type: ColorType,
+	) -> ImageResult<()> {
+		if color_type != ColorType::Rgba8 {
+			return Err(ImageError::Unsupported(
+				UnsupportedError::from_format_and_kind(
+					ImageFormatHint::Exact(ImageFormat::Avif),
+					UnsupportedErrorKind::Color(color_type.into()),
+				),
+			));
+		}
+		let pixels = buf
+			.chunks_exact(4)
+			.map(|pixel| RGBA8::new(pixel[0], pixel[1], pixel[2], pixel[3]))
+			.collect::<Vec<RGBA8>>();
+		let quality = f32::from(self.settings.quality);
+		let image = Encoder::new()
+			.with_quality(quality)
+			.with_alpha_quality(quality)
+			.with_speed(self.settings.speed)
+			.encode_rgba(Img::new(
+				pixels.as_slice(),
+				width as usize,
+				height as usize,
+			))
+			.map_err(|e| {
+				ImageError::Encoding(EncodingError::new(
+					ImageFormatHint::Exact(ImageFormat::Avif),
+					e,
+				))
+			})?;
+		self.output.write_all(&image.avif_file)?;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_avif_encoder() -> ImageResult<()> {
+		let mut output = Vec::new();
+		AvifEncoder::new(&mut output, AvifSettings::new(60, 10)).write_image(
+			&[255, 0, 0, 255].repeat(16 * 16),
+			16,
+			16,
+			ColorType::Rgba8,
+		)?;
+		assert_eq!(b"ftypavif", &output[4..12]);
+		assert!(AvifEncoder::new(Vec::new(), AvifSettings::default())
+			.write_image(&[0; 3], 1, 1, ColorType::Rgb8)
+			.is_err());
+		Ok(())
+	}
+}
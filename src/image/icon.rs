@@ -0,0 +1,249 @@
+use crate::file::format::FileFormat;
+use crate::image::settings::IconSettings;
+use image::codecs::ico::{IcoEncoder, IcoFrame};
+use image::codecs::png::PngEncoder;
+use image::error::{
+	EncodingError, ImageFormatHint, UnsupportedError, UnsupportedErrorKind,
+};
+use image::imageops::{self, FilterType};
+use image::RgbaImage;
+use image::{ColorType, ImageEncoder, ImageError, ImageFormat, ImageResult};
+use std::io::Write;
+
+/* Magic bytes of the ICNS header */
+const ICNS_MAGIC: &[u8; 4] = b"icns";
+
+/* Sizes and PNG element types of the ICNS format */
+const ICNS_TYPES: [(u32, &[u8; 4]); 7] = [
+	(16, b"icp4"),
+	(32, b"icp5"),
+	(64, b"icp6"),
+	(128, b"ic07"),
+	(256, b"ic08"),
+	(512, b"ic09"),
+	(1024, b"ic10"),
+];
+
+/* Maximum size of an ICO image */
+const ICO_MAX_SIZE: u32 = 256;
+
+/* Multi-size icon (ICO/ICNS) encoder */
+pub struct IconEncoder<'a, Output: Write> {
+	output: Output,
+	format: &'a FileFormat,
+	settings: &'a IconSettings,
+	filter: FilterType,
+}
+
+impl<'a, Output: Write> IconEncoder<'a, Output> {
+	/**
+	 * Create a new IconEncoder object.
+	 *
+	 * @param  output
+	 * @param  format
+	 * @param  settings
+	 * @param  filter
+	 * @return IconEncoder
+	 */
+	pub fn new(
+		output: Output,
+		format: &'a FileFormat,
+		settings: &'a IconSettings,
+		filter: FilterType,
+	) -> Self {
+		Self {
+			output,
+			format,
+			settings,
+			filter,
+		}
+	}
+
+	/**
+	 * Get the icon sizes that are supported by the format.
+	 *
+	 * @return Vector of u32
+	 */
+	fn get_sizes(&self) -> Vec<u32> {
+		self.settings
+			.sizes
+			.iter()
+			.copied()
+			.filter(|size| {
+				let supported = match self.format {
+					FileFormat::Icns => {
+						ICNS_TYPES.iter().any(|(icns_size, _)| icns_size == size)
+					}
+					_ => (1..=ICO_MAX_SIZE).contains(size),
+				};
+				if !supported {
+					warn!("Icon size is not supported by {}: {}", self.format, size);
+				}
+				supported
+			})
+			.collect()
+	}
+
+	/**
+	 * Resample the image to fit into a square icon of the given size.
+	 *
+	 * @param  image
+	 * @param  size
+	 * @return RgbaImage
+	 */
+	fn resample(&self, image: &RgbaImage, size: u32) -> RgbaImage {
+		let ratio = f64::from(size) / f64::from(image.width().max(image.height()));
+		let (width, height) = (
+			((f64::from(image.width()) * ratio).round() as u32).clamp(1, size),
+			((f64::from(image.height()) * ratio).round() as u32).clamp(1, size),
+		);
+		let resized = imageops::resize(image, width, height, self.filter);
+		let mut icon = RgbaImage::new(size, size);
+		imageops::overlay(
+			&mut icon,
+			&resized,
+			i64::from((size - width) / 2),
+			i64::from((size - height) / 2),
+		);
+		icon
+	}
+
+	/**
+	 * Encode the image as PNG.
+	 *
+	 * @param  image
+	 * @return Vector of u8 (Result)
+	 */
+	fn encode_png(image: &RgbaImage) -> ImageResult<Vec<u8>> {
+		let mut data = Vec::new();
+		PngEncoder::new(&mut data).write_image(
+			image.as_raw(),
+			image.width(),
+			image.height(),
+			ColorType::Rgba8,
+		)?;
+		Ok(data)
+	}
+
+	/**
+	 * Write the icons as ICNS.
+	 *
+	 * @param  icons
+	 * @return Result
+	 */
+	fn write_icns(&mut self, icons: &[RgbaImage]) -> ImageResult<()> {
+		let mut elements = Vec::new();
+		for icon in icons {
+			if let Some((_, icns_type)) =
+				ICNS_TYPES.iter().find(|(size, _)| *size == icon.width())
+			{
+				let data = Self::encode_png(icon)?;
+				elements.extend_from_slice(*icns_type);
+				elements.extend_from_slice(&(data.len() as u32 + 8).to_be_bytes());
+				elements.extend(data);
+			}
+		}
+		self.output.write_all(ICNS_MAGIC)?;
+		self.output
+			.write_all(&(elements.len() as u32 + 8).to_be_bytes())?;
+		self.output.write_all(&elements)?;
+		Ok(())
+	}
+}
+
+/* Image encoder implementation for IconEncoder */
+impl<'a, Output: Write> ImageEncoder for IconEncoder<'a, Output> {
+	fn write_image(
+		mut self,
+		buf: &[u8],
+		width: u32,
+		height: u32,
+		color_type: ColorType,
+	) -> ImageResult<()> {
+		let image = match color_type {
+			ColorType::Rgba8 => RgbaImage::from_raw(width, height, buf.to_vec()),
+			_ => None,
+		}
+		.ok_or_else(|| {
+			ImageError::Unsupported(UnsupportedError::from_format_and_kind(
+				ImageFormatHint::Exact(ImageFormat::Ico),
+				UnsupportedErrorKind::Color(color_type.into()),
+			))
+		})?;
+		let icons = self
+			.get_sizes()
+			.into_iter()
+			.map(|size| self.resample(&image, size))
+			.collect::<Vec<RgbaImage>>();
+		if icons.is_empty() {
+			return Err(ImageError::Encoding(EncodingError::new(
+				ImageFormatHint::Exact(ImageFormat::Ico),
+				"No valid icon sizes given",
+			)));
+		}
+		match self.format {
+			FileFormat::Icns => self.write_icns(&icons),
+			_ => IcoEncoder::new(self.output).encode_images(
+				&icons
+					.iter()
+					.map(|icon| {
+						IcoFrame::as_png(
+							icon.as_raw(),
+							icon.width(),
+							icon.height(),
+							ColorType::Rgba8,
+						)
+					})
+					.collect::<ImageResult<Vec<IcoFrame<'_>>>>()?,
+			),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use image::codecs::ico::IcoDecoder;
+	use image::ImageDecoder;
+	use pretty_assertions::assert_eq;
+	use std::io::Cursor;
+	fn encode(format: &FileFormat, sizes: Vec<u32>) -> ImageResult<Vec<u8>> {
+		let mut output = Vec::new();
+		IconEncoder::new(
+			&mut output,
+			format,
+			&IconSettings::new(sizes),
+			FilterType::Triangle,
+		)
+		.write_image(
+			&[255, 0, 0, 255].repeat(40 * 20),
+			40,
+			20,
+			ColorType::Rgba8,
+		)?;
+		Ok(output)
+	}
+	#[test]
+	fn test_icon_encoder() -> ImageResult<()> {
+		let output = encode(&FileFormat::Ico, vec![16, 32, 48, 300])?;
+		assert_eq!([0, 0, 1, 0, 3, 0], output[..6]);
+		assert_eq!(
+			[16, 16, 32, 32, 48, 48],
+			[output[6], output[7], output[22], output[23], output[38], output[39]]
+		);
+		let decoder = IcoDecoder::new(Cursor::new(output.as_slice()))?;
+		assert_eq!((48, 48), decoder.dimensions());
+		let output = encode(&FileFormat::Icns, vec![16, 20, 128])?;
+		assert_eq!(ICNS_MAGIC, &output[..4]);
+		assert_eq!(
+			output.len() as u32,
+			u32::from_be_bytes([output[4], output[5], output[6], output[7]])
+		);
+		assert_eq!(b"icp4", &output[8..12]);
+		let length =
+			u32::from_be_bytes([output[12], output[13], output[14], output[15]]);
+		assert_eq!(b"ic07", &output[8 + length as usize..12 + length as usize]);
+		assert!(encode(&FileFormat::Icns, vec![20]).is_err());
+		Ok(())
+	}
+}
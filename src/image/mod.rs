@@ -1,5 +1,9 @@
+pub mod avif;
 pub mod geometry;
+pub mod icon;
 pub mod padding;
+pub mod png;
+pub mod qoi;
 pub mod settings;
 use std::fmt;
 
@@ -0,0 +1,324 @@
+use crate::image::settings::PngSettings;
+use color_quant::NeuQuant;
+use image::error::{
+	EncodingError, ImageFormatHint, UnsupportedError, UnsupportedErrorKind,
+};
+use image::{ColorType, ImageEncoder, ImageError, ImageFormat, ImageResult};
+use png::{AdaptiveFilterType, BitDepth, Compression, Encoder, FilterType};
+use std::collections::HashMap;
+use std::io::Write;
+
+/* Sampling factor of the color quantizer (1-30, lower is better) */
+const QUANTIZER_SAMPLING: i32 = 10;
+
+/* Pixel data and color information of a PNG image */
+#[derive(Debug)]
+struct PngData {
+	color: png::ColorType,
+	depth: BitDepth,
+	palette: Option<(Vec<u8>, Vec<u8>)>,
+	data: Vec<u8>,
+}
+
+/* PNG encoder with palette and optimization support */
+pub struct PngWriter<Output: Write> {
+	output: Output,
+	settings: PngSettings,
+}
+
+impl<Output: Write> PngWriter<Output> {
+	/**
+	 * Create a new PngWriter object.
+	 *
+	 * @param  output
+	 * @param  settings
+	 * @return PngWriter
+	 */
+	pub fn new(output: Output, settings: PngSettings) -> Self {
+		Self { output, settings }
+	}
+
+	/**
+	 * Get the palette and color indices of the pixels.
+	 *
+	 * The exact colors are used if they fit into the palette,
+	 * otherwise the pixels are quantized.
+	 *
+	 * @param  buf
+	 * @param  colors
+	 * @return Tuple of palette and indices
+	 */
+	fn quantize(buf: &[u8], colors: usize) -> (Vec<[u8; 4]>, Vec<u8>) {
+		let mut unique = HashMap::<[u8; 4], u8>::new();
+		for rgba in buf.chunks_exact(4) {
+			let color = [rgba[0], rgba[1], rgba[2], rgba[3]];
+			if unique.len() > colors {
+				break;
+			}
+			unique.entry(color).or_default();
+		}
+		let (mut palette, indices) = if unique.len() <= colors {
+			let palette = unique.keys().copied().collect::<Vec<[u8; 4]>>();
+			for (i, color) in palette.iter().enumerate() {
+				unique.insert(*color, i as u8);
+			}
+			let indices = buf
+				.chunks_exact(4)
+				.map(|rgba| unique[&[rgba[0], rgba[1], rgba[2], rgba[3]]])
+				.collect::<Vec<u8>>();
+			(palette, indices)
+		} else {
+			let quantizer = NeuQuant::new(QUANTIZER_SAMPLING, colors, buf);
+			let palette = quantizer
+				.color_map_rgba()
+				.chunks_exact(4)
+				.map(|rgba| [rgba[0], rgba[1], rgba[2], rgba[3]])
+				.collect::<Vec<[u8; 4]>>();
+			let indices = buf
+				.chunks_exact(4)
+				.map(|rgba| quantizer.index_of(rgba) as u8)
+				.collect::<Vec<u8>>();
+			(palette, indices)
+		};
+		let mut order = (0..palette.len()).collect::<Vec<usize>>();
+		order.sort_by_key(|i| {
+			let rgba = palette[*i];
+			(rgba[3], rgba[0], rgba[1], rgba[2])
+		});
+		let mut positions = vec![0; palette.len()];
+		for (position, i) in order.iter().enumerate() {
+			positions[*i] = position as u8;
+		}
+		palette = order.iter().map(|i| palette[*i]).collect();
+		(
+			palette,
+			indices.iter().map(|i| positions[usize::from(*i)]).collect(),
+		)
+	}
+
+	/**
+	 * Convert the RGBA pixels to indexed colors.
+	 *
+	 * @param  buf
+	 * @param  width
+	 * @param  colors
+	 * @return PngData
+	 */
+	fn get_indexed_data(buf: &[u8], width: u32, colors: usize) -> PngData {
+		let (palette, indices) = Self::quantize(buf, colors);
+		let (depth, bits) = match palette.len() {
+			0..=2 => (BitDepth::One, 1),
+			3..=4 => (BitDepth::Two, 2),
+			5..=16 => (BitDepth::Four, 4),
+			_ => (BitDepth::Eight, 8),
+		};
+		let width = width as usize;
+		let data = indices
+			.chunks(width.max(1))
+			.flat_map(|row| {
+				let mut packed = vec![0; (width * bits).div_ceil(8)];
+				for (x, index) in row.iter().enumerate() {
+					packed[x * bits / 8] |= index << (8 - bits - (x * bits) % 8);
+				}
+				packed
+			})
+			.collect();
+		let mut trns = palette.iter().map(|rgba| rgba[3]).collect::<Vec<u8>>();
+		while trns.last() == Some(&0xFF) {
+			trns.pop();
+		}
+		PngData {
+			color: png::ColorType::Indexed,
+			depth,
+			palette: Some((
+				palette
+					.iter()
+					.flat_map(|rgba| [rgba[0], rgba[1], rgba[2]])
+					.collect(),
+				trns,
+			)),
+			data,
+		}
+	}
+
+	/**
+	 * Encode the image data with the given options.
+	 *
+	 * @param  image
+	 * @param  size
+	 * @param  compression
+	 * @param  filter
+	 * @return Vector of u8 (Result)
+	 */
+	fn encode(
+		image: &PngData,
+		size: (u32, u32),
+		compression: Compression,
+		filter: (FilterType, AdaptiveFilterType),
+	) -> ImageResult<Vec<u8>> {
+		let mut output = Vec::new();
+		let mut encoder = Encoder::new(&mut output, size.0, size.1);
+		encoder.set_color(image.color);
+		encoder.set_depth(image.depth);
+		encoder.set_compression(compression);
+		encoder.set_filter(filter.0);
+		encoder.set_adaptive_filter(filter.1);
+		if let Some((palette, trns)) = &image.palette {
+			encoder.set_palette(palette.as_slice());
+			if !trns.is_empty() {
+				encoder.set_trns(trns.as_slice());
+			}
+		}
+		encoder
+			.write_header()
+			.and_then(|mut writer| writer.write_image_data(&image.data))
+			.map_err(|e| {
+				ImageError::Encoding(EncodingError::new(
+					ImageFormatHint::Exact(ImageFormat::Png),
+					e,
+				))
+			})?;
+		Ok(output)
+	}
+
+	/**
+	 * Encode the image with every filter and compression level.
+	 *
+	 * @param  image
+	 * @param  size
+	 * @return Vector of u8 (Result)
+	 */
+	fn encode_optimized(image: &PngData, size: (u32, u32)) -> ImageResult<Vec<u8>> {
+		let filters = [
+			FilterType::NoFilter,
+			FilterType::Sub,
+			FilterType::Up,
+			FilterType::Avg,
+			FilterType::Paeth,
+		]
+		.into_iter()
+		.map(|filter| (filter, AdaptiveFilterType::NonAdaptive))
+		.chain([(FilterType::Sub, AdaptiveFilterType::Adaptive)]);
+		let mut smallest = Vec::new();
+		for filter in filters {
+			for compression in
+				[Compression::Fast, Compression::Default, Compression::Best]
+			{
+				let data = Self::encode(image, size, compression, filter)?;
+				debug!(
+					"{:?} compression, {:?} filter: {} bytes",
+					compression,
+					filter,
+					data.len()
+				);
+				if smallest.is_empty() || data.len() < smallest.len() {
+					smallest = data;
+				}
+			}
+		}
+		Ok(smallest)
+	}
+}
+
+/* Image encoder implementation for PngWriter */
+impl<Output: Write> ImageEncoder for PngWriter<Output> {
+	fn write_image(
+		mut self,
+		buf: &[u8],
+		width: u32,
+		height: u32,
+		color_type: ColorType,
+	) -> ImageResult<()> {
+		if color_type != ColorType::Rgba8 {
+			return Err(ImageError::Unsupported(
+				UnsupportedError::from_format_and_kind(
+					ImageFormatHint::Exact(ImageFormat::Png),
+					UnsupportedErrorKind::Color(color_type.into()),
+				),
+			));
+		}
+		let image = match self.settings.colors {
+			Some(colors) => Self::get_indexed_data(buf, width, usize::from(colors)),
+			None => PngData {
+				color: png::ColorType::Rgba,
+				depth: BitDepth::Eight,
+				palette: None,
+				data: buf.to_vec(),
+			},
+		};
+		let data = if self.settings.optimize {
+			Self::encode_optimized(&image, (width, height))?
+		} else {
+			Self::encode(
+				&image,
+				(width, height),
+				self.settings.get_compression(),
+				self.settings.get_filter(),
+			)?
+		};
+		self.output.write_all(&data)?;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use image::codecs::png::PngDecoder;
+	use image::{DynamicImage, ImageDecoder};
+	use pretty_assertions::assert_eq;
+	fn encode(
+		buf: &[u8],
+		width: u32,
+		settings: PngSettings,
+	) -> ImageResult<Vec<u8>> {
+		let mut output = Vec::new();
+		PngWriter::new(&mut output, settings).write_image(
+			buf,
+			width,
+			buf.len() as u32 / 4 / width,
+			ColorType::Rgba8,
+		)?;
+		Ok(output)
+	}
+	#[test]
+	fn test_png_writer() -> ImageResult<()> {
+		let buf = (0..30 * 20)
+			.flat_map(|i| match i % 3 {
+				0 => [255, 0, 0, 255],
+				1 => [0, 0, 255, 128],
+				_ => [0, 0, 0, 0],
+			})
+			.collect::<Vec<u8>>();
+		for (colors, optimize) in [(None, true), (Some(4), false), (Some(3), true)] {
+			let settings = PngSettings {
+				colors,
+				optimize,
+				..PngSettings::default()
+			};
+			let output = encode(&buf, 30, settings)?;
+			let image =
+				DynamicImage::from_decoder(PngDecoder::new(output.as_slice())?)?;
+			assert_eq!(buf, image.to_rgba8().into_raw());
+			if colors.is_some() {
+				assert_eq!([2, 3], output[24..26]);
+			}
+		}
+		let buf = (0..64 * 64)
+			.flat_map(|i| [(i % 64 * 4) as u8, (i / 64 * 4) as u8, 128, 255])
+			.collect::<Vec<u8>>();
+		let settings = PngSettings {
+			colors: Some(16),
+			..PngSettings::default()
+		};
+		let output = encode(&buf, 64, settings)?;
+		let decoder = PngDecoder::new(output.as_slice())?;
+		assert_eq!((64, 64), decoder.dimensions());
+		let image = DynamicImage::from_decoder(decoder)?.to_rgba8();
+		let mut colors = image.pixels().map(|p| p.0).collect::<Vec<[u8; 4]>>();
+		colors.sort_unstable();
+		colors.dedup();
+		assert!(colors.len() <= 16);
+		Ok(())
+	}
+}
@@ -0,0 +1,374 @@
+use image::error::{
+	DecodingError, EncodingError, ImageFormatHint, UnsupportedError,
+	UnsupportedErrorKind,
+};
+use image::io::Reader;
+use image::{ColorType, DynamicImage, ImageEncoder, ImageError, ImageResult};
+use image::{Rgba, RgbaImage};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+/* Magic bytes of the QOI header */
+const QOI_MAGIC: &[u8; 4] = b"qoif";
+
+/* Size of the QOI header */
+const QOI_HEADER_SIZE: usize = 14;
+
+/* Maximum number of pixels that a single byte can encode */
+const QOI_MAX_RUN: u64 = 62;
+
+/* Bytes that mark the end of the QOI stream */
+const QOI_END_MARKER: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 1];
+
+/* Chunk tags */
+const QOI_OP_INDEX: u8 = 0x00;
+const QOI_OP_DIFF: u8 = 0x40;
+const QOI_OP_LUMA: u8 = 0x80;
+const QOI_OP_RUN: u8 = 0xC0;
+const QOI_OP_RGB: u8 = 0xFE;
+const QOI_OP_RGBA: u8 = 0xFF;
+
+/**
+ * Get the position of the pixel in the array of seen pixels.
+ *
+ * @param  pixel
+ * @return usize
+ */
+fn get_index_position(pixel: [u8; 4]) -> usize {
+	(usize::from(pixel[0]) * 3
+		+ usize::from(pixel[1]) * 5
+		+ usize::from(pixel[2]) * 7
+		+ usize::from(pixel[3]) * 11)
+		% 64
+}
+
+/**
+ * Read an image from the given path, including QOI files.
+ *
+ * @param  path
+ * @return DynamicImage (Result)
+ */
+pub fn read_image(path: &Path) -> ImageResult<DynamicImage> {
+	if QoiDecoder::is_qoi_file(path) {
+		QoiDecoder::new(File::open(path)?)?.decode()
+	} else {
+		Reader::open(path)?.with_guessed_format()?.decode()
+	}
+}
+
+/* QOI (Quite OK Image) encoder */
+pub struct QoiEncoder<Output: Write> {
+	output: Output,
+}
+
+impl<Output: Write> QoiEncoder<Output> {
+	/**
+	 * Create a new QoiEncoder object.
+	 *
+	 * @param  output
+	 * @return QoiEncoder
+	 */
+	pub fn new(output: Output) -> Self {
+		Self { output }
+	}
+
+	/**
+	 * Encode the pixels as QOI chunks.
+	 *
+	 * @param  buf
+	 * @param  channels
+	 * @return Vector of u8
+	 */
+	fn encode(buf: &[u8], channels: usize) -> Vec<u8> {
+		let mut data = Vec::with_capacity(buf.len() / 2);
+		let mut index = [[0; 4]; 64];
+		let mut previous = [0, 0, 0, 255];
+		let mut run = 0;
+		for pixel in buf.chunks_exact(channels) {
+			let pixel = [
+				pixel[0],
+				pixel[1],
+				pixel[2],
+				if channels == 4 { pixel[3] } else { 255 },
+			];
+			if pixel == previous {
+				run += 1;
+				if run == 62 {
+					data.push(QOI_OP_RUN | (run - 1));
+					run = 0;
+				}
+				continue;
+			}
+			if run > 0 {
+				data.push(QOI_OP_RUN | (run - 1));
+				run = 0;
+			}
+			let position = get_index_position(pixel);
+			if index[position] == pixel {
+				data.push(QOI_OP_INDEX | position as u8);
+			} else if pixel[3] == previous[3] {
+				index[position] = pixel;
+				let dr = pixel[0].wrapping_sub(previous[0]) as i8;
+				let dg = pixel[1].wrapping_sub(previous[1]) as i8;
+				let db = pixel[2].wrapping_sub(previous[2]) as i8;
+				let (dr_dg, db_dg) = (dr.wrapping_sub(dg), db.wrapping_sub(dg));
+				if [dr, dg, db].iter().all(|v| (-2..=1).contains(v)) {
+					data.push(
+						QOI_OP_DIFF
+							| ((dr + 2) as u8) << 4
+							| ((dg + 2) as u8) << 2
+							| (db + 2) as u8,
+					);
+				} else if (-32..=31).contains(&dg)
+					&& (-8..=7).contains(&dr_dg)
+					&& (-8..=7).contains(&db_dg)
+				{
+					data.push(QOI_OP_LUMA | (dg + 32) as u8);
+					data.push(((dr_dg + 8) as u8) << 4 | (db_dg + 8) as u8);
+				} else {
+					data.extend([QOI_OP_RGB, pixel[0], pixel[1], pixel[2]]);
+				}
+			} else {
+				index[position] = pixel;
+				data.push(QOI_OP_RGBA);
+				data.extend(pixel);
+			}
+			previous = pixel;
+		}
+		if run > 0 {
+			data.push(QOI_OP_RUN | (run - 1));
+		}
+		data
+	}
+}
+
+/* Image encoder implementation for QoiEncoder */
+impl<Output: Write> ImageEncoder for QoiEncoder<Output> {
+	fn write_image(
+		mut self,
+		buf: &[u8],
+		width: u32,
+		height: u32,
+		color_type: ColorType,
+	) -> ImageResult<()> {
+		let channels = match color_type {
+			ColorType::Rgb8 => 3,
+			ColorType::Rgba8 => 4,
+			_ => {
+				return Err(ImageError::Unsupported(
+					UnsupportedError::from_format_and_kind(
+						ImageFormatHint::Name(String::from("QOI")),
+						UnsupportedErrorKind::Color(color_type.into()),
+					),
+				))
+			}
+		};
+		if buf.len() != width as usize * height as usize * channels {
+			return Err(ImageError::Encoding(EncodingError::new(
+				ImageFormatHint::Name(String::from("QOI")),
+				"Invalid buffer length",
+			)));
+		}
+		self.output.write_all(QOI_MAGIC)?;
+		self.output.write_all(&width.to_be_bytes())?;
+		self.output.write_all(&height.to_be_bytes())?;
+		self.output.write_all(&[channels as u8, 0])?;
+		self.output.write_all(&Self::encode(buf, channels))?;
+		self.output.write_all(&QOI_END_MARKER)?;
+		Ok(())
+	}
+}
+
+/* QOI (Quite OK Image) decoder */
+#[derive(Debug)]
+pub struct QoiDecoder {
+	data: Vec<u8>,
+	width: u32,
+	height: u32,
+	channels: u8,
+}
+
+impl QoiDecoder {
+	/**
+	 * Create a new QoiDecoder object.
+	 *
+	 * @param  input
+	 * @return QoiDecoder (Result)
+	 */
+	pub fn new<Input: Read>(mut input: Input) -> ImageResult<Self> {
+		let mut data = Vec::new();
+		input.read_to_end(&mut data)?;
+		if data.len() < QOI_HEADER_SIZE || !data.starts_with(QOI_MAGIC) {
+			return Err(Self::get_error("Invalid QOI header"));
+		}
+		let width = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
+		let height = u32::from_be_bytes([data[8], data[9], data[10], data[11]]);
+		let channels = data[12];
+		if width == 0 || height == 0 || !(3..=4).contains(&channels) {
+			return Err(Self::get_error("Invalid image properties"));
+		}
+		let pixels = u64::from(width) * u64::from(height);
+		if usize::try_from(pixels * 4).is_err()
+			|| pixels > (data.len() - QOI_HEADER_SIZE) as u64 * QOI_MAX_RUN
+		{
+			return Err(Self::get_error("Image size does not match the data"));
+		}
+		Ok(Self {
+			data,
+			width,
+			height,
+			channels,
+		})
+	}
+
+	/**
+	 * Check if the file at the given path is a QOI image.
+	 *
+	 * @param  path
+	 * @return bool
+	 */
+	pub fn is_qoi_file(path: &Path) -> bool {
+		let mut magic = [0; 4];
+		File::open(path)
+			.and_then(|mut file| file.read_exact(&mut magic))
+			.is_ok() && &magic == QOI_MAGIC
+	}
+
+	/**
+	 * Create a decoding error with the given message.
+	 *
+	 * @param  message
+	 * @return ImageError
+	 */
+	fn get_error(message: &str) -> ImageError {
+		ImageError::Decoding(DecodingError::new(
+			ImageFormatHint::Name(String::from("QOI")),
+			message,
+		))
+	}
+
+	/**
+	 * Decode the QOI chunks into an image.
+	 *
+	 * @return DynamicImage (Result)
+	 */
+	pub fn decode(self) -> ImageResult<DynamicImage> {
+		let mut bytes = self.data[QOI_HEADER_SIZE..].iter().copied();
+		let mut next = || {
+			bytes
+				.next()
+				.ok_or_else(|| Self::get_error("Unexpected end of the stream"))
+		};
+		let mut image = RgbaImage::new(self.width, self.height);
+		let mut index = [[0; 4]; 64];
+		let mut pixel = [0, 0, 0, 255];
+		let mut run = 0;
+		for output in image.pixels_mut() {
+			if run > 0 {
+				run -= 1;
+			} else {
+				match next()? {
+					QOI_OP_RGB => {
+						pixel = [next()?, next()?, next()?, pixel[3]];
+					}
+					QOI_OP_RGBA => {
+						pixel = [next()?, next()?, next()?, next()?];
+					}
+					tag if tag & 0xC0 == QOI_OP_INDEX => {
+						pixel = index[usize::from(tag)];
+					}
+					tag if tag & 0xC0 == QOI_OP_DIFF => {
+						pixel[0] =
+							pixel[0].wrapping_add((tag >> 4) & 0x03).wrapping_sub(2);
+						pixel[1] =
+							pixel[1].wrapping_add((tag >> 2) & 0x03).wrapping_sub(2);
+						pixel[2] = pixel[2].wrapping_add(tag & 0x03).wrapping_sub(2);
+					}
+					tag if tag & 0xC0 == QOI_OP_LUMA => {
+						let diff = next()?;
+						let dg = (tag & 0x3F).wrapping_sub(32);
+						pixel[0] = pixel[0]
+							.wrapping_add(dg)
+							.wrapping_add(diff >> 4)
+							.wrapping_sub(8);
+						pixel[1] = pixel[1].wrapping_add(dg);
+						pixel[2] = pixel[2]
+							.wrapping_add(dg)
+							.wrapping_add(diff & 0x0F)
+							.wrapping_sub(8);
+					}
+					tag => run = tag & 0x3F,
+				}
+				index[get_index_position(pixel)] = pixel;
+			}
+			*output = Rgba(pixel);
+		}
+		let image = DynamicImage::ImageRgba8(image);
+		Ok(if self.channels == 3 {
+			DynamicImage::ImageRgb8(image.to_rgb8())
+		} else {
+			image
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::image::geometry::Geometry;
+	use crate::image::Image;
+	use image::{ExtendedColorType, GenericImageView};
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_qoi() -> ImageResult<()> {
+		let geometry = Geometry::new(0, 0, 40, 30);
+		let data = (0..geometry.width * geometry.height)
+			.map(|i| match i % 7 {
+				0..=2 => Rgba([10, 20, 30, 255]),
+				3 => Rgba([11, 19, 31, 255]),
+				4 => Rgba([(i * 3) as u8, (i * 2) as u8, 5, 255]),
+				5 => Rgba([200, (i / 5) as u8, 100, (i % 256) as u8]),
+				_ => Rgba([i as u8, (i * 7) as u8, (i * 13) as u8, 128]),
+			})
+			.collect();
+		let image = Image::new(data, true, geometry);
+		for (color_type, extended_color_type) in [
+			(ColorType::Rgba8, ExtendedColorType::Rgba8),
+			(ColorType::Rgb8, ExtendedColorType::Rgb8),
+		] {
+			let pixels = image.get_data(extended_color_type);
+			let mut output = Vec::new();
+			QoiEncoder::new(&mut output).write_image(
+				&pixels,
+				geometry.width,
+				geometry.height,
+				color_type,
+			)?;
+			assert_eq!(QOI_MAGIC, &output[..4]);
+			assert_eq!(QOI_END_MARKER, output[output.len() - 8..]);
+			let decoded = QoiDecoder::new(output.as_slice())?.decode()?;
+			assert_eq!((geometry.width, geometry.height), decoded.dimensions());
+			assert_eq!(color_type, decoded.color());
+			assert_eq!(pixels, decoded.as_bytes());
+		}
+		let mut output = Vec::new();
+		QoiEncoder::new(&mut output).write_image(
+			&[0, 0, 0, 255].repeat(100),
+			10,
+			10,
+			ColorType::Rgba8,
+		)?;
+		assert_eq!(QOI_HEADER_SIZE + 2 + 8, output.len());
+		output.truncate(QOI_HEADER_SIZE + 1);
+		assert!(QoiDecoder::new(output.as_slice())
+			.and_then(|decoder| decoder.decode())
+			.is_err());
+		let mut header = output[..QOI_HEADER_SIZE].to_vec();
+		header[4..12].copy_from_slice(&[0, 0, 255, 255, 0, 0, 255, 255]);
+		header.extend(QOI_END_MARKER);
+		assert!(QoiDecoder::new(header.as_slice()).is_err());
+		assert!(QoiDecoder::new(&b"qoif"[..]).is_err());
+		Ok(())
+	}
+}
@@ -1,14 +1,18 @@
 use crate::args::matches::ArgMatches;
 use crate::args::parser::ArgParser;
+use crate::file::format::FileFormat;
 use image::codecs::png::{CompressionType, FilterType};
 use image::codecs::pnm::{PnmSubtype, SampleEncoding};
 use image::codecs::webp::WebPQuality;
 
-/* PNG compression and filter settings */
+/* PNG compression, filter and palette settings */
 #[derive(Clone, Copy, Debug)]
 pub struct PngSettings {
 	pub compression: CompressionType,
 	pub filter: FilterType,
+	pub palette: bool,
+	pub colors: Option<u16>,
+	pub optimize: bool,
 }
 
 /* Default initialization values for PngSettings */
@@ -17,6 +21,9 @@ impl Default for PngSettings {
 		Self {
 			compression: CompressionType::Fast,
 			filter: FilterType::Sub,
+			palette: false,
+			colors: None,
+			optimize: false,
 		}
 	}
 }
@@ -27,12 +34,24 @@ impl PngSettings {
 	 *
 	 * @param  compression
 	 * @param  filter
+	 * @param  palette
+	 * @param  colors (Option)
+	 * @param  optimize
 	 * @return PngSettings
 	 */
-	pub fn new(compression: CompressionType, filter: FilterType) -> Self {
+	pub fn new(
+		compression: CompressionType,
+		filter: FilterType,
+		palette: bool,
+		colors: Option<u16>,
+		optimize: bool,
+	) -> Self {
 		Self {
 			compression,
 			filter,
+			palette,
+			colors: colors.map(|colors| colors.clamp(2, 256)),
+			optimize,
 		}
 	}
 
@@ -40,10 +59,60 @@ impl PngSettings {
 	 * Create a new PngSettings object from arguments.
 	 *
 	 * @param  matches
+	 * @param  file_format
 	 * @return PngSettings
 	 */
-	pub fn from_args(matches: &ArgMatches<'_>) -> Self {
-		Self::from_parser(ArgParser::from_subcommand(matches, "png"))
+	pub fn from_args(matches: &ArgMatches<'_>, file_format: &FileFormat) -> Self {
+		Self::from_parser(ArgParser::from_subcommand(
+			matches,
+			if file_format != &FileFormat::Apng {
+				"png"
+			} else if matches.is_present("make") {
+				"make"
+			} else {
+				"apng"
+			},
+		))
+	}
+
+	/**
+	 * Get the compression level for the png crate.
+	 *
+	 * @return Compression
+	 */
+	pub fn get_compression(&self) -> png::Compression {
+		match self.compression {
+			CompressionType::Default => png::Compression::Default,
+			CompressionType::Best => png::Compression::Best,
+			_ => png::Compression::Fast,
+		}
+	}
+
+	/**
+	 * Get the filter type for the png crate.
+	 *
+	 * @return Tuple of FilterType and AdaptiveFilterType
+	 */
+	pub fn get_filter(&self) -> (png::FilterType, png::AdaptiveFilterType) {
+		match self.filter {
+			FilterType::NoFilter => (
+				png::FilterType::NoFilter,
+				png::AdaptiveFilterType::NonAdaptive,
+			),
+			FilterType::Up => {
+				(png::FilterType::Up, png::AdaptiveFilterType::NonAdaptive)
+			}
+			FilterType::Avg => {
+				(png::FilterType::Avg, png::AdaptiveFilterType::NonAdaptive)
+			}
+			FilterType::Paeth => {
+				(png::FilterType::Paeth, png::AdaptiveFilterType::NonAdaptive)
+			}
+			FilterType::Adaptive => {
+				(png::FilterType::Sub, png::AdaptiveFilterType::Adaptive)
+			}
+			_ => (png::FilterType::Sub, png::AdaptiveFilterType::NonAdaptive),
+		}
 	}
 
 	/**
@@ -69,6 +138,11 @@ impl PngSettings {
 					Some("paeth") => FilterType::Paeth,
 					_ => FilterType::Sub,
 				},
+				matches.is_present("palette"),
+				matches
+					.value_of("colors")
+					.and_then(|colors| colors.parse().ok()),
+				matches.is_present("optimize"),
 			),
 			None => Self::default(),
 		}
@@ -103,10 +177,20 @@ impl JpgSettings {
 	 * Create a new JpgSettings object from arguments.
 	 *
 	 * @param  matches
+	 * @param  file_format
 	 * @return JpgSettings
 	 */
-	pub fn from_args(matches: &ArgMatches<'_>) -> Self {
-		Self::from_parser(ArgParser::from_subcommand(matches, "jpg"))
+	pub fn from_args(matches: &ArgMatches<'_>, file_format: &FileFormat) -> Self {
+		Self::from_parser(ArgParser::from_subcommand(
+			matches,
+			if file_format != &FileFormat::Pdf {
+				"jpg"
+			} else if matches.is_present("make") {
+				"make"
+			} else {
+				"pdf"
+			},
+		))
 	}
 
 	/**
@@ -123,10 +207,58 @@ impl JpgSettings {
 	}
 }
 
+/* PDF image embedding settings */
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PdfSettings {
+	pub jpg: bool,
+}
+
+impl PdfSettings {
+	/**
+	 * Create a new PdfSettings object.
+	 *
+	 * @param  jpg
+	 * @return PdfSettings
+	 */
+	pub fn new(jpg: bool) -> Self {
+		Self { jpg }
+	}
+
+	/**
+	 * Create a new PdfSettings object from arguments.
+	 *
+	 * @param  matches
+	 * @return PdfSettings
+	 */
+	pub fn from_args(matches: &ArgMatches<'_>) -> Self {
+		Self::from_parser(ArgParser::from_subcommand(
+			matches,
+			if matches.is_present("make") {
+				"make"
+			} else {
+				"pdf"
+			},
+		))
+	}
+
+	/**
+	 * Create a PdfSettings object from an argument parser.
+	 *
+	 * @param  parser
+	 * @return PdfSettings
+	 */
+	fn from_parser(parser: ArgParser<'_>) -> Self {
+		match parser.args {
+			Some(matches) => Self::new(matches.is_present("jpg")),
+			None => Self::default(),
+		}
+	}
+}
+
 /* WebP quality setting */
 #[derive(Clone, Copy, Debug)]
 pub struct WebPSettings {
-	quality: Option<u8>,
+	pub quality: Option<u8>,
 }
 
 /* Default initialization values for WebPSettings */
@@ -165,10 +297,18 @@ impl WebPSettings {
 	 * Create a new WebPSettings object from arguments.
 	 *
 	 * @param  matches
+	 * @param  file_format
 	 * @return WebPSettings
 	 */
-	pub fn from_args(matches: &ArgMatches<'_>) -> Self {
-		Self::from_parser(ArgParser::from_subcommand(matches, "webp"))
+	pub fn from_args(matches: &ArgMatches<'_>, file_format: &FileFormat) -> Self {
+		Self::from_parser(ArgParser::from_subcommand(
+			matches,
+			if file_format == &FileFormat::AnimWebP && matches.is_present("make") {
+				"make"
+			} else {
+				"webp"
+			},
+		))
 	}
 
 	/**
@@ -191,6 +331,132 @@ impl WebPSettings {
 	}
 }
 
+/* AVIF quality and speed settings */
+#[derive(Clone, Copy, Debug)]
+pub struct AvifSettings {
+	pub quality: u8,
+	pub speed: u8,
+}
+
+/* Default initialization values for AvifSettings */
+impl Default for AvifSettings {
+	fn default() -> Self {
+		Self {
+			quality: 80,
+			speed: 6,
+		}
+	}
+}
+
+impl AvifSettings {
+	/**
+	 * Create a new AvifSettings object.
+	 *
+	 * @param  quality
+	 * @param  speed
+	 * @return AvifSettings
+	 */
+	pub fn new(quality: u8, speed: u8) -> Self {
+		Self {
+			quality: quality.clamp(1, 100),
+			speed: speed.clamp(1, 10),
+		}
+	}
+
+	/**
+	 * Create a new AvifSettings object from arguments.
+	 *
+	 * @param  matches
+	 * @return AvifSettings
+	 */
+	pub fn from_args(matches: &ArgMatches<'_>) -> Self {
+		Self::from_parser(ArgParser::from_subcommand(matches, "avif"))
+	}
+
+	/**
+	 * Create a AvifSettings object from an argument parser.
+	 *
+	 * @param  parser
+	 * @return AvifSettings
+	 */
+	fn from_parser(parser: ArgParser<'_>) -> Self {
+		match parser.args {
+			Some(_) => Self::new(
+				parser.parse("quality", Self::default().quality),
+				parser.parse("speed", Self::default().speed),
+			),
+			None => Self::default(),
+		}
+	}
+}
+
+/* Icon size settings */
+#[derive(Clone, Debug)]
+pub struct IconSettings {
+	pub sizes: Vec<u32>,
+}
+
+/* Default initialization values for IconSettings */
+impl Default for IconSettings {
+	fn default() -> Self {
+		Self {
+			sizes: vec![16, 32, 48, 256],
+		}
+	}
+}
+
+impl IconSettings {
+	/**
+	 * Create a new IconSettings object.
+	 *
+	 * @param  sizes
+	 * @return IconSettings
+	 */
+	pub fn new(mut sizes: Vec<u32>) -> Self {
+		sizes.sort_unstable();
+		sizes.dedup();
+		Self { sizes }
+	}
+
+	/**
+	 * Create a new IconSettings object from arguments.
+	 *
+	 * @param  matches
+	 * @param  file_format
+	 * @return IconSettings
+	 */
+	pub fn from_args(matches: &ArgMatches<'_>, file_format: &FileFormat) -> Self {
+		Self::from_parser(ArgParser::from_subcommand(
+			matches,
+			if file_format == &FileFormat::Icns {
+				"icns"
+			} else {
+				"ico"
+			},
+		))
+	}
+
+	/**
+	 * Create a IconSettings object from an argument parser.
+	 *
+	 * @param  parser
+	 * @return IconSettings
+	 */
+	fn from_parser(parser: ArgParser<'_>) -> Self {
+		match parser.args {
+			Some(matches) => Self::new(
+				matches
+					.value_of("sizes")
+					.unwrap_or_default()
+					.split(',')
+					.filter_map(|size| size.trim().parse().ok())
+					.collect(),
+			),
+			None => Self::default(),
+		}
+	}
+}
+
 /* PNM subtype settings */
 #[derive(Clone, Copy, Debug)]
 pub struct PnmSettings {
@@ -273,14 +539,24 @@ mod tests {
 						.takes_value(true),
 				)
 				.arg(Arg::with_name("filter").long("filter").takes_value(true))
+				.arg(Arg::with_name("palette").long("palette"))
+				.arg(Arg::with_name("colors").long("colors").takes_value(true))
+				.arg(Arg::with_name("optimize").long("optimize"))
 				.get_matches_from(vec![
 					"test",
 					"--compression",
 					value.0,
 					"--filter",
 					value.1,
+					"--palette",
+					"--colors",
+					"1000",
+					"--optimize",
 				]);
 			let png_settings = PngSettings::from_parser(ArgParser::from_args(&args));
+			assert!(png_settings.palette);
+			assert_eq!(Some(256), png_settings.colors);
+			assert!(png_settings.optimize);
 			if value.0.is_empty() && value.1.is_empty() {
 				assert_eq!(
 					PngSettings::default().compression,
@@ -295,6 +571,18 @@ mod tests {
 				assert_ne!(PngSettings::default().filter, png_settings.filter);
 			}
 		}
+		let png_settings = PngSettings::new(
+			CompressionType::Best,
+			FilterType::Adaptive,
+			false,
+			None,
+			false,
+		);
+		assert_eq!("Best", format!("{:?}", png_settings.get_compression()));
+		assert_eq!(
+			(png::FilterType::Sub, png::AdaptiveFilterType::Adaptive),
+			png_settings.get_filter()
+		);
 	}
 	#[test]
 	fn test_jpg_settings() {
@@ -329,6 +617,33 @@ mod tests {
 		);
 	}
 	#[test]
+	fn test_avif_settings() {
+		let args = App::new("test")
+			.arg(Arg::with_name("quality").long("quality").takes_value(true))
+			.arg(Arg::with_name("speed").long("speed").takes_value(true))
+			.get_matches_from(vec!["test", "--quality", "50", "--speed", "12"]);
+		let avif_settings = AvifSettings::from_parser(ArgParser::from_args(&args));
+		assert_eq!(50, avif_settings.quality);
+		assert_eq!(10, avif_settings.speed);
+		let avif_settings = AvifSettings::from_parser(ArgParser::new(None));
+		assert_eq!(80, avif_settings.quality);
+		assert_eq!(6, avif_settings.speed);
+	}
+	#[test]
+	fn test_icon_settings() {
+		let args = App::new("test")
+			.arg(Arg::with_name("sizes").long("sizes").takes_value(true))
+			.get_matches_from(vec!["test", "--sizes", "256, 16,x,32,16"]);
+		assert_eq!(
+			vec![16, 32, 256],
+			IconSettings::from_parser(ArgParser::from_args(&args)).sizes
+		);
+		assert_eq!(
+			IconSettings::default().sizes,
+			IconSettings::from_parser(ArgParser::new(None)).sizes
+		);
+	}
+	#[test]
 	fn test_pnm_settings() {
 		let args = App::new("test")
 			.arg(Arg::with_name("format").long("format").takes_value(true))
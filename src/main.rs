@@ -1,5 +1,6 @@
 #![allow(clippy::tabs_in_doc_comments)]
 #![allow(clippy::manual_map)]
+#![allow(clippy::unnecessary_map_or)]
 #![warn(rust_2018_idioms)]
 
 #[macro_use]
@@ -14,10 +15,14 @@ mod edit;
 mod file;
 mod gif;
 mod image;
+mod pdf;
 mod record;
 mod settings;
+mod tiff;
 mod util;
+mod video;
 mod view;
+mod webp;
 mod window;
 #[cfg(not(all(unix, not(target_os = "macos"))))]
 mod ws;
@@ -0,0 +1,280 @@
+use crate::app::AppResult;
+use crate::image::settings::{JpgSettings, PdfSettings};
+use crate::image::Image;
+use crate::util::state::InputState;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use image::codecs::jpeg::JpegEncoder;
+use image::{ColorType, ExtendedColorType, ImageEncoder};
+use std::io::{self, Write};
+
+/* Header of the PDF file (with binary marker) */
+const PDF_HEADER: &[u8] = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n";
+
+/* PDF document encoder */
+pub struct PdfEncoder<'a, Output: Write> {
+	output: Output,
+	settings: &'a PdfSettings,
+	jpg: &'a JpgSettings,
+	data: Vec<u8>,
+	offsets: Vec<Option<usize>>,
+}
+
+impl<'a, Output: Write> PdfEncoder<'a, Output> {
+	/**
+	 * Create a new PdfEncoder object.
+	 *
+	 * @param  output
+	 * @param  settings
+	 * @param  jpg
+	 * @return PdfEncoder
+	 */
+	pub fn new(
+		output: Output,
+		settings: &'a PdfSettings,
+		jpg: &'a JpgSettings,
+	) -> Self {
+		Self {
+			output,
+			settings,
+			jpg,
+			data: PDF_HEADER.to_vec(),
+			offsets: Vec::new(),
+		}
+	}
+
+	/**
+	 * Compress the data with zlib (Flate).
+	 *
+	 * @param  data
+	 * @return Vector of u8 (Result)
+	 */
+	fn deflate(data: &[u8]) -> AppResult<Vec<u8>> {
+		let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+		encoder.write_all(data)?;
+		Ok(encoder.finish()?)
+	}
+
+	/**
+	 * Write an object with the given dictionary and optional stream.
+	 *
+	 * @param  id
+	 * @param  dictionary
+	 * @param  stream (Option)
+	 */
+	fn write_object(&mut self, id: usize, dictionary: &str, stream: Option<&[u8]>) {
+		if self.offsets.len() < id {
+			self.offsets.resize(id, None);
+		}
+		self.offsets[id - 1] = Some(self.data.len());
+		self.data
+			.extend(format!("{id} 0 obj\n<< {dictionary}").as_bytes());
+		match stream {
+			Some(stream) => {
+				self.data.extend(
+					format!(" /Length {} >>\nstream\n", stream.len()).as_bytes(),
+				);
+				self.data.extend(stream);
+				self.data.extend(b"\nendstream");
+			}
+			None => self.data.extend(b" >>"),
+		}
+		self.data.extend(b"\nendobj\n");
+	}
+
+	/**
+	 * Write the image as a page of the document.
+	 *
+	 * @param  image
+	 * @param  id
+	 * @return Result
+	 */
+	fn write_page(&mut self, image: &Image, id: usize) -> AppResult<()> {
+		let (width, height) = (image.geometry.width, image.geometry.height);
+		let rgba = image.get_data(ExtendedColorType::Rgba8);
+		let (image_id, content_id, mask_id) = (id + 1, id + 2, id + 3);
+		let dictionary = format!(
+			"/Type /XObject /Subtype /Image /Width {width} /Height {height} \
+			/ColorSpace /DeviceRGB /BitsPerComponent 8"
+		);
+		let has_alpha = rgba.chunks(4).any(|pixel| pixel[3] != 0xFF);
+		let mask = if has_alpha {
+			format!(" /SMask {mask_id} 0 R")
+		} else {
+			String::new()
+		};
+		if self.settings.jpg {
+			let mut data = Vec::new();
+			JpegEncoder::new_with_quality(&mut data, self.jpg.quality).write_image(
+				&image.get_data(ExtendedColorType::Rgb8),
+				width,
+				height,
+				ColorType::Rgb8,
+			)?;
+			self.write_object(
+				image_id,
+				&format!("{dictionary} /Filter /DCTDecode{mask}"),
+				Some(&data),
+			);
+		} else {
+			let data = Self::deflate(&image.get_data(ExtendedColorType::Rgb8))?;
+			self.write_object(
+				image_id,
+				&format!("{dictionary} /Filter /FlateDecode{mask}"),
+				Some(&data),
+			);
+		}
+		let content = format!("q {width} 0 0 {height} 0 0 cm /Im0 Do Q");
+		self.write_object(content_id, "", Some(content.as_bytes()));
+		if has_alpha {
+			let alpha = rgba.chunks(4).map(|pixel| pixel[3]).collect::<Vec<u8>>();
+			self.write_object(
+				mask_id,
+				&format!(
+					"/Type /XObject /Subtype /Image /Width {width} /Height {height} \
+					/ColorSpace /DeviceGray /BitsPerComponent 8 /Filter /FlateDecode"
+				),
+				Some(&Self::deflate(&alpha)?),
+			);
+		}
+		self.write_object(
+			id,
+			&format!(
+				"/Type /Page /Parent 2 0 R /MediaBox [0 0 {width} {height}] \
+				/Resources << /XObject << /Im0 {image_id} 0 R >> >> \
+				/Contents {content_id} 0 R"
+			),
+			None,
+		);
+		Ok(())
+	}
+
+	/**
+	 * Write the cross-reference table and the trailer.
+	 *
+	 * Object IDs that are not written (such as the soft masks
+	 * of the opaque pages) are marked as free entries.
+	 */
+	fn write_trailer(&mut self) {
+		let offset = self.data.len();
+		let mut xref =
+			format!("xref\n0 {}\n0000000000 65535 f \n", self.offsets.len() + 1);
+		for object_offset in &self.offsets {
+			xref += &match object_offset {
+				Some(offset) => format!("{offset:010} 00000 n \n"),
+				None => String::from("0000000000 65535 f \n"),
+			};
+		}
+		xref += &format!(
+			"trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{offset}\n%%EOF\n",
+			self.offsets.len() + 1
+		);
+		self.data.extend(xref.as_bytes());
+	}
+
+	/**
+	 * Write the images as pages of the PDF document.
+	 *
+	 * @param  images
+	 * @param  input_state (Option)
+	 * @return Result
+	 */
+	pub fn save(
+		mut self,
+		images: Vec<Image>,
+		input_state: Option<&'static InputState>,
+	) -> AppResult<()> {
+		let pages = (0..images.len()).map(|i| 3 + i * 4).collect::<Vec<usize>>();
+		self.write_object(1, "/Type /Catalog /Pages 2 0 R", None);
+		self.write_object(
+			2,
+			&format!(
+				"/Type /Pages /Kids [{}] /Count {}",
+				pages
+					.iter()
+					.map(|id| format!("{id} 0 R"))
+					.collect::<Vec<String>>()
+					.join(" "),
+				pages.len()
+			),
+			None,
+		);
+		for (i, (image, id)) in images.iter().zip(pages).enumerate() {
+			let percentage = ((i + 1) as f64 / images.len() as f64) * 100.;
+			info!("Saving... ({:.1}%)\r", percentage);
+			debug!(
+				"Encoding... ({:.1}%) [{}/{}]\r",
+				percentage,
+				i + 1,
+				images.len()
+			);
+			io::stdout().flush()?;
+			if let Some(state) = input_state {
+				if state.check_cancel_keys() {
+					info!("\n");
+					warn!("User interrupt detected.");
+					panic!("Failed to write the frames")
+				}
+			}
+			self.write_page(image, id)?;
+		}
+		info!("\n");
+		self.write_trailer();
+		self.output.write_all(&self.data)?;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::image::geometry::Geometry;
+	use image::Rgba;
+	use pretty_assertions::assert_eq;
+	fn get_offset(data: &[u8], pattern: &str) -> usize {
+		data.windows(pattern.len())
+			.position(|v| v == pattern.as_bytes())
+			.unwrap_or_default()
+	}
+	#[test]
+	fn test_pdf_encoder() -> AppResult<()> {
+		let geometry = Geometry::new(0, 0, 4, 3);
+		let images = vec![
+			Image::new(vec![Rgba([255, 0, 0, 255]); 12], false, geometry),
+			Image::new(vec![Rgba([0, 0, 255, 128]); 12], true, geometry),
+		];
+		for jpg in [false, true] {
+			let mut output = Vec::new();
+			PdfEncoder::new(
+				&mut output,
+				&PdfSettings::new(jpg),
+				&JpgSettings::default(),
+			)
+			.save(images.clone(), None)?;
+			let data = String::from_utf8_lossy(&output).to_string();
+			assert!(data.starts_with("%PDF-1.4"));
+			assert!(data.ends_with("%%EOF\n"));
+			assert!(data.contains("/Kids [3 0 R 7 0 R] /Count 2"));
+			assert!(data.contains("/MediaBox [0 0 4 3]"));
+			assert!(data.contains("/SMask 10 0 R") && data.contains("10 0 obj"));
+			assert!(!data.contains("/SMask 6 0 R") && !data.contains("6 0 obj"));
+			assert_eq!(jpg, data.contains("/DCTDecode"));
+			let xref = get_offset(&output, "xref\n");
+			let trailer = String::from_utf8_lossy(&output[xref..]).to_string();
+			assert_eq!(
+				Some(xref),
+				trailer
+					.lines()
+					.skip_while(|v| *v != "startxref")
+					.nth(1)
+					.and_then(|v| v.parse::<usize>().ok())
+			);
+			assert_eq!(
+				Some(format!("{:010} 00000 n ", get_offset(&output, "3 0 obj\n"))),
+				trailer.lines().nth(5).map(String::from)
+			);
+			assert_eq!(Some("0000000000 65535 f "), trailer.lines().nth(8));
+		}
+		Ok(())
+	}
+}
@@ -108,14 +108,19 @@ where
 	/**
 	 * Record frames synchronously with blocking the current thread.
 	 *
+	 * Frames are sent through the channel as they are captured
+	 * and recording stops if the receiver is dropped.
+	 *
 	 * @param  input_state (Option)
-	 * @return Vector of Image
+	 * @param  sender
+	 * @return bool (Result)
 	 */
 	pub fn record_sync(
 		&mut self,
 		input_state: Option<&InputState>,
-	) -> AppResult<Vec<Image>> {
-		let mut frames = Vec::new();
+		sender: mpsc::Sender<Image>,
+	) -> AppResult<bool> {
+		let mut frames = 0;
 		let recording = Arc::new(AtomicBool::new(true));
 		let rec_state = recording.clone();
 		ctrlc::set_handler(move || {
@@ -123,35 +128,42 @@ where
 		})?;
 		self.window.show_countdown();
 		let max_frames = self.get_max_frames();
-		while recording.load(Ordering::SeqCst) && frames.len() < max_frames {
+		while recording.load(Ordering::SeqCst) && frames < max_frames {
 			if let Some(state) = input_state {
 				if state.check_cancel_keys() {
-					frames.clear();
 					debug!("\n");
 					warn!("User interrupt detected.");
-					break;
+					return Ok(false);
 				} else if state.check_action() {
 					break;
 				}
 			}
 			self.clock.tick();
-			frames.push(self.window.get_image().ok_or_else(|| {
+			let image = self.window.get_image().ok_or_else(|| {
 				AppError::FrameError(String::from("Failed to get image"))
-			})?);
-			debug!("Frames: {}\r", frames.len());
+			})?;
+			if sender.send(image).is_err() {
+				break;
+			}
+			frames += 1;
+			debug!("Frames: {}\r", frames);
 			io::stdout().flush()?;
 		}
 		debug!("\n");
-		Ok(frames)
+		Ok(true)
 	}
 
 	/**
 	 * Record frames asynchronously and without blocking.
 	 *
+	 * Frames are sent through the channel as they are captured
+	 * and recording stops if the receiver is dropped.
+	 *
+	 * @param  sender
 	 * @return RecordResult
 	 */
-	pub fn record_async(mut self) -> RecordResult<Vec<Image>> {
-		let mut frames = Vec::new();
+	pub fn record_async(mut self, sender: mpsc::Sender<Image>) -> RecordResult<()> {
+		let mut frames = 0;
 		RecordResult::new(
 			self.channel.0.clone(),
 			thread::spawn(move || {
@@ -159,18 +171,23 @@ where
 				let max_frames = self.get_max_frames();
 				while self.channel.1.try_recv().is_err() {
 					self.clock.tick();
-					if frames.len() < max_frames {
-						frames.push(
-							self.window
-								.get_image()
-								.expect("Failed to get the image"),
-						);
-						debug!("Frames: {}\r", frames.len());
+					if frames < max_frames {
+						if sender
+							.send(
+								self.window
+									.get_image()
+									.expect("Failed to get the image"),
+							)
+							.is_err()
+						{
+							break;
+						}
+						frames += 1;
+						debug!("Frames: {}\r", frames);
 						io::stdout().flush().expect("Failed to flush stdout");
 					}
 				}
 				debug!("\n");
-				frames
 			}),
 		)
 	}
@@ -188,12 +205,16 @@ mod tests {
 	fn test_record() {
 		let window = TestWindow::default();
 		let recorder = Recorder::new(window, 10, false, RecordSettings::default());
-		let record = recorder.record_async();
+		let (sender, receiver) = mpsc::channel();
+		let record = recorder.record_async(sender);
 		thread::sleep(Duration::from_millis(200));
-		assert!(!record.get().unwrap().unwrap().is_empty());
+		record.get().unwrap().unwrap();
+		assert_ne!(0, receiver.iter().count());
 		let mut recorder =
 			Recorder::new(window, 10, false, RecordSettings::default());
 		recorder.settings.time.duration = Some(0.2);
-		assert_ne!(0, recorder.record_sync(None).unwrap().len());
+		let (sender, receiver) = mpsc::channel();
+		assert!(recorder.record_sync(None, sender).unwrap());
+		assert_ne!(0, receiver.iter().count());
 	}
 }
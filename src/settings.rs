@@ -4,9 +4,11 @@ use crate::args::matches::ArgMatches;
 use crate::edit::settings::EditSettings;
 use crate::file::format::FileFormat;
 use crate::file::settings::SaveSettings;
-use crate::image::geometry::Geometry;
-use crate::image::settings::{JpgSettings, PngSettings, PnmSettings, WebPSettings};
-use crate::record::settings::{RecordSettings, RecordWindow};
+use crate::image::settings::{
+	AvifSettings, IconSettings, JpgSettings, PdfSettings, PngSettings, PnmSettings,
+	WebPSettings,
+};
+use crate::record::settings::RecordSettings;
 use crate::util::keys::{ActionKeys, KeyType};
 use crate::util::state::InputState;
 use crate::view::settings::ViewSettings;
@@ -22,6 +24,9 @@ pub struct AppSettings<'a> {
 	pub png: PngSettings,
 	pub jpg: JpgSettings,
 	pub webp: WebPSettings,
+	pub avif: AvifSettings,
+	pub icon: IconSettings,
+	pub pdf: PdfSettings,
 	pub pnm: PnmSettings,
 	pub edit: EditSettings,
 	pub analyze: AnalyzeSettings,
@@ -51,9 +56,12 @@ impl<'a> AppSettings<'a> {
 			record,
 			anim: AnimSettings::from_args(args, &save.file.format),
 			split: SplitSettings::from_args(args),
-			png: PngSettings::from_args(args),
-			jpg: JpgSettings::from_args(args),
-			webp: WebPSettings::from_args(args),
+			png: PngSettings::from_args(args, &save.file.format),
+			jpg: JpgSettings::from_args(args, &save.file.format),
+			webp: WebPSettings::from_args(args, &save.file.format),
+			avif: AvifSettings::from_args(args),
+			icon: IconSettings::from_args(args, &save.file.format),
+			pdf: PdfSettings::from_args(args),
 			analyze: AnalyzeSettings::from_args(args, Self::get_color(args)),
 			view: ViewSettings::from_args(args),
 			pnm,
@@ -136,6 +144,19 @@ impl<'a> AppSettings<'a> {
 		if self.anim.quality <= 20 {
 			warn!("Animation will be encoded in low quality.")
 		}
+		if self.anim.gifski.0 && self.anim.lossy != 0 {
+			warn!("Lossy LZW compression is not supported by gifski.")
+		}
+		if self.save.max_size.is_some()
+			&& !matches!(
+				self.save.file.format,
+				FileFormat::Gif | FileFormat::Apng | FileFormat::AnimWebP
+			) {
+			self.save.max_size = None;
+			warn!(
+				"Maximum file size is only used for GIF, APNG and WebP animations."
+			)
+		}
 		if self.record.time.countdown > 99 {
 			self.record.time.countdown = 99;
 			warn!("Countdown value cannot be greater than 99.")
@@ -161,37 +182,14 @@ impl<'a> AppSettings<'a> {
 		if !self.record.flag.select {
 			self.record.border = None;
 		}
-		if self.save.file.format == FileFormat::Ico {
-			self.set_icon_size()
-		}
-	}
-
-	/* Set the area size to 256x256 for encoding ICO. */
-	fn set_icon_size(&mut self) {
-		let ico_geometry = Geometry::new(0, 0, 256, 256);
-		match self.record.window {
-			RecordWindow::Focus(None, parent) => {
-				self.record.window = RecordWindow::Focus(Some(ico_geometry), parent)
-			}
-			RecordWindow::Root(None) => {
-				self.record.window = RecordWindow::Root(Some(ico_geometry))
-			}
-			RecordWindow::Focus(Some(ref mut geometry), _)
-			| RecordWindow::Root(Some(ref mut geometry)) => {
-				if geometry.width == 0 || geometry.width > ico_geometry.width {
-					geometry.width = ico_geometry.width;
-				}
-				if geometry.height == 0 || geometry.height > ico_geometry.height {
-					geometry.height = ico_geometry.height;
-				}
-			}
-		}
 	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::image::geometry::Geometry;
+	use crate::record::settings::RecordWindow;
 	use clap::ArgMatches as Args;
 	use pretty_assertions::assert_eq;
 	use std::env;
@@ -211,8 +209,10 @@ mod tests {
 		settings.jpg.quality = 0;
 		settings.anim.quality = 0;
 		settings.save.file.format = FileFormat::Ico;
+		settings.save.max_size = Some(1000);
 		settings.record.window =
 			RecordWindow::Focus(Some(Geometry::default()), false);
 		settings.check();
+		assert_eq!(None, settings.save.max_size);
 	}
 }
@@ -0,0 +1,120 @@
+use image::error::{DecodingError, ImageFormatHint};
+use image::{
+	AnimationDecoder, Frame, Frames, ImageError, ImageFormat, ImageResult, RgbaImage,
+};
+use std::io::{Cursor, Read};
+use tiff::decoder::{Decoder, DecodingResult};
+use tiff::ColorType;
+
+/* Decoder for (multi-page) TIFF files */
+#[derive(Debug)]
+pub struct AnimTiffDecoder {
+	data: Vec<u8>,
+}
+
+impl AnimTiffDecoder {
+	/**
+	 * Create a new AnimTiffDecoder object.
+	 *
+	 * @param  input
+	 * @return AnimTiffDecoder (Result)
+	 */
+	pub fn new<Input: Read>(mut input: Input) -> ImageResult<Self> {
+		let mut data = Vec::new();
+		input.read_to_end(&mut data)?;
+		if !data.starts_with(b"II*\0") && !data.starts_with(b"MM\0*") {
+			return Err(Self::get_error("Invalid TIFF header"));
+		}
+		Ok(Self { data })
+	}
+
+	/**
+	 * Create a decoding error with the given message.
+	 *
+	 * @param  message
+	 * @return ImageError
+	 */
+	fn get_error<Message: ToString>(message: Message) -> ImageError {
+		ImageError::Decoding(DecodingError::new(
+			ImageFormatHint::Exact(ImageFormat::Tiff),
+			message.to_string(),
+		))
+	}
+
+	/**
+	 * Convert the samples of a page to RGBA.
+	 *
+	 * @param  samples
+	 * @param  color_type
+	 * @return Vector of u8 (Result)
+	 */
+	fn get_rgba(
+		samples: DecodingResult,
+		color_type: ColorType,
+	) -> ImageResult<Vec<u8>> {
+		let samples = match samples {
+			DecodingResult::U8(samples) => samples,
+			DecodingResult::U16(samples) => {
+				samples.into_iter().map(|v| (v >> 8) as u8).collect()
+			}
+			_ => return Err(Self::get_error("Unsupported sample format")),
+		};
+		Ok(match color_type {
+			ColorType::Gray(_) => {
+				samples.iter().flat_map(|v| [*v, *v, *v, 255]).collect()
+			}
+			ColorType::GrayA(_) => samples
+				.chunks_exact(2)
+				.flat_map(|v| [v[0], v[0], v[0], v[1]])
+				.collect(),
+			ColorType::RGB(_) => samples
+				.chunks_exact(3)
+				.flat_map(|v| [v[0], v[1], v[2], 255])
+				.collect(),
+			ColorType::RGBA(_) => samples,
+			_ => {
+				return Err(Self::get_error(format!(
+					"Unsupported color type: {color_type:?}"
+				)))
+			}
+		})
+	}
+
+	/**
+	 * Decode the pages of the TIFF file.
+	 *
+	 * @return Vector of Frame (Result)
+	 */
+	fn decode_frames(&self) -> ImageResult<Vec<Frame>> {
+		let mut decoder = Decoder::new(Cursor::new(self.data.as_slice()))
+			.map_err(Self::get_error)?;
+		let mut frames = Vec::new();
+		loop {
+			let (width, height) = decoder.dimensions().map_err(Self::get_error)?;
+			let color_type = decoder.colortype().map_err(Self::get_error)?;
+			let rgba = Self::get_rgba(
+				decoder.read_image().map_err(Self::get_error)?,
+				color_type,
+			)?;
+			frames.push(Frame::new(
+				RgbaImage::from_raw(width, height, rgba)
+					.ok_or_else(|| Self::get_error("Invalid page dimensions"))?,
+			));
+			if !decoder.more_images() {
+				break;
+			}
+			decoder.next_image().map_err(Self::get_error)?;
+		}
+		Ok(frames)
+	}
+}
+
+/* Animation decoder implementation for AnimTiffDecoder */
+impl<'a> AnimationDecoder<'a> for AnimTiffDecoder {
+	fn into_frames(self) -> Frames<'a> {
+		Frames::new(match self.decode_frames() {
+			Ok(frames) => Box::new(frames.into_iter().map(Ok)),
+			Err(e) => Box::new(std::iter::once(Err(e))),
+		})
+	}
+}
@@ -0,0 +1,106 @@
+pub mod decoder;
+
+use crate::app::AppResult;
+use crate::image::Image;
+use crate::util::state::InputState;
+use image::ExtendedColorType;
+use std::io::{self, Seek, Write};
+use tiff::encoder::colortype::RGBA8;
+use tiff::encoder::compression::Deflate;
+use tiff::encoder::TiffEncoder;
+
+/* Multi-page TIFF encoder */
+pub struct AnimTiffEncoder<Output: Write + Seek> {
+	output: Output,
+}
+
+impl<Output: Write + Seek> AnimTiffEncoder<Output> {
+	/**
+	 * Create a new AnimTiffEncoder object.
+	 *
+	 * @param  output
+	 * @return AnimTiffEncoder
+	 */
+	pub fn new(output: Output) -> Self {
+		Self { output }
+	}
+
+	/**
+	 * Write the images as pages of the TIFF file.
+	 *
+	 * @param  images
+	 * @param  input_state (Option)
+	 * @return Result
+	 */
+	pub fn save(
+		mut self,
+		images: Vec<Image>,
+		input_state: Option<&'static InputState>,
+	) -> AppResult<()> {
+		let mut encoder = TiffEncoder::new(&mut self.output)?;
+		for (i, image) in images.iter().enumerate() {
+			let percentage = ((i + 1) as f64 / images.len() as f64) * 100.;
+			info!("Saving... ({:.1}%)\r", percentage);
+			debug!(
+				"Encoding... ({:.1}%) [{}/{}]\r",
+				percentage,
+				i + 1,
+				images.len()
+			);
+			io::stdout().flush()?;
+			if let Some(state) = input_state {
+				if state.check_cancel_keys() {
+					info!("\n");
+					warn!("User interrupt detected.");
+					panic!("Failed to write the frames")
+				}
+			}
+			encoder.write_image_with_compression::<RGBA8, _>(
+				image.geometry.width,
+				image.geometry.height,
+				Deflate::default(),
+				&image.get_data(ExtendedColorType::Rgba8),
+			)?;
+		}
+		info!("\n");
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::decoder::AnimTiffDecoder;
+	use super::*;
+	use crate::image::geometry::Geometry;
+	use image::{AnimationDecoder, Rgba};
+	use pretty_assertions::assert_eq;
+	use std::io::Cursor;
+	#[test]
+	fn test_tiff_encoder() -> AppResult<()> {
+		let images = [[255, 0, 0, 255], [0, 0, 255, 128], [0, 255, 0, 0]]
+			.into_iter()
+			.enumerate()
+			.map(|(i, rgba)| {
+				let geometry = Geometry::new(0, 0, 4 + i as u32, 3);
+				Image::new(
+					vec![Rgba(rgba); geometry.width as usize * 3],
+					true,
+					geometry,
+				)
+			})
+			.collect::<Vec<Image>>();
+		let mut output = Cursor::new(Vec::new());
+		AnimTiffEncoder::new(&mut output).save(images.clone(), None)?;
+		let frames = AnimTiffDecoder::new(output.get_ref().as_slice())?
+			.into_frames()
+			.collect_frames()?;
+		assert_eq!(images.len(), frames.len());
+		for (image, frame) in images.iter().zip(frames) {
+			assert_eq!(
+				image.get_data(ExtendedColorType::Rgba8),
+				frame.into_buffer().into_raw()
+			);
+		}
+		Ok(())
+	}
+}
@@ -1,5 +1,6 @@
+use crate::app::{AppError, AppResult};
 use std::io::Error;
-use std::process::Command as OsCommand;
+use std::process::{Child, Command as OsCommand, Stdio};
 
 /* The command and its arguments */
 #[derive(Debug)]
@@ -46,11 +47,47 @@ impl<'a> Command<'a> {
 			Err(e) => Err(e),
 		}
 	}
+
+	/**
+	 * Spawn the command with a pipe to its standard input.
+	 *
+	 * @param  envs
+	 * @return Child (Result)
+	 */
+	pub fn spawn_piped(&self, envs: Vec<(String, String)>) -> AppResult<Child> {
+		info!("Piping to the command...");
+		Ok(OsCommand::new(self.cmd)
+			.args(&self.args)
+			.envs(envs)
+			.stdin(Stdio::piped())
+			.spawn()?)
+	}
+
+	/**
+	 * Close the standard input of the command and wait for it to exit.
+	 *
+	 * @param  child
+	 * @return Result
+	 */
+	pub fn wait(&self, mut child: Child) -> AppResult<()> {
+		drop(child.stdin.take());
+		let status = child.wait()?;
+		if status.success() {
+			Ok(())
+		} else {
+			Err(AppError::CommandError(format!(
+				"{:?} failed with {}",
+				self.args.last().unwrap_or(&self.cmd),
+				status
+			)))
+		}
+	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use std::io::Write;
 	use std::time::{Duration, Instant};
 	#[test]
 	fn test_command() -> Result<(), Error> {
@@ -59,6 +96,23 @@ mod tests {
 		Command::new("sleep", vec!["0.01"]).execute()?;
 		assert!(now.elapsed() >= sleep_time);
 		assert!(Command::from("xyz").execute().is_err());
+		let envs = vec![(String::from("MENYOKI_WIDTH"), String::from("2"))];
+		for (command, success) in [
+			("test \"$(cat)$MENYOKI_WIDTH\" = x2", true),
+			("cat > /dev/null; exit 3", false),
+		] {
+			let command = Command::from(command);
+			let mut child = command.spawn_piped(envs.clone()).unwrap();
+			let written = child.stdin.as_mut().map(|stdin| stdin.write_all(b"x"));
+			let result = command.wait(child);
+			written.transpose()?;
+			match result {
+				Ok(()) => assert!(success),
+				Err(e) => {
+					assert!(!success && matches!(e, AppError::CommandError(_)))
+				}
+			}
+		}
 		Ok(())
 	}
 }
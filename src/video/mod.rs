@@ -0,0 +1,3 @@
+pub mod pipe;
+pub mod raw;
+pub mod y4m;
@@ -0,0 +1,208 @@
+use crate::app::{AppError, AppResult};
+use crate::file::format::FileFormat;
+use crate::image::geometry::Geometry;
+use crate::image::qoi::QoiEncoder;
+use crate::image::settings::PngSettings;
+use crate::image::Image;
+use crate::util::command::Command;
+use crate::video::raw::RawEncoder;
+use crate::video::y4m::Y4mEncoder;
+use image::codecs::png::PngEncoder;
+use image::{ColorType, ExtendedColorType, ImageEncoder};
+use std::process::{Child, ChildStdin};
+
+/* Running command and the properties of its stream */
+type Process = (Child, ChildStdin, Geometry, u32);
+
+/* Frame writer for streaming to a command */
+pub struct FramePipe<'a> {
+	command: Command<'a>,
+	format: &'a FileFormat,
+	png: &'a PngSettings,
+	process: Option<Process>,
+	frames: usize,
+}
+
+impl<'a> FramePipe<'a> {
+	/**
+	 * Create a new FramePipe object.
+	 *
+	 * @param  command
+	 * @param  format
+	 * @param  png
+	 * @return FramePipe
+	 */
+	pub fn new(
+		command: &'a str,
+		format: &'a FileFormat,
+		png: &'a PngSettings,
+	) -> Self {
+		Self {
+			command: Command::from(command),
+			format,
+			png,
+			process: None,
+			frames: 0,
+		}
+	}
+
+	/**
+	 * Spawn the command and write the stream header.
+	 *
+	 * @param  geometry
+	 * @param  fps
+	 * @return Result
+	 */
+	fn spawn(&mut self, geometry: Geometry, fps: u32) -> AppResult<()> {
+		let envs = [
+			("WIDTH", geometry.width.to_string()),
+			("HEIGHT", geometry.height.to_string()),
+			("FPS", fps.to_string()),
+			("FORMAT", self.format.as_extension()),
+		]
+		.into_iter()
+		.map(|(name, value)| {
+			(
+				format!("{}_{}", env!("CARGO_PKG_NAME").to_uppercase(), name),
+				value,
+			)
+		})
+		.collect();
+		debug!("Environment: {:?}", envs);
+		let mut child = self.command.spawn_piped(envs)?;
+		let mut stdin = child.stdin.take().ok_or_else(|| {
+			AppError::CommandError(String::from("Failed to open the standard input"))
+		})?;
+		if *self.format == FileFormat::Y4m {
+			Y4mEncoder::new(fps, geometry, &mut stdin).write_header()?;
+		}
+		self.process = Some((child, stdin, geometry, fps));
+		Ok(())
+	}
+
+	/**
+	 * Write the image to the standard input in the given format.
+	 *
+	 * @param  process
+	 * @param  format
+	 * @param  png
+	 * @param  image
+	 * @return Result
+	 */
+	fn write_frame(
+		process: &mut Process,
+		format: &FileFormat,
+		png: &PngSettings,
+		image: &Image,
+	) -> AppResult<()> {
+		let (_, stdin, geometry, fps) = process;
+		match format {
+			FileFormat::Y4m => {
+				Y4mEncoder::new(*fps, *geometry, stdin).write_frame(image)
+			}
+			FileFormat::Png => {
+				Ok(
+					PngEncoder::new_with_quality(stdin, png.compression, png.filter)
+						.write_image(
+							&image.get_data(ExtendedColorType::Rgba8),
+							image.geometry.width,
+							image.geometry.height,
+							ColorType::Rgba8,
+						)?,
+				)
+			}
+			FileFormat::Qoi => Ok(QoiEncoder::new(stdin).write_image(
+				&image.get_data(ExtendedColorType::Rgba8),
+				image.geometry.width,
+				image.geometry.height,
+				ColorType::Rgba8,
+			)?),
+			_ => RawEncoder::new(*fps, *geometry, stdin).write_frame(image),
+		}
+	}
+
+	/**
+	 * Write the frame to the command.
+	 *
+	 * Command is spawned on the first frame since its size
+	 * is passed in the environment along with the FPS.
+	 * Exit status of the command is checked if writing fails.
+	 *
+	 * @param  image
+	 * @param  fps
+	 * @return Result
+	 */
+	pub fn write(&mut self, image: &Image, fps: u32) -> AppResult<()> {
+		if self.process.is_none() {
+			self.spawn(image.geometry, fps)?;
+		}
+		if let Some(process) = self.process.as_mut() {
+			if let Err(e) = Self::write_frame(process, self.format, self.png, image)
+			{
+				if let Some((child, stdin, _, _)) = self.process.take() {
+					drop(stdin);
+					self.command.wait(child)?;
+				}
+				return Err(e);
+			}
+		}
+		self.frames += 1;
+		debug!("Frames: {}\r", self.frames);
+		Ok(())
+	}
+
+	/**
+	 * Close the pipe and wait for the command to exit.
+	 *
+	 * @return usize (Result)
+	 */
+	pub fn finish(self) -> AppResult<usize> {
+		match self.process {
+			Some((child, stdin, _, _)) => {
+				drop(stdin);
+				self.command.wait(child)?;
+				Ok(self.frames)
+			}
+			None => Err(AppError::FrameError(String::from(
+				"No frames found to pipe",
+			))),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use image::Rgba;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_frame_pipe() -> AppResult<()> {
+		let png = PngSettings::default();
+		let image = Image::new(
+			vec![Rgba::from([1, 2, 3, 4]); 6],
+			true,
+			Geometry::new(0, 0, 3, 2),
+		);
+		for format in [
+			FileFormat::Rgba,
+			FileFormat::Png,
+			FileFormat::Qoi,
+			FileFormat::Y4m,
+		] {
+			let mut pipe = FramePipe::new(
+				"test \"$MENYOKI_WIDTH\" = 3 && cat > /dev/null",
+				&format,
+				&png,
+			);
+			pipe.write(&image, 10)?;
+			pipe.write(&image, 10)?;
+			assert_eq!(2, pipe.finish()?);
+		}
+		let format = FileFormat::Rgba;
+		assert!(FramePipe::new("cat", &format, &png).finish().is_err());
+		let mut pipe = FramePipe::new("cat > /dev/null; exit 1", &format, &png);
+		pipe.write(&image, 10)?;
+		assert!(matches!(pipe.finish(), Err(AppError::CommandError(_))));
+		Ok(())
+	}
+}
@@ -0,0 +1,116 @@
+use crate::app::AppResult;
+use crate::image::geometry::Geometry;
+use crate::image::Image;
+use crate::util::state::InputState;
+use image::ExtendedColorType;
+use std::io::{self, Write};
+
+/* Raw RGBA video encoder */
+pub struct RawEncoder<Output: Write> {
+	fps: u32,
+	geometry: Geometry,
+	output: Output,
+}
+
+impl<Output: Write> RawEncoder<Output> {
+	/**
+	 * Create a new RawEncoder object.
+	 *
+	 * @param  fps
+	 * @param  geometry
+	 * @param  output
+	 * @return RawEncoder
+	 */
+	pub fn new(fps: u32, geometry: Geometry, output: Output) -> Self {
+		Self {
+			fps,
+			geometry,
+			output,
+		}
+	}
+
+	/**
+	 * Get the sidecar header that describes the raw stream.
+	 *
+	 * @param  frames
+	 * @return String
+	 */
+	pub fn get_header(&self, frames: usize) -> String {
+		format!(
+			"pixel_format = rgba\nwidth = {}\nheight = {}\nfps = {}\nframes = {}\n",
+			self.geometry.width,
+			self.geometry.height,
+			self.fps.max(1),
+			frames
+		)
+	}
+
+	/**
+	 * Write the image as a raw RGBA frame.
+	 *
+	 * @param  image
+	 * @return Result
+	 */
+	pub fn write_frame(&mut self, image: &Image) -> AppResult<()> {
+		self.output
+			.write_all(&image.get_data(ExtendedColorType::Rgba8))?;
+		Ok(())
+	}
+
+	/**
+	 * Write the images as raw RGBA frames.
+	 *
+	 * @param  images
+	 * @param  input_state (Option)
+	 * @return Result
+	 */
+	pub fn save(
+		mut self,
+		images: Vec<Image>,
+		input_state: Option<&'static InputState>,
+	) -> AppResult<()> {
+		for (i, image) in images.iter().enumerate() {
+			let percentage = ((i + 1) as f64 / images.len() as f64) * 100.;
+			info!("Saving... ({:.1}%)\r", percentage);
+			debug!(
+				"Encoding... ({:.1}%) [{}/{}]\r",
+				percentage,
+				i + 1,
+				images.len()
+			);
+			io::stdout().flush()?;
+			if let Some(state) = input_state {
+				if state.check_cancel_keys() {
+					info!("\n");
+					warn!("User interrupt detected.");
+					panic!("Failed to write the frames")
+				}
+			}
+			self.write_frame(image)?;
+		}
+		info!("\n");
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use image::Rgba;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_raw_encoder() -> AppResult<()> {
+		let geometry = Geometry::new(0, 0, 2, 1);
+		let data = vec![Rgba::from([1, 2, 3, 4]), Rgba::from([5, 6, 7, 8])];
+		let images = vec![Image::new(data, true, geometry); 3];
+		let mut output = Vec::new();
+		let encoder = RawEncoder::new(10, geometry, &mut output);
+		assert_eq!(
+			"pixel_format = rgba\nwidth = 2\nheight = 1\nfps = 10\nframes = 3\n",
+			encoder.get_header(images.len())
+		);
+		encoder.save(images, None)?;
+		assert_eq!([1, 2, 3, 4, 5, 6, 7, 8].repeat(3), output);
+		Ok(())
+	}
+}
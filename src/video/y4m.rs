@@ -0,0 +1,193 @@
+use crate::app::AppResult;
+use crate::image::geometry::Geometry;
+use crate::image::Image;
+use crate::util::state::InputState;
+use image::ExtendedColorType;
+use std::io::{self, Write};
+
+/* YUV4MPEG2 (Y4M) encoder */
+pub struct Y4mEncoder<Output: Write> {
+	fps: u32,
+	geometry: Geometry,
+	output: Output,
+}
+
+impl<Output: Write> Y4mEncoder<Output> {
+	/**
+	 * Create a new Y4mEncoder object.
+	 *
+	 * @param  fps
+	 * @param  geometry
+	 * @param  output
+	 * @return Y4mEncoder
+	 */
+	pub fn new(fps: u32, geometry: Geometry, output: Output) -> Self {
+		Self {
+			fps,
+			geometry,
+			output,
+		}
+	}
+
+	/**
+	 * Get the stream header.
+	 *
+	 * @return String
+	 */
+	fn get_header(&self) -> String {
+		format!(
+			"YUV4MPEG2 W{} H{} F{}:1 Ip A1:1 C420jpeg\n",
+			self.geometry.width,
+			self.geometry.height,
+			self.fps.max(1)
+		)
+	}
+
+	/**
+	 * Convert the RGB data to Y, Cb and Cr components (BT.601).
+	 *
+	 * @param  rgb
+	 * @return Tuple of f32
+	 */
+	fn rgb_to_ycbcr(rgb: &[u8]) -> (f32, f32, f32) {
+		let (r, g, b) = (f32::from(rgb[0]), f32::from(rgb[1]), f32::from(rgb[2]));
+		(
+			16. + (65.481 * r + 128.553 * g + 24.966 * b) / 255.,
+			128. + (-37.797 * r - 74.203 * g + 112. * b) / 255.,
+			128. + (112. * r - 93.786 * g - 18.214 * b) / 255.,
+		)
+	}
+
+	/**
+	 * Get the planar YUV 4:2:0 data of the image.
+	 *
+	 * @param  image
+	 * @return Vector of u8
+	 */
+	fn get_frame_data(&self, image: &Image) -> Vec<u8> {
+		let (width, height) =
+			(self.geometry.width as usize, self.geometry.height as usize);
+		let rgb = image.get_data(ExtendedColorType::Rgb8);
+		let pixels = rgb
+			.chunks(3)
+			.map(Self::rgb_to_ycbcr)
+			.collect::<Vec<(f32, f32, f32)>>();
+		let mut data = pixels
+			.iter()
+			.map(|(y, _, _)| y.round() as u8)
+			.collect::<Vec<u8>>();
+		let (chroma_width, chroma_height) = (width.div_ceil(2), height.div_ceil(2));
+		let mut cb = Vec::with_capacity(chroma_width * chroma_height);
+		let mut cr = Vec::with_capacity(chroma_width * chroma_height);
+		for y in 0..chroma_height {
+			for x in 0..chroma_width {
+				let (u, v) = [(0, 0), (1, 0), (0, 1), (1, 1)].iter().fold(
+					(0., 0.),
+					|(u, v), (dx, dy)| {
+						let row = (y * 2 + dy).min(height - 1);
+						let column = (x * 2 + dx).min(width - 1);
+						let (_, cb, cr) = pixels[row * width + column];
+						(u + cb, v + cr)
+					},
+				);
+				cb.push((u / 4_f32).round() as u8);
+				cr.push((v / 4_f32).round() as u8);
+			}
+		}
+		data.extend(cb);
+		data.extend(cr);
+		data
+	}
+
+	/**
+	 * Write the stream header.
+	 *
+	 * @return Result
+	 */
+	pub fn write_header(&mut self) -> AppResult<()> {
+		self.output.write_all(self.get_header().as_bytes())?;
+		Ok(())
+	}
+
+	/**
+	 * Encode the image as a frame and write it to the stream.
+	 *
+	 * @param  image
+	 * @return Result
+	 */
+	pub fn write_frame(&mut self, image: &Image) -> AppResult<()> {
+		self.output.write_all(b"FRAME\n")?;
+		self.output.write_all(&self.get_frame_data(image))?;
+		Ok(())
+	}
+
+	/**
+	 * Encode images as frame and write to the Y4M stream.
+	 *
+	 * @param  images
+	 * @param  input_state (Option)
+	 * @return Result
+	 */
+	pub fn save(
+		mut self,
+		images: Vec<Image>,
+		input_state: Option<&'static InputState>,
+	) -> AppResult<()> {
+		self.write_header()?;
+		for (i, image) in images.iter().enumerate() {
+			let percentage = ((i + 1) as f64 / images.len() as f64) * 100.;
+			info!("Saving... ({:.1}%)\r", percentage);
+			debug!(
+				"Encoding... ({:.1}%) [{}/{}]\r",
+				percentage,
+				i + 1,
+				images.len()
+			);
+			io::stdout().flush()?;
+			if let Some(state) = input_state {
+				if state.check_cancel_keys() {
+					info!("\n");
+					warn!("User interrupt detected.");
+					panic!("Failed to write the frames")
+				}
+			}
+			self.write_frame(image)?;
+		}
+		info!("\n");
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use image::Rgba;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_y4m_encoder() -> AppResult<()> {
+		let geometry = Geometry::new(0, 0, 3, 1);
+		let images = vec![
+			Image::new(
+				vec![
+					Rgba::from([0, 0, 0, 255]),
+					Rgba::from([255, 255, 255, 255]),
+					Rgba::from([255, 0, 0, 255]),
+				],
+				false,
+				geometry,
+			);
+			2
+		];
+		let mut output = Vec::new();
+		Y4mEncoder::new(25, geometry, &mut output).save(images, None)?;
+		let header = b"YUV4MPEG2 W3 H1 F25:1 Ip A1:1 C420jpeg\n";
+		assert_eq!(header, &output[..header.len()]);
+		let frame = &output[header.len()..];
+		assert_eq!(2 * (6 + 3 + 2 + 2), frame.len());
+		assert_eq!(b"FRAME\n", &frame[..6]);
+		assert_eq!([16, 235, 81], frame[6..9]);
+		assert_eq!([128, 90], frame[9..11]);
+		assert_eq!([128, 240], frame[11..13]);
+		Ok(())
+	}
+}
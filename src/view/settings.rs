@@ -7,6 +7,7 @@ use std::path::PathBuf;
 pub struct ViewSettings {
 	pub file: PathBuf,
 	pub transparent: bool,
+	pub auto_orient: bool,
 }
 
 /* Default initialization values for ViewSettings */
@@ -15,6 +16,7 @@ impl Default for ViewSettings {
 		Self {
 			file: PathBuf::new(),
 			transparent: false,
+			auto_orient: true,
 		}
 	}
 }
@@ -25,10 +27,15 @@ impl ViewSettings {
 	 *
 	 * @param  file
 	 * @param  transparent
+	 * @param  auto_orient
 	 * @return ViewSettings
 	 */
-	pub fn new(file: PathBuf, transparent: bool) -> Self {
-		Self { file, transparent }
+	pub fn new(file: PathBuf, transparent: bool, auto_orient: bool) -> Self {
+		Self {
+			file,
+			transparent,
+			auto_orient,
+		}
 	}
 
 	/**
@@ -54,7 +61,11 @@ impl ViewSettings {
 				let file = shellexpand::full(file)
 					.map(|s| s.to_string())
 					.unwrap_or(file.to_string());
-				Self::new(PathBuf::from(file), matches.is_present("transparent"))
+				Self::new(
+					PathBuf::from(file),
+					matches.is_present("transparent"),
+					!matches.is_present("no-auto-orient"),
+				)
 			}
 			None => Self::default(),
 		}
@@ -71,12 +82,20 @@ mod tests {
 		let args = App::new("test")
 			.arg(Arg::with_name("file").required(true))
 			.arg(Arg::with_name("transparent").long("transparent"))
-			.get_matches_from(vec!["test", "test.png", "--transparent"]);
+			.arg(Arg::with_name("no-auto-orient").long("no-auto-orient"))
+			.get_matches_from(vec![
+				"test",
+				"test.png",
+				"--transparent",
+				"--no-auto-orient",
+			]);
 		let view_settings = ViewSettings::from_parser(ArgParser::from_args(&args));
 		assert_eq!(Some("test.png"), view_settings.file.to_str());
 		assert!(view_settings.transparent);
+		assert!(!view_settings.auto_orient);
 		let view_settings = ViewSettings::default();
 		assert_eq!(Some(""), view_settings.file.to_str());
 		assert!(!view_settings.transparent);
+		assert!(view_settings.auto_orient);
 	}
 }
@@ -0,0 +1,216 @@
+use image::error::{DecodingError, ImageFormatHint};
+use image::{
+	AnimationDecoder, Delay, Frame, Frames, ImageError, ImageFormat, ImageResult,
+	Rgba, RgbaImage,
+};
+use libwebp_sys::{WebPDecodeRGBA, WebPFree};
+use std::io::Read;
+use std::os::raw::c_int;
+use std::slice;
+
+/* Position, size and timing of an animation frame */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct FrameInfo {
+	x: u32,
+	y: u32,
+	width: u32,
+	height: u32,
+	duration: u32,
+	blend: bool,
+	dispose: bool,
+}
+
+/* Decoder for (animated) WebP files */
+#[derive(Debug)]
+pub struct AnimWebPDecoder {
+	data: Vec<u8>,
+}
+
+impl AnimWebPDecoder {
+	/**
+	 * Create a new AnimWebPDecoder object.
+	 *
+	 * @param  input
+	 * @return AnimWebPDecoder (Result)
+	 */
+	pub fn new<Input: Read>(mut input: Input) -> ImageResult<Self> {
+		let mut data = Vec::new();
+		input.read_to_end(&mut data)?;
+		if data.len() < 12 || &data[..4] != b"RIFF" || &data[8..12] != b"WEBP" {
+			return Err(Self::get_error("Invalid WebP header"));
+		}
+		Ok(Self { data })
+	}
+
+	/**
+	 * Create a decoding error with the given message.
+	 *
+	 * @param  message
+	 * @return ImageError
+	 */
+	fn get_error(message: &str) -> ImageError {
+		ImageError::Decoding(DecodingError::new(
+			ImageFormatHint::Exact(ImageFormat::WebP),
+			message,
+		))
+	}
+
+	/**
+	 * Read a 24-bit little endian value.
+	 *
+	 * @param  data
+	 * @return u32
+	 */
+	fn read_u24(data: &[u8]) -> u32 {
+		u32::from(data[0]) | u32::from(data[1]) << 8 | u32::from(data[2]) << 16
+	}
+
+	/**
+	 * Get the chunks of the RIFF container.
+	 *
+	 * @param  data
+	 * @return Vector of tuple (fourcc, payload)
+	 */
+	fn get_chunks(mut data: &[u8]) -> Vec<(&[u8], &[u8])> {
+		let mut chunks = Vec::new();
+		while data.len() >= 8 {
+			let size =
+				u32::from_le_bytes([data[4], data[5], data[6], data[7]]) as usize;
+			let payload = data.get(8..8 + size).unwrap_or(&data[8..]);
+			chunks.push((&data[..4], payload));
+			data = data.get(8 + size + size % 2..).unwrap_or_default();
+		}
+		chunks
+	}
+
+	/**
+	 * Decode a WebP bitstream into an RGBA image.
+	 *
+	 * @param  data
+	 * @return RgbaImage (Result)
+	 */
+	fn decode_image(data: &[u8]) -> ImageResult<RgbaImage> {
+		let (mut width, mut height): (c_int, c_int) = (0, 0);
+		let buffer = unsafe {
+			WebPDecodeRGBA(data.as_ptr(), data.len(), &mut width, &mut height)
+		};
+		if buffer.is_null() {
+			return Err(Self::get_error("Failed to decode the frame"));
+		}
+		let (width, height) = (width as u32, height as u32);
+		let pixels = unsafe {
+			slice::from_raw_parts(buffer, (width * height * 4) as usize).to_vec()
+		};
+		unsafe { WebPFree(buffer.cast()) };
+		RgbaImage::from_raw(width, height, pixels)
+			.ok_or_else(|| Self::get_error("Invalid frame dimensions"))
+	}
+
+	/**
+	 * Draw the frame on the canvas.
+	 *
+	 * @param canvas
+	 * @param image
+	 * @param info
+	 */
+	fn draw_frame(canvas: &mut RgbaImage, image: &RgbaImage, info: FrameInfo) {
+		for (x, y, pixel) in image.enumerate_pixels() {
+			let (cx, cy) = (info.x + x, info.y + y);
+			if cx >= canvas.width() || cy >= canvas.height() {
+				continue;
+			}
+			let dst = *canvas.get_pixel(cx, cy);
+			let src = *pixel;
+			canvas.put_pixel(
+				cx,
+				cy,
+				if !info.blend || src[3] == 0xFF || dst[3] == 0 {
+					src
+				} else if src[3] == 0 {
+					dst
+				} else {
+					let src_a = u32::from(src[3]);
+					let dst_a = u32::from(dst[3]) * (255 - src_a) / 255;
+					let alpha = src_a + dst_a;
+					let mut blended = [0, 0, 0, alpha as u8];
+					for i in 0..3 {
+						blended[i] = ((u32::from(src[i]) * src_a
+							+ u32::from(dst[i]) * dst_a)
+							/ alpha) as u8;
+					}
+					Rgba(blended)
+				},
+			);
+		}
+	}
+
+	/**
+	 * Decode and compose the frames of the animation.
+	 *
+	 * @return Vector of Frame (Result)
+	 */
+	fn decode_frames(&self) -> ImageResult<Vec<Frame>> {
+		let chunks = Self::get_chunks(&self.data[12..]);
+		let size = match chunks.iter().find(|(fourcc, _)| fourcc == b"VP8X") {
+			Some((_, vp8x)) if vp8x.len() >= 10 => (
+				Self::read_u24(&vp8x[4..]) + 1,
+				Self::read_u24(&vp8x[7..]) + 1,
+			),
+			_ => {
+				let image = Self::decode_image(&self.data)?;
+				return Ok(vec![Frame::new(image)]);
+			}
+		};
+		let mut canvas = RgbaImage::new(size.0, size.1);
+		let mut frames = Vec::new();
+		let mut previous: Option<FrameInfo> = None;
+		for (_, anmf) in chunks
+			.iter()
+			.filter(|(fourcc, payload)| fourcc == b"ANMF" && payload.len() > 16)
+		{
+			let info = FrameInfo {
+				x: Self::read_u24(anmf) * 2,
+				y: Self::read_u24(&anmf[3..]) * 2,
+				width: Self::read_u24(&anmf[6..]) + 1,
+				height: Self::read_u24(&anmf[9..]) + 1,
+				duration: Self::read_u24(&anmf[12..]),
+				blend: anmf[15] & 0x02 == 0,
+				dispose: anmf[15] & 0x01 != 0,
+			};
+			if let Some(previous) = previous.filter(|v| v.dispose) {
+				Self::draw_frame(
+					&mut canvas,
+					&RgbaImage::new(previous.width, previous.height),
+					FrameInfo {
+						blend: false,
+						..previous
+					},
+				);
+			}
+			let image = Self::decode_image(&anmf[16..])?;
+			Self::draw_frame(&mut canvas, &image, info);
+			frames.push(Frame::from_parts(
+				canvas.clone(),
+				0,
+				0,
+				Delay::from_numer_denom_ms(info.duration, 1),
+			));
+			previous = Some(info);
+		}
+		if frames.is_empty() {
+			let image = Self::decode_image(&self.data)?;
+			frames.push(Frame::new(image));
+		}
+		Ok(frames)
+	}
+}
+
+/* Animation decoder implementation for AnimWebPDecoder */
+impl<'a> AnimationDecoder<'a> for AnimWebPDecoder {
+	fn into_frames(self) -> Frames<'a> {
+		Frames::new(match self.decode_frames() {
+			Ok(frames) => Box::new(frames.into_iter().map(Ok)),
+			Err(e) => Box::new(std::iter::once(Err(e))),
+		})
+	}
+}
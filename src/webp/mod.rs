@@ -0,0 +1,301 @@
+pub mod decoder;
+
+use crate::anim::settings::AnimSettings;
+use crate::app::{AppError, AppResult};
+use crate::image::geometry::Geometry;
+use crate::image::settings::WebPSettings;
+use crate::image::Image;
+use crate::util::state::InputState;
+use image::ExtendedColorType;
+use libwebp_sys::*;
+use std::ffi::CStr;
+use std::io::{self, Write};
+use std::mem::MaybeUninit;
+use std::os::raw::c_int;
+use std::ptr;
+use std::slice;
+
+/* Handle of the libwebp animation encoder */
+struct EncoderHandle(*mut WebPAnimEncoder);
+
+impl EncoderHandle {
+	/**
+	 * Get the last error of the encoder.
+	 *
+	 * @return AppError
+	 */
+	fn get_error(&self) -> AppError {
+		let error = unsafe { WebPAnimEncoderGetError(self.0) };
+		AppError::WebPEncoding(if error.is_null() {
+			String::from("Unknown error")
+		} else {
+			unsafe { CStr::from_ptr(error) }
+				.to_string_lossy()
+				.to_string()
+		})
+	}
+}
+
+/* Delete the encoder when the handle goes out of scope */
+impl Drop for EncoderHandle {
+	fn drop(&mut self) {
+		unsafe { WebPAnimEncoderDelete(self.0) }
+	}
+}
+
+/* Animated WebP encoder and settings */
+pub struct AnimWebPEncoder<'a, Output: Write> {
+	fps: u32,
+	geometry: Geometry,
+	output: Output,
+	settings: &'a AnimSettings,
+	webp: &'a WebPSettings,
+}
+
+impl<'a, Output: Write> AnimWebPEncoder<'a, Output> {
+	/**
+	 * Create a new AnimWebPEncoder object.
+	 *
+	 * @param  fps
+	 * @param  geometry
+	 * @param  output
+	 * @param  settings
+	 * @param  webp
+	 * @return AnimWebPEncoder
+	 */
+	pub fn new(
+		fps: u32,
+		geometry: Geometry,
+		output: Output,
+		settings: &'a AnimSettings,
+		webp: &'a WebPSettings,
+	) -> Self {
+		Self {
+			fps,
+			geometry,
+			output,
+			settings,
+			webp,
+		}
+	}
+
+	/**
+	 * Create the libwebp animation encoder.
+	 *
+	 * @return EncoderHandle (Result)
+	 */
+	fn get_encoder(&self) -> AppResult<EncoderHandle> {
+		let mut options = MaybeUninit::<WebPAnimEncoderOptions>::uninit();
+		if unsafe {
+			WebPAnimEncoderOptionsInitInternal(
+				options.as_mut_ptr(),
+				WEBP_MUX_ABI_VERSION,
+			)
+		} == 0
+		{
+			return Err(AppError::WebPEncoding(String::from(
+				"Version mismatch for the encoder options",
+			)));
+		}
+		let mut options = unsafe { options.assume_init() };
+		options.anim_params.loop_count = match self.settings.repeat {
+			n if n >= 0 => n + 1,
+			_ => 0,
+		};
+		if self.settings.keyframe > 0 {
+			options.kmax = self.settings.keyframe.try_into().unwrap_or(c_int::MAX);
+			options.kmin = options.kmax - 1;
+		}
+		let encoder = unsafe {
+			WebPAnimEncoderNewInternal(
+				self.geometry.width.try_into().unwrap_or_default(),
+				self.geometry.height.try_into().unwrap_or_default(),
+				&options,
+				WEBP_MUX_ABI_VERSION,
+			)
+		};
+		if encoder.is_null() {
+			Err(AppError::WebPEncoding(String::from(
+				"Failed to create the encoder",
+			)))
+		} else {
+			Ok(EncoderHandle(encoder))
+		}
+	}
+
+	/**
+	 * Get the encoding configuration from WebP settings.
+	 *
+	 * @return WebPConfig (Result)
+	 */
+	fn get_config(&self) -> AppResult<WebPConfig> {
+		let mut config = MaybeUninit::<WebPConfig>::uninit();
+		let quality = f32::from(self.webp.quality.unwrap_or(100));
+		if unsafe {
+			WebPConfigInitInternal(
+				config.as_mut_ptr(),
+				WebPPreset::WEBP_PRESET_DEFAULT,
+				quality,
+				WEBP_ENCODER_ABI_VERSION,
+			)
+		} == 0
+		{
+			return Err(AppError::WebPEncoding(String::from(
+				"Version mismatch for the encoder config",
+			)));
+		}
+		let mut config = unsafe { config.assume_init() };
+		config.lossless = c_int::from(self.webp.quality.is_none());
+		if unsafe { WebPValidateConfig(&config) } == 0 {
+			return Err(AppError::WebPEncoding(String::from(
+				"Invalid encoder config",
+			)));
+		}
+		Ok(config)
+	}
+
+	/**
+	 * Add an image to the animation at the given timestamp.
+	 *
+	 * @param  encoder
+	 * @param  config
+	 * @param  image
+	 * @param  timestamp
+	 * @return Result
+	 */
+	fn add_frame(
+		&self,
+		encoder: &EncoderHandle,
+		config: &WebPConfig,
+		image: &Image,
+		timestamp: c_int,
+	) -> AppResult<()> {
+		let mut picture = MaybeUninit::<WebPPicture>::uninit();
+		if unsafe {
+			WebPPictureInitInternal(picture.as_mut_ptr(), WEBP_ENCODER_ABI_VERSION)
+		} == 0
+		{
+			return Err(AppError::WebPEncoding(String::from(
+				"Version mismatch for the picture",
+			)));
+		}
+		let mut picture = unsafe { picture.assume_init() };
+		picture.use_argb = 1;
+		picture.width = image.geometry.width.try_into().unwrap_or_default();
+		picture.height = image.geometry.height.try_into().unwrap_or_default();
+		let data = image.get_data(ExtendedColorType::Rgba8);
+		let added = unsafe {
+			WebPPictureImportRGBA(&mut picture, data.as_ptr(), picture.width * 4)
+				!= 0 && WebPAnimEncoderAdd(encoder.0, &mut picture, timestamp, config)
+				!= 0
+		};
+		unsafe { WebPPictureFree(&mut picture) };
+		if added {
+			Ok(())
+		} else {
+			Err(encoder.get_error())
+		}
+	}
+
+	/**
+	 * Encode images as frame and write to the WebP file.
+	 *
+	 * @param  images
+	 * @param  input_state (Option)
+	 * @return Result
+	 */
+	pub fn save(
+		mut self,
+		images: Vec<Image>,
+		input_state: Option<&'static InputState>,
+	) -> AppResult<()> {
+		let encoder = self.get_encoder()?;
+		let config = self.get_config()?;
+		let delay = 1e3 / self.fps.max(1) as f64;
+		for (i, image) in images.iter().enumerate() {
+			let percentage = ((i + 1) as f64 / images.len() as f64) * 100.;
+			info!("Saving... ({:.1}%)\r", percentage);
+			debug!(
+				"Encoding... ({:.1}%) [{}/{}]\r",
+				percentage,
+				i + 1,
+				images.len()
+			);
+			io::stdout().flush()?;
+			if let Some(state) = input_state {
+				if state.check_cancel_keys() {
+					info!("\n");
+					warn!("User interrupt detected.");
+					panic!("Failed to write the frames")
+				}
+			}
+			self.add_frame(&encoder, &config, image, (i as f64 * delay) as c_int)?;
+		}
+		info!("\n");
+		let mut data = WebPData::default();
+		if unsafe {
+			WebPAnimEncoderAdd(
+				encoder.0,
+				ptr::null_mut(),
+				(images.len() as f64 * delay) as c_int,
+				ptr::null(),
+			) == 0 || WebPAnimEncoderAssemble(encoder.0, &mut data) == 0
+		} {
+			return Err(encoder.get_error());
+		}
+		let result = self
+			.output
+			.write_all(unsafe { slice::from_raw_parts(data.bytes, data.size) });
+		unsafe { WebPDataClear(&mut data) };
+		Ok(result?)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::webp::decoder::AnimWebPDecoder;
+	use image::{AnimationDecoder, Rgba};
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_anim_webp_encoder() -> AppResult<()> {
+		let geometry = Geometry::new(0, 0, 2, 2);
+		let pixels = [
+			Rgba::from([255, 0, 0, 255]),
+			Rgba::from([0, 255, 0, 255]),
+			Rgba::from([0, 0, 255, 128]),
+			Rgba::from([0, 0, 0, 0]),
+		];
+		let images = (0..3)
+			.map(|i| {
+				let mut data = pixels.to_vec();
+				data.rotate_left(i);
+				Image::new(data, true, geometry)
+			})
+			.collect::<Vec<Image>>();
+		let settings = AnimSettings::default();
+		for quality in [None, Some(80)] {
+			let webp = WebPSettings::new(quality);
+			let mut output = Vec::new();
+			AnimWebPEncoder::new(10, geometry, &mut output, &settings, &webp)
+				.save(images.clone(), None)?;
+			assert_eq!(b"RIFF", &output[..4]);
+			assert_eq!(b"WEBP", &output[8..12]);
+			let frames = AnimWebPDecoder::new(output.as_slice())?
+				.into_frames()
+				.collect_frames()?;
+			assert_eq!(images.len(), frames.len());
+			for (image, frame) in images.iter().zip(frames.iter()) {
+				assert_eq!((100, 1), frame.delay().numer_denom_ms());
+				assert_eq!((2, 2), frame.buffer().dimensions());
+				if quality.is_none() {
+					assert_eq!(
+						image.get_data(ExtendedColorType::Rgba8),
+						frame.buffer().as_raw().to_vec()
+					);
+				}
+			}
+		}
+		Ok(())
+	}
+}
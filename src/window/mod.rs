@@ -16,6 +16,7 @@ pub trait Access<'a, Window: Capture + Send + Sync + Copy + Debug + 'static> {
 /* Window methods for capturing an image */
 pub trait Capture {
 	fn get_image(&self) -> Option<Image>;
+	fn get_name(&self) -> Option<String>;
 	fn show_countdown(&self);
 	fn release(&self);
 }
@@ -43,6 +43,15 @@ impl Capture for TestWindow {
 		))
 	}
 
+	/**
+	 * Get the name of the test window.
+	 *
+	 * @return String (Option)
+	 */
+	fn get_name(&self) -> Option<String> {
+		Some(String::from("Test Window"))
+	}
+
 	/* Do not show countdown for testing window. */
 	fn show_countdown(&self) {}
 
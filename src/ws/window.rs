@@ -28,6 +28,15 @@ impl Capture for Window {
 		unimplemented!()
 	}
 
+	/**
+	 * Get the name of the window.
+	 *
+	 * @return String (Option)
+	 */
+	fn get_name(&self) -> Option<String> {
+		unimplemented!()
+	}
+
 	/* Show countdown on the window. */
 	fn show_countdown(&self) {
 		unimplemented!()
@@ -197,31 +197,6 @@ impl Window {
 		crtc_info
 	}
 
-	/**
-	 * Get the name of the window.
-	 *
-	 * @return String (Option)
-	 */
-	pub fn get_name(&self) -> Option<String> {
-		unsafe {
-			let mut window_name = MaybeUninit::<*mut c_char>::uninit();
-			if xlib::XFetchName(
-				self.display.inner,
-				self.xid,
-				window_name.as_mut_ptr(),
-			) != 0
-			{
-				Some(
-					CString::from_raw(*window_name.as_ptr())
-						.into_string()
-						.unwrap_or_default(),
-				)
-			} else {
-				None
-			}
-		}
-	}
-
 	/* Draw a rectangle inside the window. */
 	pub fn draw_borders(&self) {
 		if self.display.settings.border.is_some() {
@@ -419,6 +394,31 @@ impl Capture for Window {
 		}
 	}
 
+	/**
+	 * Get the name of the window.
+	 *
+	 * @return String (Option)
+	 */
+	fn get_name(&self) -> Option<String> {
+		unsafe {
+			let mut window_name = MaybeUninit::<*mut c_char>::uninit();
+			if xlib::XFetchName(
+				self.display.inner,
+				self.xid,
+				window_name.as_mut_ptr(),
+			) != 0
+			{
+				Some(
+					CString::from_raw(*window_name.as_ptr())
+						.into_string()
+						.unwrap_or_default(),
+				)
+			} else {
+				None
+			}
+		}
+	}
+
 	/* Show a countdown on the corner of window. */
 	fn show_countdown(&self) {
 		if self.display.settings.time.countdown != 0 {